@@ -1,8 +1,8 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env,
-    Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, vec, xdr::ToXdr, Address,
+    Bytes, BytesN, Env, IntoVal, Map, String, Symbol, SymbolStr, TryFromVal, Val, Vec,
 };
 
 // ============================================================================
@@ -29,31 +29,62 @@ pub enum AntiBotError {
     TimeWindowInvalid = 14,
     ChallengeExpired = 15,
     InvalidProof = 16,
+    ReviewWindowOpen = 17,
+    BatchTooLarge = 18,
+    InvalidStatusTransition = 19,
 }
 
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
-// #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
+// Explicit discriminants keep this a u32-encoded enum, so profiles stored
+// while `status` was a raw code still decode.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
 pub enum PlayerStatus {
-    Unverified,
-    Verified,
-    Suspicious,
-    Flagged,
-    Penalized,
-    AppealPending,
-    Whitelisted,
-    Blacklisted,
+    Unverified = 0,
+    Verified = 1,
+    Suspicious = 2,
+    Flagged = 3,
+    Penalized = 4,
+    AppealPending = 5,
+    Whitelisted = 6,
+    Blacklisted = 7,
+}
+
+impl PlayerStatus {
+    fn from_code(code: u32) -> PlayerStatus {
+        match code {
+            1 => PlayerStatus::Verified,
+            2 => PlayerStatus::Suspicious,
+            3 => PlayerStatus::Flagged,
+            4 => PlayerStatus::Penalized,
+            5 => PlayerStatus::AppealPending,
+            6 => PlayerStatus::Whitelisted,
+            7 => PlayerStatus::Blacklisted,
+            _ => PlayerStatus::Unverified,
+        }
+    }
+
+    /// Checks a direct moderator status change. Only clearing a flag or a
+    /// suspicion is allowed here; penalized and blacklisted players must go
+    /// through `remove_penalty`, an appeal, or `remove_blacklist` instead.
+    fn transition(self, next: PlayerStatus) -> Result<PlayerStatus, AntiBotError> {
+        match (self, next) {
+            (PlayerStatus::Suspicious, PlayerStatus::Verified)
+            | (PlayerStatus::Flagged, PlayerStatus::Verified) => Ok(next),
+            _ => Err(AntiBotError::InvalidStatusTransition),
+        }
+    }
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PlayerProfile {
     pub address: Address,
-    pub status: u32,
+    pub status: PlayerStatus,
     pub trust_score: u32,
     pub total_attempts: u32,
     pub successful_attempts: u32,
@@ -65,6 +96,27 @@ pub struct PlayerProfile {
     pub reputation_tier: u32,
     pub penalty_count: u32,
     pub appeal_count: u32,
+    pub solve_time_m2: u128, // running sum of squared deviations from avg_solve_time_ms
+}
+
+// Profile layout from before solve-time variance tracking. `status` is read as
+// a raw code so records from before the status enum migrate as well.
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LegacyPlayerProfile {
+    address: Address,
+    status: u32,
+    trust_score: u32,
+    total_attempts: u32,
+    successful_attempts: u32,
+    failed_attempts: u32,
+    avg_solve_time_ms: u64,
+    first_seen: u64,
+    last_activity: u64,
+    consecutive_fast_solves: u32,
+    reputation_tier: u32,
+    penalty_count: u32,
+    appeal_count: u32,
 }
 
 #[contracttype]
@@ -83,6 +135,16 @@ pub struct RateLimitWindow {
     pub window_start: u64,
     pub attempt_count: u32,
     pub last_attempt: u64,
+    pub attempts: Vec<u64>, // timestamps of attempts still inside the sliding window
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PuzzleSolveWindow {
+    // Parallel lists, one entry per distinct player, oldest first
+    pub players: Vec<Address>,
+    pub solve_times: Vec<u64>,
+    pub timestamps: Vec<u64>,
 }
 
 #[contracttype]
@@ -92,7 +154,8 @@ pub struct BehavioralPattern {
     pub pattern_variance: u32,   // 0-1000, lower = more bot-like
     pub consistency_score: u32,  // 0-1000, higher = more bot-like
     pub time_distribution: Vec<u64>, // timestamps of last 10 interactions
-    pub gas_pattern_variance: u32,
+    pub gas_pattern_variance: u32, // 0-1000, lower = more bot-like
+    pub gas_distribution: Vec<u64>, // gas used by the last 10 interactions
 }
 
 #[contracttype]
@@ -111,6 +174,7 @@ pub struct CaptchaChallenge {
 pub struct CaptchaProof {
     pub challenge_id: u32,
     pub nonce: u64,
+    pub verification_nonce: u64, // must equal the player's current nonce
     pub iterations: u32,
     pub proof_hash: BytesN<32>,
 }
@@ -163,6 +227,46 @@ pub enum PenaltyType {
     VerificationRequired,
 }
 
+// Prior penalty counts at which automatic escalation moves to each ban type;
+// below `temporary_ban_after` a player only receives a Warning
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PenaltyLadder {
+    pub temporary_ban_after: u32,
+    pub extended_ban_after: u32,
+    pub permanent_ban_after: u32,
+}
+
+// Points each risk factor adds to `analyze_player`'s bot probability
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RiskWeights {
+    pub fast_solves: u32,
+    pub high_failure_rate: u32,
+    pub consistent_timing: u32,
+    pub gas_pattern: u32,
+    pub low_variance: u32,
+    pub low_trust: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SwarmConfig {
+    pub window_seconds: u64,       // how far back solves count toward a swarm
+    pub min_players: u32,          // distinct players with near-identical times that trigger it
+    pub solve_time_spread_ms: u64, // max distance between solve times treated as identical
+    pub captcha_boost: u32,        // extra captcha difficulty while a swarm is active
+    pub boost_seconds: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TrustFactorCaps {
+    pub success_rate: u32,        // points for a perfect success rate
+    pub account_age: u32,         // points for an account at least cap/10 days old
+    pub consistency_penalty: u32, // points lost for bot-like timing consistency
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct Appeal {
@@ -170,12 +274,31 @@ pub struct Appeal {
     pub player: Address,
     pub penalty_id: u32,
     pub reason: Symbol,
-    pub evidence: Symbol,
+    pub evidence_uri: String,          // where the off-chain justification lives
+    pub evidence_hash: Option<Bytes>,  // 32-byte sha256 of the off-chain proof, if committed
     pub submitted_at: u64,
     pub status: AppealStatus,
     pub reviewed_by: Option<Address>,
     pub reviewed_at: Option<u64>,
     pub decision_reason: Option<Symbol>,
+    pub stake_token: Option<Address>,
+    pub stake_amount: i128, // escrowed until the appeal is decided
+}
+
+// Appeal layout with a single Symbol of evidence, used only to read old records
+#[contracttype]
+#[derive(Clone, Debug)]
+struct LegacyAppeal {
+    appeal_id: u32,
+    player: Address,
+    penalty_id: u32,
+    reason: Symbol,
+    evidence: Symbol,
+    submitted_at: u64,
+    status: AppealStatus,
+    reviewed_by: Option<Address>,
+    reviewed_at: Option<u64>,
+    decision_reason: Option<Symbol>,
 }
 
 #[contracttype]
@@ -209,13 +332,38 @@ pub struct Config {
     pub min_solve_time_threshold_ms: u64,
     pub suspicious_solve_time_ms: u64,
     pub captcha_difficulty: u32,
+    pub captcha_tier_adjustments: Vec<i32>, // added to captcha_difficulty, indexed by reputation tier
     pub captcha_validity_seconds: u64,
+    pub challenge_cooldown_seconds: u64, // minimum gap between challenges for one player
+    pub max_challenges_per_window: u32,
+    pub challenge_window_seconds: u64,
     pub pattern_analysis_window: u32,
     pub max_consecutive_fast_solves: u32,
+    pub fast_streak_reset_seconds: u64, // idle time after which the fast-solve streak is dropped
+    pub outlier_min_samples: u32, // solves of history needed before personal outliers are flagged
+    pub outlier_std_devs: u32,    // how far below the player's mean a solve must fall
+    pub suspicious_lookback_days: u64, // only activity this recent counts toward flagging
     pub appeal_period_days: u64,
+    pub appeal_review_days: u64, // pending appeals older than this can be expired by anyone
+    pub appeal_stake_token: Option<Address>,
+    pub appeal_stake_amount: i128, // 0 disables appeal stakes
+    pub fee_recipient: Address,    // receives stakes forfeited on rejected appeals
+    pub resolve_activity_on_appeal: bool, // approved appeals clear the suspicious records behind the penalty
     pub default_penalty_hours: u64,
+    pub temporary_ban_hours: u64,
+    pub extended_ban_hours: u64,
+    pub verification_required_hours: u64,
     pub trust_score_threshold: u32,
     pub reputation_contract: Option<Address>,
+    pub reputation_weight_pct: u32, // share of the external score in the blended trust score
+    pub trust_decay_per_day: u32, // points lost per idle day, down to TRUST_SCORE_FLOOR
+    pub penalty_ladder: PenaltyLadder,
+    pub auto_penalty_threshold: u32, // bot probability above which verify_player acts
+    pub auto_penalty_type: PenaltyType,
+    pub auto_penalty_enabled: bool, // false only recommends the penalty for moderator review
+    pub risk_weights: RiskWeights,
+    pub trust_factor_caps: TrustFactorCaps,
+    pub swarm: SwarmConfig,
 }
 
 // ============================================================================
@@ -233,6 +381,7 @@ pub enum DataKey {
     BehavioralPattern(Address),
     CaptchaChallenge(u32),
     ChallengeCounter,
+    ChallengeRequests(Address), // RateLimitWindow over the player's challenge generations
     SuspiciousActivity(Address, u32), // (player, index)
     SuspiciousCount(Address),
     Penalty(u32),
@@ -242,9 +391,48 @@ pub enum DataKey {
     Appeal(u32),
     AppealCounter,
     TimeWindow(u32), // puzzle_id
-    Whitelisted(Address),
+    Whitelisted(Address), // expiry timestamp, 0 = never expires
     Blacklisted(Address),
     VerificationNonce(Address),
+    TrustDecayedAt(Address), // last idle-day boundary decay was applied up to
+    ResolvedActivity(Address, u32), // (player, index) suspicious records cleared by appeal
+    ResolvedCount(Address),
+    FlaggedPlayers,     // Vec<Address> of players currently in Flagged status
+    BlacklistedPlayers, // Vec<Address> of players currently blacklisted
+    PuzzleSolves(u32),  // puzzle_id -> PuzzleSolveWindow
+    CaptchaBoostUntil,  // captcha difficulty is raised until this timestamp
+    Honeypot(u32),      // puzzle_id of a trap puzzle only bots attempt
+    PendingAppeals,     // Vec<u32> of appeal ids awaiting review, oldest first
+}
+
+// Neutral score that inactivity decay never drops a player below
+const TRUST_SCORE_FLOOR: u32 = 500;
+
+// Upper bound on players handled by one batch moderation call
+const MAX_BATCH_SIZE: u32 = 50;
+
+// Upper bound on distinct recent solvers tracked per puzzle
+const MAX_SWARM_SAMPLE: u32 = 50;
+
+// Copies a Symbol's characters into a String
+fn symbol_to_string(env: &Env, symbol: &Symbol) -> Option<String> {
+    let chars = SymbolStr::try_from_val(env, &symbol.to_symbol_val()).ok()?;
+    let bytes: &[u8] = chars.as_ref();
+    Some(String::from_bytes(env, bytes))
+}
+
+// Integer square root (floor) by Newton's method
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
 }
 
 // ============================================================================
@@ -275,13 +463,59 @@ impl AntiBot {
             min_solve_time_threshold_ms: 5000, // 5 seconds minimum
             suspicious_solve_time_ms: 2000,   // 2 seconds is suspicious
             captcha_difficulty: 2,
+            captcha_tier_adjustments: Vec::from_array(&env, [2, 1, 0, 0, -1, -1]),
             captcha_validity_seconds: 300,    // 5 minutes
+            challenge_cooldown_seconds: 5,
+            max_challenges_per_window: 20,
+            challenge_window_seconds: 3600,
             pattern_analysis_window: 10,
             max_consecutive_fast_solves: 3,
+            fast_streak_reset_seconds: 3600,
+            outlier_min_samples: 5,
+            outlier_std_devs: 3,
+            suspicious_lookback_days: 30,
             appeal_period_days: 7,
+            appeal_review_days: 14,
+            appeal_stake_token: None,
+            appeal_stake_amount: 0,
+            fee_recipient: admin.clone(),
+            resolve_activity_on_appeal: true,
             default_penalty_hours: 24,
+            temporary_ban_hours: 24,
+            extended_ban_hours: 24 * 7,
+            verification_required_hours: 24,
             trust_score_threshold: 500,
             reputation_contract: None,
+            reputation_weight_pct: 30,
+            trust_decay_per_day: 10,
+            penalty_ladder: PenaltyLadder {
+                temporary_ban_after: 1,
+                extended_ban_after: 2,
+                permanent_ban_after: 3,
+            },
+            auto_penalty_threshold: 85,
+            auto_penalty_type: PenaltyType::TemporaryBan,
+            auto_penalty_enabled: true,
+            risk_weights: RiskWeights {
+                fast_solves: 30,
+                high_failure_rate: 20,
+                consistent_timing: 25,
+                gas_pattern: 15,
+                low_variance: 20,
+                low_trust: 20,
+            },
+            trust_factor_caps: TrustFactorCaps {
+                success_rate: 300,
+                account_age: 100,
+                consistency_penalty: 100,
+            },
+            swarm: SwarmConfig {
+                window_seconds: 60,
+                min_players: 10,
+                solve_time_spread_ms: 250,
+                captcha_boost: 1,
+                boost_seconds: 3600,
+            },
         };
 
         env.storage().instance().set(&DataKey::Config, &config);
@@ -309,16 +543,34 @@ impl AntiBot {
         Ok(config.admin)
     }
 
-    fn require_verifier(env: &Env) -> Result<(), AntiBotError> {
+    fn require_admin_address(env: &Env, admin: &Address) -> Result<(), AntiBotError> {
+        admin.require_auth();
+
+        let config: Config = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(AntiBotError::NotInitialized)?;
+
+        if *admin != config.admin {
+            return Err(AntiBotError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn require_verifier(env: &Env, verifier: &Address) -> Result<(), AntiBotError> {
         let config: Config = env
             .storage()
             .instance()
             .get(&DataKey::Config)
             .ok_or(AntiBotError::NotInitialized)?;
 
-        let caller = env.current_contract_address(); // Use invoker in real scenario
-        // For now, require admin or check if caller is in verifiers list
-        config.admin.require_auth();
+        verifier.require_auth();
+
+        // The claimed caller must be the admin or a registered verifier
+        if *verifier != config.admin && !config.verifiers.contains(verifier) {
+            return Err(AntiBotError::Unauthorized);
+        }
         Ok(())
     }
 
@@ -327,18 +579,15 @@ impl AntiBot {
     // ========================================================================
 
     fn get_or_create_profile(env: &Env, player: &Address) -> PlayerProfile {
-        if let Some(profile) = env
-            .storage()
-            .persistent()
-            .get(&DataKey::PlayerProfile(player.clone()))
-        {
+        if let Some(mut profile) = Self::load_profile(env, player) {
+            Self::apply_trust_decay(env, &mut profile);
             return profile;
         }
 
         let now = env.ledger().timestamp();
         let profile = PlayerProfile {
             address: player.clone(),
-            status: 0,
+            status: PlayerStatus::Unverified,
             trust_score: 500, // Start at neutral
             total_attempts: 0,
             successful_attempts: 0,
@@ -350,6 +599,7 @@ impl AntiBot {
             reputation_tier: 0,
             penalty_count: 0,
             appeal_count: 0,
+            solve_time_m2: 0,
         };
 
         env.storage()
@@ -360,17 +610,149 @@ impl AntiBot {
     }
 
     pub fn get_profile(env: Env, player: Address) -> Option<PlayerProfile> {
+        Self::load_profile(&env, &player)
+    }
+
+    /// Reads a stored profile, falling back to the legacy u32-status layout
+    /// for records whose code does not map onto a `PlayerStatus` variant.
+    fn load_profile(env: &Env, player: &Address) -> Option<PlayerProfile> {
+        let raw: Val = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PlayerProfile(player.clone()))?;
+
+        // A missing field is a host error rather than a recoverable
+        // conversion error, so check for the newest field first
+        let fields = Map::<Symbol, Val>::try_from_val(env, &raw).ok()?;
+        if fields.contains_key(Symbol::new(env, "solve_time_m2")) {
+            if let Ok(profile) = PlayerProfile::try_from_val(env, &raw) {
+                return Some(profile);
+            }
+        }
+
+        let legacy = LegacyPlayerProfile::try_from_val(env, &raw).ok()?;
+        Some(PlayerProfile {
+            address: legacy.address,
+            status: PlayerStatus::from_code(legacy.status),
+            trust_score: legacy.trust_score,
+            total_attempts: legacy.total_attempts,
+            successful_attempts: legacy.successful_attempts,
+            failed_attempts: legacy.failed_attempts,
+            avg_solve_time_ms: legacy.avg_solve_time_ms,
+            first_seen: legacy.first_seen,
+            last_activity: legacy.last_activity,
+            consecutive_fast_solves: legacy.consecutive_fast_solves,
+            reputation_tier: legacy.reputation_tier,
+            penalty_count: legacy.penalty_count,
+            appeal_count: legacy.appeal_count,
+            solve_time_m2: 0,
+        })
+    }
+
+    /// Lowers the trust score of an idle player by `trust_decay_per_day` for
+    /// every full day since their last activity, never below the neutral floor.
+    /// Progress is checkpointed so repeated reads do not decay twice.
+    fn apply_trust_decay(env: &Env, profile: &mut PlayerProfile) {
+        let config: Config = match env.storage().instance().get(&DataKey::Config) {
+            Some(c) => c,
+            None => return,
+        };
+
+        let (new_score, checkpoint) = match Self::pending_trust_decay(env, &config, profile) {
+            Some(d) => d,
+            None => return,
+        };
+
+        let old_score = profile.trust_score;
+        profile.trust_score = new_score;
+        profile.reputation_tier = Self::tier_for_score(profile.trust_score);
+
         env.storage()
             .persistent()
-            .get(&DataKey::PlayerProfile(player))
+            .set(&DataKey::TrustDecayedAt(profile.address.clone()), &checkpoint);
+        Self::update_profile(env, &profile.address, profile);
+
+        env.events().publish(
+            (symbol_short!("decay"), profile.address.clone()),
+            (old_score, profile.trust_score),
+        );
+    }
+
+    /// Returns the decayed score and the idle-day boundary it covers up to,
+    /// or `None` if no whole idle day has passed. Does not touch storage.
+    fn pending_trust_decay(env: &Env, config: &Config, profile: &PlayerProfile) -> Option<(u32, u64)> {
+        // Whitelisted and blacklisted scores are pinned by the admin
+        if config.trust_decay_per_day == 0
+            || profile.trust_score <= TRUST_SCORE_FLOOR
+            || profile.status == PlayerStatus::Whitelisted
+            || profile.status == PlayerStatus::Blacklisted
+        {
+            return None;
+        }
+
+        let decayed_at: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TrustDecayedAt(profile.address.clone()))
+            .unwrap_or(0);
+        let since = profile.last_activity.max(decayed_at);
+        let now = env.ledger().timestamp();
+        let idle_days = now.saturating_sub(since) / 86400;
+        if idle_days == 0 {
+            return None;
+        }
+
+        let decay = (idle_days * config.trust_decay_per_day as u64).min(u32::MAX as u64) as u32;
+        let score = profile.trust_score.saturating_sub(decay).max(TRUST_SCORE_FLOOR);
+        Some((score, since + idle_days * 86400))
+    }
+
+    /// Applies any pending inactivity decay and returns the resulting score.
+    pub fn decay_trust_score(env: Env, player: Address) -> u32 {
+        Self::get_or_create_profile(&env, &player).trust_score
     }
 
     fn update_profile(env: &Env, player: &Address, profile: &PlayerProfile) {
+        // Every status change goes through here, so keep the flagged index in sync
+        let was_flagged = Self::load_profile(env, player)
+            .map(|p| p.status == PlayerStatus::Flagged)
+            .unwrap_or(false);
+        let is_flagged = profile.status == PlayerStatus::Flagged;
+        if is_flagged && !was_flagged {
+            Self::add_to_index(env, &DataKey::FlaggedPlayers, player);
+        } else if was_flagged && !is_flagged {
+            Self::remove_from_index(env, &DataKey::FlaggedPlayers, player);
+        }
+
         env.storage()
             .persistent()
             .set(&DataKey::PlayerProfile(player.clone()), profile);
     }
 
+    fn add_to_index(env: &Env, key: &DataKey, player: &Address) {
+        let mut players: Vec<Address> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        if !players.contains(player) {
+            players.push_back(player.clone());
+            env.storage().persistent().set(key, &players);
+        }
+    }
+
+    fn remove_from_index(env: &Env, key: &DataKey, player: &Address) {
+        let mut players: Vec<Address> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        if let Some(idx) = players.first_index_of(player) {
+            players.remove(idx);
+            env.storage().persistent().set(key, &players);
+        }
+    }
+
+    fn page_index(env: &Env, key: &DataKey, offset: u32, limit: u32) -> Vec<Address> {
+        let players: Vec<Address> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        let count = players.len();
+        let start = offset.min(count);
+        let end = offset.saturating_add(limit).min(count);
+        players.slice(start..end)
+    }
+
     // ========================================================================
     // CAPTCHA-LIKE VERIFICATION
     // ========================================================================
@@ -384,6 +766,24 @@ impl AntiBot {
             .get(&DataKey::Config)
             .ok_or(AntiBotError::NotInitialized)?;
 
+        let now = env.ledger().timestamp();
+
+        // Throttle generation so players can't farm for an easy target prefix
+        let requests_key = DataKey::ChallengeRequests(player.clone());
+        let mut requests = Self::load_rate_limit_window(&env, &requests_key, config.challenge_window_seconds);
+        if requests.attempt_count >= config.max_challenges_per_window {
+            return Err(AntiBotError::RateLimitExceeded);
+        }
+        if let Some(last) = requests.attempts.last() {
+            if now.saturating_sub(last) < config.challenge_cooldown_seconds {
+                return Err(AntiBotError::RateLimitExceeded);
+            }
+        }
+        requests.attempts.push_back(now);
+        requests.attempt_count += 1;
+        requests.last_attempt = now;
+        env.storage().persistent().set(&requests_key, &requests);
+
         let counter: u32 = env
             .storage()
             .instance()
@@ -393,8 +793,7 @@ impl AntiBot {
         let new_counter = counter + 1;
         env.storage().instance().set(&DataKey::ChallengeCounter, &new_counter);
 
-        let now = env.ledger().timestamp();
-        let difficulty = config.captcha_difficulty;
+        let difficulty = Self::captcha_difficulty_for(&env, &config, &player);
         
         // Generate pseudo-random challenge based on timestamp and player address
         let seed_bytes = Bytes::from_array(&env, &[
@@ -441,6 +840,32 @@ impl AntiBot {
         Ok(challenge)
     }
 
+    /// Scales the base captcha difficulty by the player's reputation tier so
+    /// trusted players solve easier challenges. Whitelisted players always get
+    /// the easiest level.
+    fn captcha_difficulty_for(env: &Env, config: &Config, player: &Address) -> u32 {
+        if Self::is_whitelisted(env.clone(), player.clone()) {
+            return 1;
+        }
+
+        let profile = Self::get_or_create_profile(env, player);
+        let mut adjustment = config
+            .captcha_tier_adjustments
+            .get(profile.reputation_tier)
+            .unwrap_or(0);
+
+        let boost_until: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CaptchaBoostUntil)
+            .unwrap_or(0);
+        if env.ledger().timestamp() < boost_until {
+            adjustment += config.swarm.captcha_boost as i32;
+        }
+
+        (config.captcha_difficulty as i32 + adjustment).clamp(1, 5) as u32
+    }
+
     pub fn verify_captcha_proof(
         env: Env,
         player: Address,
@@ -463,20 +888,32 @@ impl AntiBot {
             return Err(AntiBotError::VerificationFailed);
         }
 
-        // Verify proof of work
+        // Each accepted proof consumes the player's nonce, so a stale proof
+        // cannot be submitted a second time
+        let nonce_key = DataKey::VerificationNonce(player.clone());
+        let expected_nonce: u64 = env.storage().persistent().get(&nonce_key).unwrap_or(0);
+        if proof.verification_nonce != expected_nonce {
+            return Err(AntiBotError::InvalidProof);
+        }
+
+        // Verify proof of work. The hashed data binds the challenge, its target
+        // prefix, the solving player and their nonce, so a proof cannot be
+        // replayed by another account, against another challenge or twice.
         let mut data = Bytes::new(&env);
-        // Use the player's raw bytes representation
-        // In Soroban, we can convert Address to bytes using to_string or by using as a key
-        // For simplicity, we'll use a hash of the address combined with the challenge_id
         let challenge_bytes = Bytes::from_array(&env, &[
-            ((proof.challenge_id >> 0) & 0xFF) as u8,
+            (proof.challenge_id & 0xFF) as u8,
             ((proof.challenge_id >> 8) & 0xFF) as u8,
             ((proof.challenge_id >> 16) & 0xFF) as u8,
             ((proof.challenge_id >> 24) & 0xFF) as u8,
         ]);
         data.append(&challenge_bytes);
+        data.append(&Bytes::from(challenge.target_prefix.clone()));
+        data.append(&player.clone().to_xdr(&env));
         
-        // Append nonce bytes
+        // Append verification nonce and PoW nonce bytes
+        for i in 0..8 {
+            data.push_back(((proof.verification_nonce >> (i * 8)) & 0xFF) as u8);
+        }
         for i in 0..8 {
             data.push_back(((proof.nonce >> (i * 8)) & 0xFF) as u8);
         }
@@ -488,7 +925,8 @@ impl AntiBot {
             return Err(AntiBotError::VerificationFailed);
         }
 
-        // Check prefix match (simplified: first 2 bytes should be below threshold)
+        // Check prefix match: the leading 2 bytes of the hash must be within
+        // the difficulty threshold of the challenge's target prefix
         let threshold: u16 = match challenge.difficulty {
             1 => 0x4000, // ~25% chance
             2 => 0x1000, // ~6% chance
@@ -498,14 +936,17 @@ impl AntiBot {
             _ => 0x1000,
         };
 
-        let prefix_value: u16 = (computed_hash.get(0).unwrap_or(0) as u16) << 8
-            | (computed_hash.get(1).unwrap_or(0) as u16);
+        let target = &challenge.target_prefix;
+        let prefix_value: u16 = ((computed_hash.get(0).unwrap_or(0) ^ target.get(0).unwrap_or(0)) as u16) << 8
+            | ((computed_hash.get(1).unwrap_or(0) ^ target.get(1).unwrap_or(0)) as u16);
 
         let verified = prefix_value < threshold;
 
         if verified {
+            env.storage().persistent().set(&nonce_key, &(expected_nonce + 1));
+
             let mut profile = Self::get_or_create_profile(&env, &player);
-            profile.status = 1;
+            profile.status = PlayerStatus::Verified;
             Self::update_profile(&env, &player, &profile);
 
             env.events().publish(
@@ -530,6 +971,13 @@ impl AntiBot {
         Ok(verified)
     }
 
+    pub fn get_verification_nonce(env: Env, player: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VerificationNonce(player))
+            .unwrap_or(0)
+    }
+
     // ========================================================================
     // RATE LIMITING
     // ========================================================================
@@ -543,21 +991,11 @@ impl AntiBot {
 
         let now = env.ledger().timestamp();
         
-        let mut window: RateLimitWindow = env
-            .storage()
-            .persistent()
-            .get(&DataKey::RateLimit(player.clone()))
-            .unwrap_or(RateLimitWindow {
-                window_start: now,
-                attempt_count: 0,
-                last_attempt: 0,
-            });
-
-        // Check if window has expired
-        if now - window.window_start > config.rate_limit_window_seconds {
-            window.window_start = now;
-            window.attempt_count = 0;
-        }
+        let mut window = Self::load_rate_limit_window(
+            &env,
+            &DataKey::RateLimit(player.clone()),
+            config.rate_limit_window_seconds,
+        );
 
         // Check rate limit
         if window.attempt_count >= config.max_attempts_per_window {
@@ -571,6 +1009,10 @@ impl AntiBot {
             return Err(AntiBotError::RateLimitExceeded);
         }
 
+        if window.attempts.is_empty() {
+            window.window_start = now;
+        }
+        window.attempts.push_back(now);
         window.attempt_count += 1;
         window.last_attempt = now;
 
@@ -582,15 +1024,41 @@ impl AntiBot {
     }
 
     pub fn get_rate_limit_status(env: Env, player: Address) -> RateLimitWindow {
+        let window_seconds = env
+            .storage()
+            .instance()
+            .get::<DataKey, Config>(&DataKey::Config)
+            .map(|c| c.rate_limit_window_seconds)
+            .unwrap_or(0);
+        Self::load_rate_limit_window(&env, &DataKey::RateLimit(player), window_seconds)
+    }
+
+    /// Loads a rate-limit window stored under `key` with attempts older than
+    /// the sliding window pruned, so `attempt_count` only covers recent attempts.
+    fn load_rate_limit_window(env: &Env, key: &DataKey, window_seconds: u64) -> RateLimitWindow {
         let now = env.ledger().timestamp();
-        env.storage()
+        let mut window: RateLimitWindow = env
+            .storage()
             .persistent()
-            .get(&DataKey::RateLimit(player))
+            .get(key)
             .unwrap_or(RateLimitWindow {
                 window_start: now,
                 attempt_count: 0,
                 last_attempt: 0,
-            })
+                attempts: Vec::new(env),
+            });
+
+        // Timestamps are appended in order, so expired ones sit at the front
+        while let Some(oldest) = window.attempts.first() {
+            if now.saturating_sub(oldest) < window_seconds {
+                break;
+            }
+            window.attempts.pop_front();
+        }
+
+        window.attempt_count = window.attempts.len();
+        window.window_start = window.attempts.first().unwrap_or(now);
+        window
     }
 
     // ========================================================================
@@ -617,7 +1085,15 @@ impl AntiBot {
         
         // Get or create profile
         let mut profile = Self::get_or_create_profile(&env, &player);
+
+        // Whitelisted players still get stats tracked but never accrue flags
+        let whitelisted = Self::is_whitelisted(env.clone(), player.clone());
         
+        // A streak interrupted by a long break is stale, not a bot pattern
+        if now.saturating_sub(profile.last_activity) > config.fast_streak_reset_seconds {
+            profile.consecutive_fast_solves = 0;
+        }
+
         // Update basic stats
         profile.total_attempts += 1;
         profile.last_activity = now;
@@ -632,7 +1108,7 @@ impl AntiBot {
         if success && solve_time_ms < config.min_solve_time_threshold_ms {
             profile.consecutive_fast_solves += 1;
             
-            if solve_time_ms < config.suspicious_solve_time_ms {
+            if solve_time_ms < config.suspicious_solve_time_ms && !whitelisted {
                 Self::record_suspicious_activity(
                     &env,
                     &player,
@@ -645,15 +1121,33 @@ impl AntiBot {
             profile.consecutive_fast_solves = 0;
         }
 
-        // Update average solve time
+        // Check against the player's own history before it absorbs this solve
+        if success && !whitelisted && Self::is_solve_time_outlier(&config, &profile, solve_time_ms) {
+            Self::record_suspicious_activity(
+                &env,
+                &player,
+                ActivityType::TooFastSolve,
+                symbol_short!("outlier"),
+                6,
+            )?;
+        }
+
+        // Update average solve time and its variance (Welford's method)
         if success && profile.successful_attempts > 0 {
+            let old_mean = profile.avg_solve_time_ms;
             profile.avg_solve_time_ms = 
                 (profile.avg_solve_time_ms * (profile.successful_attempts - 1) as u64 + solve_time_ms)
                     / profile.successful_attempts as u64;
+
+            let delta_old = solve_time_ms as i128 - old_mean as i128;
+            let delta_new = solve_time_ms as i128 - profile.avg_solve_time_ms as i128;
+            profile.solve_time_m2 = profile
+                .solve_time_m2
+                .saturating_add((delta_old * delta_new).max(0) as u128);
         }
 
         // Check for bot patterns
-        if profile.consecutive_fast_solves >= config.max_consecutive_fast_solves {
+        if profile.consecutive_fast_solves >= config.max_consecutive_fast_solves && !whitelisted {
             Self::record_suspicious_activity(
                 &env,
                 &player,
@@ -688,19 +1182,50 @@ impl AntiBot {
         // Update behavioral pattern
         Self::update_behavioral_pattern(&env, &player, &activity)?;
 
-        // Update trust score based on activity
-        Self::update_trust_score(&env, &mut profile)?;
+        if success {
+            Self::track_puzzle_solve(&env, &config, &player, puzzle_id, solve_time_ms);
+        }
+
+        // Update trust score based on activity; whitelisted scores stay pinned
+        if !whitelisted {
+            Self::update_trust_score(&env, &mut profile)?;
+        }
         
         Self::update_profile(&env, &player, &profile);
 
         env.events().publish(
-            (symbol_short!("activity"), player),
+            (symbol_short!("activity"), player.clone()),
             (puzzle_id, solve_time_ms, success),
         );
 
+        // Solving a trap puzzle is decisive
+        if success
+            && !whitelisted
+            && Self::is_honeypot(env.clone(), puzzle_id)
+            && !Self::is_blacklisted(env.clone(), player.clone())
+        {
+            Self::blacklist(&env, &player, symbol_short!("honeypot"))?;
+        }
+
         Ok(())
     }
 
+    /// True if `solve_time_ms` is more than `outlier_std_devs` standard
+    /// deviations faster than the player's mean. The deviation is floored at
+    /// a tenth of the mean so a very steady history doesn't flag small dips.
+    /// Expects `successful_attempts` to already count this solve.
+    fn is_solve_time_outlier(config: &Config, profile: &PlayerProfile, solve_time_ms: u64) -> bool {
+        let samples = profile.successful_attempts.saturating_sub(1);
+        if samples < config.outlier_min_samples.max(2) {
+            return false;
+        }
+
+        let mean = profile.avg_solve_time_ms;
+        let variance = profile.solve_time_m2 / (samples - 1) as u128;
+        let std_dev = (isqrt(variance) as u64).max(mean / 10);
+        solve_time_ms < mean.saturating_sub(std_dev.saturating_mul(config.outlier_std_devs as u64))
+    }
+
     fn update_behavioral_pattern(
         env: &Env,
         player: &Address,
@@ -724,6 +1249,7 @@ impl AntiBot {
                 consistency_score: 500,
                 time_distribution: Vec::new(&env),
                 gas_pattern_variance: 500,
+                gas_distribution: Vec::new(env),
             });
 
         // Add new timestamp
@@ -767,17 +1293,33 @@ impl AntiBot {
             pattern.pattern_variance = (variance.min(1000000) / 1000) as u32;
             pattern.consistency_score = (1000 - pattern.pattern_variance).min(1000);
 
-            // Check for suspicious gas patterns
-            if new_activity.gas_used > 0 {
-                // Bot might use consistent gas amounts
-                let gas_threshold = 100; // threshold for gas consistency
-                pattern.gas_pattern_variance = 
-                    if new_activity.gas_used % 1000 == 0 {
-                        900 // suspicious: round gas usage
-                    } else {
-                        400 // normal variance
-                    };
+        }
+
+        // Track gas usage the same way; unreported (zero) gas is ignored
+        if new_activity.gas_used > 0 {
+            pattern.gas_distribution.push_back(new_activity.gas_used);
+            while pattern.gas_distribution.len() > window_size as u32 {
+                let _ = pattern.gas_distribution.remove(0);
+            }
+        }
+
+        // Bots tend to burn the same gas every call. Score the spread as the
+        // coefficient of variation in per-mille: 0 for identical gas, capped
+        // at 1000 for wildly varying usage.
+        if pattern.gas_distribution.len() >= 3 {
+            let n = pattern.gas_distribution.len() as u128;
+            let sum: u128 = pattern.gas_distribution.iter().map(|g| g as u128).sum();
+            let mean = sum / n;
+
+            let mut variance_sum: u128 = 0;
+            for gas in pattern.gas_distribution.iter() {
+                let diff = (gas as u128).abs_diff(mean);
+                variance_sum += diff * diff;
             }
+            let std_dev = isqrt(variance_sum / n);
+
+            pattern.gas_pattern_variance =
+                (std_dev * 1000).checked_div(mean).unwrap_or(0).min(1000) as u32;
         }
 
         env.storage()
@@ -794,6 +1336,13 @@ impl AntiBot {
     }
 
     pub fn analyze_player(env: Env, player: Address) -> Result<PlayerAnalysis, AntiBotError> {
+        let config: Config = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(AntiBotError::NotInitialized)?;
+        let weights = config.risk_weights;
+
         let profile = Self::get_or_create_profile(&env, &player);
         let pattern = Self::get_behavioral_pattern(env.clone(), player.clone());
         
@@ -804,36 +1353,36 @@ impl AntiBot {
         // Analyze based on profile
         if profile.consecutive_fast_solves >= 3 {
             risk_factors.push_back(symbol_short!("fast_slv"));
-            bot_probability += 30;
+            bot_probability += weights.fast_solves;
         }
 
         if profile.failed_attempts > profile.successful_attempts * 2 {
             risk_factors.push_back(symbol_short!("high_fail"));
-            bot_probability += 20;
+            bot_probability += weights.high_failure_rate;
         }
 
         // Analyze behavioral pattern
         if let Some(p) = pattern {
             if p.consistency_score > 800 {
                 risk_factors.push_back(symbol_short!("cons_time"));
-                bot_probability += 25;
+                bot_probability += weights.consistent_timing;
             }
             
-            if p.gas_pattern_variance > 700 {
+            if p.gas_pattern_variance < 50 {
                 risk_factors.push_back(symbol_short!("gas_patt"));
-                bot_probability += 15;
+                bot_probability += weights.gas_pattern;
             }
 
             if p.pattern_variance < 100 {
                 risk_factors.push_back(symbol_short!("low_var"));
-                bot_probability += 20;
+                bot_probability += weights.low_variance;
             }
         }
 
         // Check trust score
         if profile.trust_score < 300 {
             risk_factors.push_back(symbol_short!("low_trust"));
-            bot_probability += 20;
+            bot_probability += weights.low_trust;
         }
 
         // Determine recommendation
@@ -853,6 +1402,130 @@ impl AntiBot {
         })
     }
 
+    /// Bundles a player's activity counters, suspicious/penalty counts and
+    /// current bot probability so front ends can fetch them in one call.
+    pub fn get_player_stats(env: Env, player: Address) -> Result<PlayerStats, AntiBotError> {
+        let analysis = Self::analyze_player(env.clone(), player.clone())?;
+        let profile = Self::get_or_create_profile(&env, &player);
+
+        let suspicious_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SuspiciousCount(player.clone()))
+            .unwrap_or(0);
+
+        Ok(PlayerStats {
+            total_attempts: profile.total_attempts,
+            successful_attempts: profile.successful_attempts,
+            failed_attempts: profile.failed_attempts,
+            avg_solve_time_ms: profile.avg_solve_time_ms,
+            suspicious_count,
+            active_penalties: Self::get_active_penalties(env, player),
+            bot_probability: analysis.bot_probability,
+        })
+    }
+
+    // ========================================================================
+    // COORDINATED ATTACK DETECTION
+    // ========================================================================
+
+    fn load_puzzle_solves(env: &Env, config: &Config, puzzle_id: u32) -> PuzzleSolveWindow {
+        let now = env.ledger().timestamp();
+        let mut window: PuzzleSolveWindow = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PuzzleSolves(puzzle_id))
+            .unwrap_or(PuzzleSolveWindow {
+                players: Vec::new(env),
+                solve_times: Vec::new(env),
+                timestamps: Vec::new(env),
+            });
+
+        // Entries are appended in order, so expired ones sit at the front
+        while let Some(oldest) = window.timestamps.first() {
+            if now.saturating_sub(oldest) < config.swarm.window_seconds {
+                break;
+            }
+            window.players.pop_front();
+            window.solve_times.pop_front();
+            window.timestamps.pop_front();
+        }
+
+        window
+    }
+
+    fn track_puzzle_solve(env: &Env, config: &Config, player: &Address, puzzle_id: u32, solve_time_ms: u64) {
+        let now = env.ledger().timestamp();
+        let mut window = Self::load_puzzle_solves(env, config, puzzle_id);
+
+        // Keep only the player's latest solve so repeat submissions don't count twice
+        if let Some(index) = window.players.first_index_of(player) {
+            window.players.remove(index);
+            window.solve_times.remove(index);
+            window.timestamps.remove(index);
+        }
+        if window.players.len() >= MAX_SWARM_SAMPLE {
+            window.players.pop_front();
+            window.solve_times.pop_front();
+            window.timestamps.pop_front();
+        }
+        window.players.push_back(player.clone());
+        window.solve_times.push_back(solve_time_ms);
+        window.timestamps.push_back(now);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::PuzzleSolves(puzzle_id), &window);
+
+        if Self::is_swarm(config, &window) {
+            let boost_until: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::CaptchaBoostUntil)
+                .unwrap_or(0);
+            if now >= boost_until {
+                env.events().publish(
+                    (symbol_short!("swarm"), puzzle_id),
+                    window.players.len(),
+                );
+            }
+            env.storage()
+                .instance()
+                .set(&DataKey::CaptchaBoostUntil, &(now + config.swarm.boost_seconds));
+        }
+    }
+
+    // A swarm is enough distinct players whose solve times cluster within the spread
+    fn is_swarm(config: &Config, window: &PuzzleSolveWindow) -> bool {
+        if window.players.len() < config.swarm.min_players {
+            return false;
+        }
+
+        for anchor in window.solve_times.iter() {
+            let mut similar = 0;
+            for other in window.solve_times.iter() {
+                if other >= anchor && other - anchor <= config.swarm.solve_time_spread_ms {
+                    similar += 1;
+                }
+            }
+            if similar >= config.swarm.min_players {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn detect_coordinated_attack(env: Env, puzzle_id: u32) -> bool {
+        let config: Config = match env.storage().instance().get(&DataKey::Config) {
+            Some(c) => c,
+            None => return false,
+        };
+
+        let window = Self::load_puzzle_solves(&env, &config, puzzle_id);
+        Self::is_swarm(&config, &window)
+    }
+
     // ========================================================================
     // SUSPICIOUS ACTIVITY FLAGGING
     // ========================================================================
@@ -887,19 +1560,23 @@ impl AntiBot {
             .persistent()
             .set(&DataKey::SuspiciousCount(player.clone()), &(count + 1));
 
+        // Keep the record for auditing, but never flag a whitelisted player
+        if Self::is_whitelisted(env.clone(), player.clone()) {
+            return Ok(());
+        }
+
         // Update player status if needed
         let mut profile = Self::get_or_create_profile(env, player);
         
-        if profile.status != 3 
-            && profile.status != 4
-            && profile.status != 7 {
+        if profile.status != PlayerStatus::Flagged 
+            && profile.status != PlayerStatus::Penalized
+            && profile.status != PlayerStatus::Blacklisted {
             
             // Check if we should flag the player
-            let total_suspicious = count + 1;
-            let high_severity_count = Self::count_high_severity_activities(env, player);
+            let (total_suspicious, high_severity_count) = Self::count_recent_activities(env, player);
             
             if severity >= 8 || (total_suspicious >= 5 && high_severity_count >= 2) {
-                profile.status = 3;
+                profile.status = PlayerStatus::Flagged;
                 Self::update_profile(env, player, &profile);
                 
                 env.events().publish(
@@ -912,26 +1589,130 @@ impl AntiBot {
         Ok(())
     }
 
-    fn count_high_severity_activities(env: &Env, player: &Address) -> u32 {
+    /// Counts suspicious activities inside the configured lookback window,
+    /// returning `(total, high_severity)`. Older records stay readable but no
+    /// longer count toward flagging.
+    fn count_recent_activities(env: &Env, player: &Address) -> (u32, u32) {
         let count: u32 = env
             .storage()
             .persistent()
             .get(&DataKey::SuspiciousCount(player.clone()))
             .unwrap_or(0);
+
+        let lookback_days = env
+            .storage()
+            .instance()
+            .get::<DataKey, Config>(&DataKey::Config)
+            .map(|c| c.suspicious_lookback_days)
+            .unwrap_or(u64::MAX);
+        let cutoff = env
+            .ledger()
+            .timestamp()
+            .saturating_sub(lookback_days.saturating_mul(86400));
         
+        let mut total = 0;
         let mut high_count = 0;
-        for i in 0..count {
+        // Records are stored oldest first, so walk back until the cutoff
+        for i in (0..count).rev() {
             if let Some(activity) = env
                 .storage()
                 .persistent()
                 .get::<DataKey, SuspiciousActivity>(&DataKey::SuspiciousActivity(player.clone(), i))
             {
+                if activity.timestamp < cutoff {
+                    break;
+                }
+                total += 1;
                 if activity.severity >= 7 {
                     high_count += 1;
                 }
             }
         }
-        high_count
+        (total, high_count)
+    }
+
+    /// Moves the suspicious records that led up to `penalty_id` (those inside
+    /// the lookback window before it was applied) out of the live history and
+    /// into the resolved log, so they stop counting toward new flags.
+    fn resolve_penalty_activity(env: &Env, config: &Config, penalty_id: u32) {
+        let penalty: PenaltyRecord = match env.storage().persistent().get(&DataKey::Penalty(penalty_id)) {
+            Some(p) => p,
+            None => return,
+        };
+        let player = penalty.player;
+        let window_start = penalty
+            .applied_at
+            .saturating_sub(config.suspicious_lookback_days.saturating_mul(86400));
+
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SuspiciousCount(player.clone()))
+            .unwrap_or(0);
+        let mut resolved: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ResolvedCount(player.clone()))
+            .unwrap_or(0);
+
+        // Compact the live history in place, keeping record order intact
+        let mut kept = 0;
+        for i in 0..count {
+            let key = DataKey::SuspiciousActivity(player.clone(), i);
+            let activity: SuspiciousActivity = match env.storage().persistent().get(&key) {
+                Some(a) => a,
+                None => continue,
+            };
+            env.storage().persistent().remove(&key);
+
+            if activity.timestamp >= window_start && activity.timestamp <= penalty.applied_at {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::ResolvedActivity(player.clone(), resolved), &activity);
+                resolved += 1;
+            } else {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::SuspiciousActivity(player.clone(), kept), &activity);
+                kept += 1;
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::SuspiciousCount(player.clone()), &kept);
+        env.storage()
+            .persistent()
+            .set(&DataKey::ResolvedCount(player), &resolved);
+    }
+
+    pub fn get_resolved_activities(
+        env: Env,
+        player: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<SuspiciousActivity> {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ResolvedCount(player.clone()))
+            .unwrap_or(0);
+
+        let mut result = Vec::new(&env);
+        let start = offset.min(count);
+        let end = offset.saturating_add(limit).min(count);
+
+        for i in start..end {
+            if let Some(activity) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, SuspiciousActivity>(&DataKey::ResolvedActivity(player.clone(), i))
+            {
+                result.push_back(activity);
+            }
+        }
+
+        result
     }
 
     pub fn get_suspicious_activities(
@@ -965,20 +1746,55 @@ impl AntiBot {
 
     pub fn flag_player(
         env: Env,
+        verifier: Address,
         player: Address,
         reason: Symbol,
         severity: u32,
     ) -> Result<(), AntiBotError> {
-        Self::require_verifier(&env)?;
+        Self::require_verifier(&env, &verifier)?;
+        Self::flag_one(&env, &player, reason, severity)
+    }
 
-        let mut profile = Self::get_or_create_profile(&env, &player);
-        profile.status = 3;
-        Self::update_profile(&env, &player, &profile);
+    /// Flags every player in `players` under a single verifier auth. Players
+    /// already flagged, penalized or blacklisted are skipped. Returns how many
+    /// players were newly flagged.
+    pub fn flag_players(
+        env: Env,
+        verifier: Address,
+        players: Vec<Address>,
+        reason: Symbol,
+        severity: u32,
+    ) -> Result<u32, AntiBotError> {
+        Self::require_verifier(&env, &verifier)?;
+        if players.len() > MAX_BATCH_SIZE {
+            return Err(AntiBotError::BatchTooLarge);
+        }
+
+        let mut flagged = 0;
+        for player in players.iter() {
+            let status = Self::load_profile(&env, &player).map(|p| p.status);
+            if matches!(
+                status,
+                Some(PlayerStatus::Flagged | PlayerStatus::Penalized | PlayerStatus::Blacklisted)
+            ) {
+                continue;
+            }
+            Self::flag_one(&env, &player, reason.clone(), severity)?;
+            flagged += 1;
+        }
+
+        Ok(flagged)
+    }
+
+    fn flag_one(env: &Env, player: &Address, reason: Symbol, severity: u32) -> Result<(), AntiBotError> {
+        let mut profile = Self::get_or_create_profile(env, player);
+        profile.status = PlayerStatus::Flagged;
+        Self::update_profile(env, player, &profile);
 
-        Self::record_suspicious_activity(&env, &player, ActivityType::SuspiciousTiming, reason.clone(), severity)?;
+        Self::record_suspicious_activity(env, player, ActivityType::SuspiciousTiming, reason.clone(), severity)?;
 
         env.events().publish(
-            (symbol_short!("flagged"), player),
+            (symbol_short!("flagged"), player.clone()),
             (reason, severity),
         );
 
@@ -987,22 +1803,38 @@ impl AntiBot {
 
     pub fn unflag_player(env: Env, player: Address) -> Result<(), AntiBotError> {
         Self::require_admin(&env)?;
+        Self::unflag_one(&env, &player)
+    }
 
-        let mut profile = Self::get_or_create_profile(&env, &player);
-        
-        match profile.status {
-            3 | 2 => {
-                profile.status = 1;
-                Self::update_profile(&env, &player, &profile);
-                
-                env.events().publish(
-                    (symbol_short!("unflagged"), player),
-                    (),
-                );
-                Ok(())
+    /// Clears the flag on every flagged or suspicious player in `players`
+    /// under a single admin auth, skipping anyone else. Returns how many
+    /// players were unflagged.
+    pub fn unflag_players(env: Env, players: Vec<Address>) -> Result<u32, AntiBotError> {
+        Self::require_admin(&env)?;
+        if players.len() > MAX_BATCH_SIZE {
+            return Err(AntiBotError::BatchTooLarge);
+        }
+
+        let mut unflagged = 0;
+        for player in players.iter() {
+            if Self::unflag_one(&env, &player).is_ok() {
+                unflagged += 1;
             }
-            _ => Err(AntiBotError::Unauthorized),
         }
+
+        Ok(unflagged)
+    }
+
+    fn unflag_one(env: &Env, player: &Address) -> Result<(), AntiBotError> {
+        let mut profile = Self::get_or_create_profile(env, player);
+        profile.status = profile.status.transition(PlayerStatus::Verified)?;
+        Self::update_profile(env, player, &profile);
+
+        env.events().publish(
+            (symbol_short!("unflagged"), player.clone()),
+            (),
+        );
+        Ok(())
     }
 
     // ========================================================================
@@ -1117,31 +1949,32 @@ impl AntiBot {
             .persistent()
             .get::<DataKey, BehavioralPattern>(&DataKey::BehavioralPattern(profile.address.clone()));
 
+        let caps = &config.trust_factor_caps;
         let mut score: u32 = 500; // Start neutral
 
-        // Factor 1: Success rate (0-300 points)
+        // Factor 1: Success rate (0-300 points by default)
         if profile.total_attempts > 0 {
-            let success_rate = (profile.successful_attempts * 300) / profile.total_attempts;
-            score += success_rate.min(300);
+            let success_rate = (profile.successful_attempts * caps.success_rate) / profile.total_attempts;
+            score += success_rate.min(caps.success_rate);
         }
 
-        // Factor 2: Account age (0-100 points)
+        // Factor 2: Account age (0-100 points by default)
         let now = env.ledger().timestamp();
         let account_age_days = (now - profile.first_seen) / 86400;
-        let age_score = (account_age_days * 10).min(100) as u32;
+        let age_score = (account_age_days * 10).min(caps.account_age as u64) as u32;
         score += age_score;
 
-        // Factor 3: Activity pattern (0-100 points, or -100 for bad patterns)
+        // Factor 3: Activity pattern (0-100 points, or -100 for bad patterns by default)
         if let Some(p) = pattern {
             if p.consistency_score > 800 {
-                score = score.saturating_sub(100); // Suspicious consistency
+                score = score.saturating_sub(caps.consistency_penalty); // Suspicious consistency
             } else if p.consistency_score < 300 {
                 score += 50; // Good human-like variance
             }
         }
 
-        // Factor 4: Penalty history
-        let penalty_deduction = profile.penalty_count * 50;
+        // Factor 4: Penalty history, ignoring penalties removed or overturned on appeal
+        let penalty_deduction = Self::count_standing_penalties(env, &profile.address) * 50;
         score = score.saturating_sub(penalty_deduction);
 
         // Factor 5: Consecutive fast solves penalty
@@ -1152,22 +1985,95 @@ impl AntiBot {
         // Ensure score is in valid range
         profile.trust_score = score.min(1000);
 
+        // Factor 6: Blend in the external reputation score when configured
+        if let Some(reputation_contract) = config.reputation_contract {
+            if let Some(external) = Self::fetch_external_reputation(env, &reputation_contract, &profile.address) {
+                let weight = config.reputation_weight_pct.min(100);
+                profile.trust_score =
+                    (profile.trust_score * (100 - weight) + external.min(1000) * weight) / 100;
+            }
+        }
+
         // Update status based on trust score
-        if profile.trust_score >= 800 && profile.status == 0 {
-            profile.status = 1;
+        if profile.trust_score >= 800 && profile.status == PlayerStatus::Unverified {
+            profile.status = PlayerStatus::Verified;
         }
 
         // Update reputation tier
-        profile.reputation_tier = match profile.trust_score {
+        profile.reputation_tier = Self::tier_for_score(profile.trust_score);
+
+        Ok(())
+    }
+
+    /// Queries the reputation contract's `calculate_score`. Returns `None` if
+    /// the call fails so scoring falls back to local factors only.
+    fn fetch_external_reputation(env: &Env, reputation_contract: &Address, player: &Address) -> Option<u32> {
+        let args = vec![env, player.into_val(env)];
+        match env.try_invoke_contract::<u32, soroban_sdk::Error>(
+            reputation_contract,
+            &Symbol::new(env, "calculate_score"),
+            args,
+        ) {
+            Ok(Ok(score)) => Some(score),
+            _ => None,
+        }
+    }
+
+    fn tier_for_score(trust_score: u32) -> u32 {
+        match trust_score {
             0..=199 => 0, // New/Suspicious
             200..=399 => 1, // Low trust
             400..=599 => 2, // Neutral
             600..=749 => 3, // Good
             750..=899 => 4, // High trust
             _ => 5,         // Excellent
-        };
+        }
+    }
 
-        Ok(())
+    /// Recomputes and stores `player`'s trust score and tier from their
+    /// current profile and penalty state, e.g. after a penalty is removed.
+    pub fn refresh_trust_score(env: Env, verifier: Address, player: Address) -> Result<u32, AntiBotError> {
+        Self::require_verifier(&env, &verifier)?;
+
+        let mut profile = Self::get_or_create_profile(&env, &player);
+        let old_score = profile.trust_score;
+        Self::update_trust_score(&env, &mut profile)?;
+        Self::update_profile(&env, &player, &profile);
+
+        env.events().publish(
+            (symbol_short!("trust"), player),
+            (old_score, profile.trust_score),
+        );
+
+        Ok(profile.trust_score)
+    }
+
+    fn count_standing_penalties(env: &Env, player: &Address) -> u32 {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PlayerPenaltyCount(player.clone()))
+            .unwrap_or(0);
+
+        let mut standing = 0;
+        for i in 0..count {
+            if let Some(penalty_id) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, u32>(&DataKey::PlayerPenalties(player.clone(), i))
+            {
+                if let Some(penalty) = env
+                    .storage()
+                    .persistent()
+                    .get::<DataKey, PenaltyRecord>(&DataKey::Penalty(penalty_id))
+                {
+                    if penalty.active {
+                        standing += 1;
+                    }
+                }
+            }
+        }
+        standing
     }
 
     pub fn get_trust_score(env: Env, player: Address) -> u32 {
@@ -1184,15 +2090,68 @@ impl AntiBot {
     // PENALTY SYSTEM
     // ========================================================================
 
+    /// Applies a penalty to `player`. With no explicit `penalty_type` the
+    /// type escalates along the configured ladder from the player's prior
+    /// penalty count; only the admin may force a specific type.
     pub fn apply_penalty(
         env: Env,
+        verifier: Address,
         player: Address,
-        penalty_type: PenaltyType,
+        penalty_type: Option<PenaltyType>,
         reason: Symbol,
         severity: u32,
     ) -> Result<u32, AntiBotError> {
-        Self::require_verifier(&env)?;
+        Self::require_verifier(&env, &verifier)?;
+
+        let penalty_type = match penalty_type {
+            Some(forced) => {
+                let config: Config = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Config)
+                    .ok_or(AntiBotError::NotInitialized)?;
+                if verifier != config.admin {
+                    return Err(AntiBotError::Unauthorized);
+                }
+                forced
+            }
+            None => Self::preview_penalty(env.clone(), player.clone())?,
+        };
+
+        Self::record_penalty(&env, &player, penalty_type, reason, severity)
+    }
+
+    /// Returns the penalty type the ladder would assign to `player` next.
+    pub fn preview_penalty(env: Env, player: Address) -> Result<PenaltyType, AntiBotError> {
+        let config: Config = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(AntiBotError::NotInitialized)?;
+
+        let prior = Self::load_profile(&env, &player)
+            .map(|p| p.penalty_count)
+            .unwrap_or(0);
+
+        let ladder = config.penalty_ladder;
+        Ok(if prior >= ladder.permanent_ban_after {
+            PenaltyType::PermanentBan
+        } else if prior >= ladder.extended_ban_after {
+            PenaltyType::ExtendedBan
+        } else if prior >= ladder.temporary_ban_after {
+            PenaltyType::TemporaryBan
+        } else {
+            PenaltyType::Warning
+        })
+    }
 
+    fn record_penalty(
+        env: &Env,
+        player: &Address,
+        penalty_type: PenaltyType,
+        reason: Symbol,
+        severity: u32,
+    ) -> Result<u32, AntiBotError> {
         let now = env.ledger().timestamp();
         let config: Config = env
             .storage()
@@ -1211,11 +2170,11 @@ impl AntiBot {
         // Calculate expiration
         let expires_at = match penalty_type {
             PenaltyType::Warning => now,
-            PenaltyType::TemporaryBan => now + (config.default_penalty_hours * 3600),
-            PenaltyType::ExtendedBan => now + (config.default_penalty_hours * 3600 * 7),
+            PenaltyType::TemporaryBan => now + (config.temporary_ban_hours * 3600),
+            PenaltyType::ExtendedBan => now + (config.extended_ban_hours * 3600),
             PenaltyType::PermanentBan => u64::MAX,
             PenaltyType::ScoreReduction => now,
-            PenaltyType::VerificationRequired => now + (config.default_penalty_hours * 3600),
+            PenaltyType::VerificationRequired => now + (config.verification_required_hours * 3600),
         };
 
         let penalty = PenaltyRecord {
@@ -1248,27 +2207,29 @@ impl AntiBot {
             .set(&DataKey::PlayerPenaltyCount(player.clone()), &(player_penalty_count + 1));
 
         // Update player profile
-        let mut profile = Self::get_or_create_profile(&env, &player);
+        let mut profile = Self::get_or_create_profile(env, player);
         profile.penalty_count += 1;
-        profile.status = 4;
+        profile.status = PlayerStatus::Penalized;
 
         // Apply score reduction if needed
         if matches!(penalty_type, PenaltyType::ScoreReduction) {
             let reduction = (severity as u32 * 50).min(300);
             profile.trust_score = profile.trust_score.saturating_sub(reduction);
+            profile.reputation_tier = Self::tier_for_score(profile.trust_score);
         }
 
-        Self::update_profile(&env, &player, &profile);
+        Self::update_profile(env, player, &profile);
 
         // Add to blacklist for permanent bans
         if matches!(penalty_type, PenaltyType::PermanentBan) {
             env.storage()
                 .persistent()
                 .set(&DataKey::Blacklisted(player.clone()), &true);
+            Self::add_to_index(env, &DataKey::BlacklistedPlayers, player);
         }
 
         env.events().publish(
-            (symbol_short!("penalty"), player),
+            (symbol_short!("penalty"), player.clone()),
             (penalty_id, reason.clone(), severity),
         );
 
@@ -1277,7 +2238,10 @@ impl AntiBot {
 
     pub fn remove_penalty(env: Env, penalty_id: u32) -> Result<(), AntiBotError> {
         Self::require_admin(&env)?;
+        Self::deactivate_penalty(&env, penalty_id)
+    }
 
+    fn deactivate_penalty(env: &Env, penalty_id: u32) -> Result<(), AntiBotError> {
         let mut penalty: PenaltyRecord = env
             .storage()
             .persistent()
@@ -1293,10 +2257,10 @@ impl AntiBot {
         // Update player status if no active penalties
         let player = penalty.player.clone();
         if Self::get_active_penalties(env.clone(), player.clone()) == 0 {
-            let mut profile = Self::get_or_create_profile(&env, &player);
-            if profile.status == 4 {
-                profile.status = 1;
-                Self::update_profile(&env, &player, &profile);
+            let mut profile = Self::get_or_create_profile(env, &player);
+            if profile.status == PlayerStatus::Penalized {
+                profile.status = PlayerStatus::Verified;
+                Self::update_profile(env, &player, &profile);
             }
         }
 
@@ -1366,7 +2330,8 @@ impl AntiBot {
         player: Address,
         penalty_id: u32,
         reason: Symbol,
-        evidence: Symbol,
+        evidence_uri: String,
+        evidence_hash: Option<BytesN<32>>,
     ) -> Result<u32, AntiBotError> {
         player.require_auth();
 
@@ -1402,11 +2367,7 @@ impl AntiBot {
             .unwrap_or(0);
         
         for i in 1..=appeal_count {
-            if let Some(existing_appeal) = env
-                .storage()
-                .persistent()
-                .get::<DataKey, Appeal>(&DataKey::Appeal(i))
-            {
+            if let Some(existing_appeal) = Self::load_appeal(&env, i) {
                 if existing_appeal.penalty_id == penalty_id 
                     && existing_appeal.status == AppealStatus::Pending {
                     return Err(AntiBotError::AlreadyAppealed);
@@ -1417,27 +2378,51 @@ impl AntiBot {
         let new_appeal_id = appeal_count + 1;
         env.storage().instance().set(&DataKey::AppealCounter, &new_appeal_id);
 
+        // Escrow the appeal stake, if one is configured
+        let (stake_token, stake_amount) = match config.appeal_stake_token {
+            Some(stake_token) if config.appeal_stake_amount > 0 => {
+                token::Client::new(&env, &stake_token).transfer(
+                    &player,
+                    &env.current_contract_address(),
+                    &config.appeal_stake_amount,
+                );
+                (Some(stake_token), config.appeal_stake_amount)
+            }
+            _ => (None, 0),
+        };
+
         let appeal = Appeal {
             appeal_id: new_appeal_id,
             player: player.clone(),
             penalty_id,
             reason,
-            evidence,
+            evidence_uri,
+            evidence_hash: evidence_hash.map(Bytes::from),
             submitted_at: now,
             status: AppealStatus::Pending,
             reviewed_by: None,
             reviewed_at: None,
             decision_reason: None,
+            stake_token,
+            stake_amount,
         };
 
         env.storage()
             .persistent()
             .set(&DataKey::Appeal(new_appeal_id), &appeal);
 
+        let mut pending: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingAppeals)
+            .unwrap_or(Vec::new(&env));
+        pending.push_back(new_appeal_id);
+        env.storage().persistent().set(&DataKey::PendingAppeals, &pending);
+
         // Update player profile
         let mut profile = Self::get_or_create_profile(&env, &player);
         profile.appeal_count += 1;
-        profile.status = 5;
+        profile.status = PlayerStatus::AppealPending;
         Self::update_profile(&env, &player, &profile);
 
         env.events().publish(
@@ -1454,16 +2439,15 @@ impl AntiBot {
         approved: bool,
         decision_reason: Symbol,
     ) -> Result<(), AntiBotError> {
-        Self::require_admin(&env)?;
-
         let now = env.ledger().timestamp();
         let reviewer = Self::require_admin(&env)?;
-
-        let mut appeal: Appeal = env
+        let config: Config = env
             .storage()
-            .persistent()
-            .get(&DataKey::Appeal(appeal_id))
-            .ok_or(AntiBotError::AppealNotFound)?;
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(AntiBotError::NotInitialized)?;
+
+        let mut appeal = Self::load_appeal(&env, appeal_id).ok_or(AntiBotError::AppealNotFound)?;
 
         if appeal.status != AppealStatus::Pending {
             return Err(AntiBotError::AppealNotFound);
@@ -1481,6 +2465,7 @@ impl AntiBot {
         env.storage()
             .persistent()
             .set(&DataKey::Appeal(appeal_id), &appeal);
+        Self::remove_pending_appeal(&env, appeal_id);
 
         // Update player status
         let player = appeal.player.clone();
@@ -1488,22 +2473,29 @@ impl AntiBot {
 
         if approved {
             // Remove the penalty
-            let _ = Self::remove_penalty(env.clone(), appeal.penalty_id);
-            profile.status = 1;
+            Self::deactivate_penalty(&env, appeal.penalty_id)?;
+            if config.resolve_activity_on_appeal {
+                Self::resolve_penalty_activity(&env, &config, appeal.penalty_id);
+            }
+            profile.status = PlayerStatus::Verified;
             
             // Restore some trust score
             profile.trust_score = (profile.trust_score + 100).min(1000);
         } else {
             // Appeal rejected, restore penalized status if needed
             if Self::get_active_penalties(env.clone(), player.clone()) > 0 {
-                profile.status = 4;
+                profile.status = PlayerStatus::Penalized;
             } else {
-                profile.status = 1;
+                profile.status = PlayerStatus::Verified;
             }
         }
 
         Self::update_profile(&env, &player, &profile);
 
+        // Approved appellants get their stake back; rejected stakes are forfeited
+        let stake_to = if approved { player.clone() } else { config.fee_recipient };
+        Self::release_appeal_stake(&env, &appeal, &stake_to);
+
         env.events().publish(
             (symbol_short!("app_res"), appeal_id),
             (approved, decision_reason.clone()),
@@ -1512,25 +2504,156 @@ impl AntiBot {
         Ok(())
     }
 
-    pub fn get_appeal(env: Env, appeal_id: u32) -> Option<Appeal> {
+    /// Closes an appeal that has sat unreviewed past `appeal_review_days`.
+    /// Callable by anyone; the appeal is recorded as rejected by timeout and
+    /// the player returns to the status their active penalties imply.
+    pub fn expire_appeal(env: Env, appeal_id: u32) -> Result<(), AntiBotError> {
+        let config: Config = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(AntiBotError::NotInitialized)?;
+
+        let mut appeal = Self::load_appeal(&env, appeal_id).ok_or(AntiBotError::AppealNotFound)?;
+
+        if appeal.status != AppealStatus::Pending {
+            return Err(AntiBotError::AppealNotFound);
+        }
+
+        let now = env.ledger().timestamp();
+        if now <= appeal.submitted_at + (config.appeal_review_days * 86400) {
+            return Err(AntiBotError::ReviewWindowOpen);
+        }
+
+        appeal.status = AppealStatus::Rejected;
+        appeal.reviewed_at = Some(now);
+        appeal.decision_reason = Some(symbol_short!("timeout"));
+
         env.storage()
             .persistent()
-            .get(&DataKey::Appeal(appeal_id))
+            .set(&DataKey::Appeal(appeal_id), &appeal);
+        Self::remove_pending_appeal(&env, appeal_id);
+
+        let player = appeal.player.clone();
+        let mut profile = Self::get_or_create_profile(&env, &player);
+        if profile.status == PlayerStatus::AppealPending {
+            profile.status = if Self::get_active_penalties(env.clone(), player.clone()) > 0 {
+                PlayerStatus::Penalized
+            } else {
+                PlayerStatus::Verified
+            };
+            Self::update_profile(&env, &player, &profile);
+        }
+
+        // The player is not at fault for a missed review, so the stake is returned
+        Self::release_appeal_stake(&env, &appeal, &player);
+
+        env.events().publish(
+            (symbol_short!("app_exp"), appeal_id),
+            (player,),
+        );
+
+        Ok(())
+    }
+
+    fn release_appeal_stake(env: &Env, appeal: &Appeal, to: &Address) {
+        if let Some(stake_token) = &appeal.stake_token {
+            if appeal.stake_amount > 0 {
+                token::Client::new(env, stake_token).transfer(
+                    &env.current_contract_address(),
+                    to,
+                    &appeal.stake_amount,
+                );
+            }
+        }
+    }
+
+    fn remove_pending_appeal(env: &Env, appeal_id: u32) {
+        let mut pending: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingAppeals)
+            .unwrap_or(Vec::new(env));
+        if let Some(idx) = pending.first_index_of(appeal_id) {
+            pending.remove(idx);
+            env.storage().persistent().set(&DataKey::PendingAppeals, &pending);
+        }
+    }
+
+    /// Review queue, oldest first, so admins don't have to scan every appeal
+    pub fn get_pending_appeals(env: Env, offset: u32, limit: u32) -> Vec<Appeal> {
+        let pending: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingAppeals)
+            .unwrap_or(Vec::new(&env));
+
+        let start = offset.min(pending.len());
+        let end = offset.saturating_add(limit).min(pending.len());
+
+        let mut result = Vec::new(&env);
+        for appeal_id in pending.slice(start..end).iter() {
+            if let Some(appeal) = Self::load_appeal(&env, appeal_id) {
+                result.push_back(appeal);
+            }
+        }
+        result
+    }
+
+    pub fn get_appeal(env: Env, appeal_id: u32) -> Option<Appeal> {
+        Self::load_appeal(&env, appeal_id)
+    }
+
+    /// Reads an appeal, upgrading records stored before evidence carried a
+    /// URI and hash. The old evidence Symbol becomes the URI.
+    fn load_appeal(env: &Env, appeal_id: u32) -> Option<Appeal> {
+        let raw: Val = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Appeal(appeal_id))?;
+
+        // The layouts differ in field names, which the host rejects outright
+        // rather than as a recoverable conversion error, so check keys first
+        let fields = Map::<Symbol, Val>::try_from_val(env, &raw).ok()?;
+        if !fields.contains_key(symbol_short!("evidence")) {
+            return Appeal::try_from_val(env, &raw).ok();
+        }
+
+        let legacy = LegacyAppeal::try_from_val(env, &raw).ok()?;
+        Some(Appeal {
+            appeal_id: legacy.appeal_id,
+            player: legacy.player,
+            penalty_id: legacy.penalty_id,
+            reason: legacy.reason,
+            evidence_uri: symbol_to_string(env, &legacy.evidence)?,
+            evidence_hash: None,
+            submitted_at: legacy.submitted_at,
+            status: legacy.status,
+            reviewed_by: legacy.reviewed_by,
+            reviewed_at: legacy.reviewed_at,
+            decision_reason: legacy.decision_reason,
+            stake_token: None,
+            stake_amount: 0,
+        })
     }
 
     // ========================================================================
     // WHITELIST/BLACKLIST MANAGEMENT
     // ========================================================================
 
-    pub fn whitelist_player(env: Env, player: Address) -> Result<(), AntiBotError> {
+    pub fn whitelist_player(
+        env: Env,
+        player: Address,
+        expires_at: Option<u64>,
+    ) -> Result<(), AntiBotError> {
         Self::require_admin(&env)?;
 
         env.storage()
             .persistent()
-            .set(&DataKey::Whitelisted(player.clone()), &true);
+            .set(&DataKey::Whitelisted(player.clone()), &expires_at.unwrap_or(0));
 
         let mut profile = Self::get_or_create_profile(&env, &player);
-        profile.status = 6;
+        profile.status = PlayerStatus::Whitelisted;
         profile.trust_score = 1000;
         Self::update_profile(&env, &player, &profile);
 
@@ -1550,7 +2673,7 @@ impl AntiBot {
             .remove(&DataKey::Whitelisted(player.clone()));
 
         let mut profile = Self::get_or_create_profile(&env, &player);
-        profile.status = 1;
+        profile.status = PlayerStatus::Verified;
         profile.trust_score = 800;
         Self::update_profile(&env, &player, &profile);
 
@@ -1568,28 +2691,32 @@ impl AntiBot {
         reason: Symbol,
     ) -> Result<(), AntiBotError> {
         Self::require_admin(&env)?;
+        Self::blacklist(&env, &player, reason)
+    }
 
+    fn blacklist(env: &Env, player: &Address, reason: Symbol) -> Result<(), AntiBotError> {
         env.storage()
             .persistent()
             .set(&DataKey::Blacklisted(player.clone()), &true);
+        Self::add_to_index(env, &DataKey::BlacklistedPlayers, player);
 
-        let mut profile = Self::get_or_create_profile(&env, &player);
-        profile.status = 7;
+        let mut profile = Self::get_or_create_profile(env, player);
+        profile.status = PlayerStatus::Blacklisted;
         profile.trust_score = 0;
-        Self::update_profile(&env, &player, &profile);
+        Self::update_profile(env, player, &profile);
 
         // Also apply permanent penalty
         let reason_for_event = reason.clone();
-        let _ = Self::apply_penalty(
-            env.clone(),
-            player.clone(),
+        let _ = Self::record_penalty(
+            env,
+            player,
             PenaltyType::PermanentBan,
             reason,
             10,
         )?;
 
         env.events().publish(
-            (symbol_short!("blacklist"), player),
+            (symbol_short!("blacklist"), player.clone()),
             (reason_for_event,),
         );
 
@@ -1602,9 +2729,10 @@ impl AntiBot {
         env.storage()
             .persistent()
             .remove(&DataKey::Blacklisted(player.clone()));
+        Self::remove_from_index(&env, &DataKey::BlacklistedPlayers, &player);
 
         let mut profile = Self::get_or_create_profile(&env, &player);
-        profile.status = 1;
+        profile.status = PlayerStatus::Verified;
         profile.trust_score = 300; // Low starting score
         Self::update_profile(&env, &player, &profile);
 
@@ -1616,11 +2744,37 @@ impl AntiBot {
         Ok(())
     }
 
+    pub fn get_flagged_players(env: Env, offset: u32, limit: u32) -> Vec<Address> {
+        Self::page_index(&env, &DataKey::FlaggedPlayers, offset, limit)
+    }
+
+    pub fn get_blacklisted_players(env: Env, offset: u32, limit: u32) -> Vec<Address> {
+        Self::page_index(&env, &DataKey::BlacklistedPlayers, offset, limit)
+    }
+
     pub fn is_whitelisted(env: Env, player: Address) -> bool {
-        env.storage()
+        match Self::whitelist_expiry(&env, &player) {
+            Some(0) => true,
+            Some(expires_at) => env.ledger().timestamp() < expires_at,
+            None => false,
+        }
+    }
+
+    fn whitelist_expiry(env: &Env, player: &Address) -> Option<u64> {
+        let raw: Val = env
+            .storage()
             .persistent()
-            .get(&DataKey::Whitelisted(player))
-            .unwrap_or(false)
+            .get(&DataKey::Whitelisted(player.clone()))?;
+
+        if let Ok(expires_at) = u64::try_from_val(env, &raw) {
+            return Some(expires_at);
+        }
+
+        // Entries written before expiry support hold a bare `true`
+        match bool::try_from_val(env, &raw) {
+            Ok(true) => Some(0),
+            _ => None,
+        }
     }
 
     pub fn is_blacklisted(env: Env, player: Address) -> bool {
@@ -1630,6 +2784,42 @@ impl AntiBot {
             .unwrap_or(false)
     }
 
+    // ========================================================================
+    // HONEYPOTS
+    // ========================================================================
+
+    pub fn register_honeypot(env: Env, admin: Address, puzzle_id: u32) -> Result<(), AntiBotError> {
+        Self::require_admin_address(&env, &admin)?;
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Honeypot(puzzle_id), &true);
+
+        env.events().publish(
+            (symbol_short!("honeypot"), puzzle_id),
+            (),
+        );
+
+        Ok(())
+    }
+
+    pub fn remove_honeypot(env: Env, admin: Address, puzzle_id: u32) -> Result<(), AntiBotError> {
+        Self::require_admin_address(&env, &admin)?;
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Honeypot(puzzle_id));
+
+        Ok(())
+    }
+
+    pub fn is_honeypot(env: Env, puzzle_id: u32) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Honeypot(puzzle_id))
+            .unwrap_or(false)
+    }
+
     // ========================================================================
     // COMPREHENSIVE VERIFICATION
     // ========================================================================
@@ -1677,6 +2867,12 @@ impl AntiBot {
             });
         }
 
+        let config: Config = env
+            .storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(AntiBotError::NotInitialized)?;
+
         // Get player analysis
         let analysis = Self::analyze_player(env.clone(), player.clone())?;
         
@@ -1689,21 +2885,29 @@ impl AntiBot {
                 allowed = false;
                 required_action = symbol_short!("blocked");
                 
-                // Apply penalty for high bot probability
-                if analysis.bot_probability > 85 {
-                    let _ = Self::apply_penalty(
-                        env.clone(),
-                        player.clone(),
-                        PenaltyType::TemporaryBan,
-                        symbol_short!("bot_det"),
-                        7,
-                    );
+                // Penalize high bot probability, or flag it for a moderator
+                if analysis.bot_probability > config.auto_penalty_threshold {
+                    if config.auto_penalty_enabled {
+                        let _ = Self::record_penalty(
+                            &env,
+                            &player,
+                            config.auto_penalty_type,
+                            symbol_short!("bot_det"),
+                            7,
+                        );
+                    } else {
+                        required_action = symbol_short!("review");
+                        env.events().publish(
+                            (symbol_short!("rec_pen"), player.clone()),
+                            (config.auto_penalty_type, analysis.bot_probability),
+                        );
+                    }
                 }
             }
             rec if rec == symbol_short!("verify") => {
                 // Require CAPTCHA verification
                 let profile = Self::get_or_create_profile(&env, &player);
-                if profile.status != 1 {
+                if profile.status != PlayerStatus::Verified {
                     allowed = false;
                     required_action = symbol_short!("captcha");
                 }
@@ -1725,6 +2929,39 @@ impl AntiBot {
         })
     }
 
+    /// Cheap yes/no gate for other contracts, returning `(allowed, trust_score)`.
+    ///
+    /// Read-only: no auth, no profile creation and no decay checkpointing, so
+    /// callers can invoke it cross-contract before their own actions. The
+    /// result depends only on stored state and the ledger timestamp, so two
+    /// calls in the same ledger always agree. Pending inactivity decay is
+    /// included in the returned score without being persisted.
+    pub fn get_risk_summary(env: Env, player: Address) -> (bool, u32) {
+        let config: Config = match env.storage().instance().get(&DataKey::Config) {
+            Some(c) => c,
+            None => return (false, 0),
+        };
+
+        if Self::is_blacklisted(env.clone(), player.clone()) {
+            return (false, 0);
+        }
+
+        let trust_score = match Self::load_profile(&env, &player) {
+            Some(profile) => Self::pending_trust_decay(&env, &config, &profile)
+                .map(|(score, _)| score)
+                .unwrap_or(profile.trust_score),
+            None => 500, // Neutral score a new profile would start with
+        };
+
+        if Self::is_whitelisted(env.clone(), player.clone()) {
+            return (true, trust_score);
+        }
+
+        let allowed = Self::get_active_penalties(env, player) == 0
+            && trust_score >= config.trust_score_threshold;
+        (allowed, trust_score)
+    }
+
     // ========================================================================
     // ADMIN FUNCTIONS
     // ========================================================================
@@ -1774,6 +3011,54 @@ impl AntiBot {
         env.storage().instance().set(&DataKey::Config, &config);
         Ok(())
     }
+
+    /// Erases a player's tracked data: profile, activity history, suspicious
+    /// and resolved records, rate-limit windows and behavioral pattern.
+    /// Blacklist status and penalty records are kept so a purge can't be used
+    /// to shed a ban.
+    pub fn purge_player(env: Env, admin: Address, player: Address) -> Result<(), AntiBotError> {
+        Self::require_admin_address(&env, &admin)?;
+
+        let storage = env.storage().persistent();
+
+        let activity_count: u32 = storage
+            .get(&DataKey::ActivityCount(player.clone()))
+            .unwrap_or(0);
+        for i in 0..activity_count {
+            storage.remove(&DataKey::ActivityHistory(player.clone(), i));
+        }
+        storage.remove(&DataKey::ActivityCount(player.clone()));
+
+        let suspicious_count: u32 = storage
+            .get(&DataKey::SuspiciousCount(player.clone()))
+            .unwrap_or(0);
+        for i in 0..suspicious_count {
+            storage.remove(&DataKey::SuspiciousActivity(player.clone(), i));
+        }
+        storage.remove(&DataKey::SuspiciousCount(player.clone()));
+
+        let resolved_count: u32 = storage
+            .get(&DataKey::ResolvedCount(player.clone()))
+            .unwrap_or(0);
+        for i in 0..resolved_count {
+            storage.remove(&DataKey::ResolvedActivity(player.clone(), i));
+        }
+        storage.remove(&DataKey::ResolvedCount(player.clone()));
+
+        storage.remove(&DataKey::PlayerProfile(player.clone()));
+        storage.remove(&DataKey::RateLimit(player.clone()));
+        storage.remove(&DataKey::ChallengeRequests(player.clone()));
+        storage.remove(&DataKey::BehavioralPattern(player.clone()));
+        storage.remove(&DataKey::TrustDecayedAt(player.clone()));
+        Self::remove_from_index(&env, &DataKey::FlaggedPlayers, &player);
+
+        env.events().publish(
+            (symbol_short!("purge"), player),
+            (activity_count, suspicious_count),
+        );
+
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -1788,7 +3073,19 @@ pub struct PlayerAnalysis {
     pub bot_probability: u32,
     pub risk_factors: Vec<Symbol>,
     pub recommendation: Symbol,
-    pub status: u32,
+    pub status: PlayerStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PlayerStats {
+    pub total_attempts: u32,
+    pub successful_attempts: u32,
+    pub failed_attempts: u32,
+    pub avg_solve_time_ms: u64,
+    pub suspicious_count: u32,
+    pub active_penalties: u32,
+    pub bot_probability: u32,
 }
 
 #[contracttype]
@@ -1,6 +1,7 @@
 use crate::*;
 use soroban_sdk::testutils::{Address as _, Ledger as _};
-use soroban_sdk::{symbol_short, Bytes};
+use soroban_sdk::token::{Client as TokenClient, StellarAssetClient};
+use soroban_sdk::{contract, contractimpl, symbol_short, xdr::ToXdr, Bytes};
 
 // ============================================================================
 // TEST UTILITIES
@@ -13,10 +14,32 @@ fn setup_env() -> (Env, Address, Address) {
     (env, admin, player)
 }
 
+// Registers a stake token, funds `player` and requires `amount` per appeal
+fn configure_appeal_stake(
+    env: &Env,
+    client: &AntiBotClient,
+    player: &Address,
+    amount: i128,
+) -> (TokenClient<'static>, Address) {
+    let token_contract = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let token_id = token_contract.address();
+    StellarAssetClient::new(env, &token_id).mint(player, &1000);
+
+    let fee_recipient = Address::generate(env);
+    let mut config = client.get_config();
+    config.appeal_stake_token = Some(token_id.clone());
+    config.appeal_stake_amount = amount;
+    config.fee_recipient = fee_recipient.clone();
+    client.update_config(&config);
+
+    (TokenClient::new(env, &token_id), fee_recipient)
+}
+
 fn create_proof_of_work(
     env: &Env,
     player: &Address,
     challenge: &CaptchaChallenge,
+    verification_nonce: u64,
 ) -> Option<CaptchaProof> {
     // Simplified proof of work solver for testing
     // In real implementation, client would actually compute this
@@ -29,6 +52,11 @@ fn create_proof_of_work(
             ((challenge.challenge_id >> 24) & 0xFF) as u8,
         ]);
         data.append(&challenge_bytes);
+        data.append(&Bytes::from(challenge.target_prefix.clone()));
+        data.append(&player.clone().to_xdr(env));
+        for i in 0..8 {
+            data.push_back(((verification_nonce >> (i * 8)) & 0xFF) as u8);
+        }
         for i in 0..8 {
             data.push_back(((nonce >> (i * 8)) & 0xFF) as u8);
         }
@@ -44,13 +72,15 @@ fn create_proof_of_work(
             _ => 0x1000,
         };
 
-        let prefix_value: u16 = (computed_hash.get(0).unwrap_or(0) as u16) << 8
-            | (computed_hash.get(1).unwrap_or(0) as u16);
+        let target = &challenge.target_prefix;
+        let prefix_value: u16 = ((computed_hash.get(0).unwrap_or(0) ^ target.get(0).unwrap_or(0)) as u16) << 8
+            | ((computed_hash.get(1).unwrap_or(0) ^ target.get(1).unwrap_or(0)) as u16);
 
         if prefix_value < threshold {
             return Some(CaptchaProof {
                 challenge_id: challenge.challenge_id,
                 nonce,
+                verification_nonce,
                 iterations: challenge.min_iterations,
                 proof_hash: computed_hash,
             });
@@ -59,6 +89,40 @@ fn create_proof_of_work(
     None
 }
 
+// Stand-in for the reputation contract; fails until a score is configured
+#[contract]
+pub struct MockReputation;
+
+#[contractimpl]
+impl MockReputation {
+    pub fn set_score(env: Env, score: u32) {
+        env.storage().instance().set(&symbol_short!("score"), &score);
+    }
+
+    pub fn calculate_score(env: Env, _player: Address) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("score"))
+            .expect("reputation unavailable")
+    }
+}
+
+// Stand-in for a contract that gates its own action on the anti-bot summary
+#[contract]
+pub struct MockConsumer;
+
+#[contractimpl]
+impl MockConsumer {
+    pub fn gated_action(env: Env, anti_bot: Address, player: Address) -> bool {
+        let (allowed, _): (bool, u32) = env.invoke_contract(
+            &anti_bot,
+            &Symbol::new(&env, "get_risk_summary"),
+            soroban_sdk::vec![&env, player.into_val(&env)],
+        );
+        allowed
+    }
+}
+
 // ============================================================================
 // INITIALIZATION TESTS
 // ============================================================================
@@ -114,11 +178,196 @@ fn test_get_or_create_profile() {
     // Now profile should exist
     let profile = client.get_profile(&player).unwrap();
     assert_eq!(profile.address, player);
-    assert_eq!(profile.status, 0); // Unverified
+    assert_eq!(profile.status, PlayerStatus::Unverified);
     assert_eq!(profile.trust_score, 500);
     assert_eq!(profile.total_attempts, 0);
 }
 
+#[test]
+fn test_submit_appeal_with_rich_evidence() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("bot_like"),
+        &7,
+    );
+
+    let uri = String::from_str(&env, "https://example.org/appeals/recording-2024-07-01.mp4");
+    let hash: BytesN<32> = env
+        .crypto()
+        .sha256(&Bytes::from_slice(&env, b"screen recording"))
+        .into();
+    let appeal_id = client.submit_appeal(
+        &player,
+        &penalty_id,
+        &symbol_short!("wrong_acc"),
+        &uri,
+        &Some(hash.clone()),
+    );
+
+    let appeal = client.get_appeal(&appeal_id).unwrap();
+    assert_eq!(appeal.evidence_uri, uri);
+    assert_eq!(appeal.evidence_hash, Some(Bytes::from(hash)));
+}
+
+// Appeal exactly as the contract stored it before evidence URIs and stakes
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BaselineAppeal {
+    pub appeal_id: u32,
+    pub player: Address,
+    pub penalty_id: u32,
+    pub reason: Symbol,
+    pub evidence: Symbol,
+    pub submitted_at: u64,
+    pub status: AppealStatus,
+    pub reviewed_by: Option<Address>,
+    pub reviewed_at: Option<u64>,
+    pub decision_reason: Option<Symbol>,
+}
+
+#[test]
+fn test_baseline_appeal_record_readable() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Longer than a small symbol, so the characters live in a host object
+    let evidence = Symbol::new(&env, "screenshot_archive_2024");
+    env.as_contract(&contract_id, || {
+        let record = BaselineAppeal {
+            appeal_id: 1,
+            player: player.clone(),
+            penalty_id: 1,
+            reason: symbol_short!("wrong_acc"),
+            evidence,
+            submitted_at: 900,
+            status: AppealStatus::Rejected,
+            reviewed_by: Some(admin.clone()),
+            reviewed_at: Some(950),
+            decision_reason: Some(symbol_short!("no_proof")),
+        };
+        env.storage().persistent().set(&DataKey::Appeal(1), &record);
+    });
+
+    let appeal = client.get_appeal(&1).unwrap();
+    assert_eq!(appeal.player, player);
+    assert_eq!(appeal.evidence_uri, String::from_str(&env, "screenshot_archive_2024"));
+    assert_eq!(appeal.evidence_hash, None);
+    assert_eq!(appeal.status, AppealStatus::Rejected);
+    assert_eq!(appeal.reviewed_by, Some(admin));
+    assert_eq!(appeal.reviewed_at, Some(950));
+    assert_eq!(appeal.decision_reason, Some(symbol_short!("no_proof")));
+    assert_eq!(appeal.stake_token, None);
+    assert_eq!(appeal.stake_amount, 0);
+}
+
+#[test]
+fn test_legacy_appeal_readable() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("bot_like"),
+        &7,
+    );
+
+    // An appeal stored before evidence carried a URI
+    env.as_contract(&contract_id, || {
+        let legacy = LegacyAppeal {
+            appeal_id: 1,
+            player: player.clone(),
+            penalty_id,
+            reason: symbol_short!("wrong_acc"),
+            evidence: symbol_short!("evidence1"),
+            submitted_at: 1000,
+            status: AppealStatus::Pending,
+            reviewed_by: None,
+            reviewed_at: None,
+            decision_reason: None,
+        };
+        env.storage().persistent().set(&DataKey::Appeal(1), &legacy);
+        env.storage().instance().set(&DataKey::AppealCounter, &1u32);
+    });
+
+    let appeal = client.get_appeal(&1).unwrap();
+    assert_eq!(appeal.evidence_uri, String::from_str(&env, "evidence1"));
+    assert_eq!(appeal.evidence_hash, None);
+
+    // Old appeals still go through review
+    client.review_appeal(&1, &true, &symbol_short!("approved"));
+    let appeal = client.get_appeal(&1).unwrap();
+    assert_eq!(appeal.status, AppealStatus::Approved);
+    assert_eq!(appeal.evidence_uri, String::from_str(&env, "evidence1"));
+}
+
+#[test]
+fn test_legacy_profile_status_migrates() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let legacy = |status: u32| LegacyPlayerProfile {
+        address: player.clone(),
+        status,
+        trust_score: 640,
+        total_attempts: 4,
+        successful_attempts: 3,
+        failed_attempts: 1,
+        avg_solve_time_ms: 12000,
+        first_seen: 0,
+        last_activity: 0,
+        consecutive_fast_solves: 0,
+        reputation_tier: 3,
+        penalty_count: 0,
+        appeal_count: 0,
+    };
+
+    // Known raw codes decode straight into the enum
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PlayerProfile(player.clone()), &legacy(3));
+    });
+    let profile = client.get_profile(&player).unwrap();
+    assert_eq!(profile.status, PlayerStatus::Flagged);
+    assert_eq!(profile.trust_score, 640);
+
+    // Unknown codes fall back to the legacy layout
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PlayerProfile(player.clone()), &legacy(42));
+    });
+    let profile = client.get_profile(&player).unwrap();
+    assert_eq!(profile.status, PlayerStatus::Unverified);
+    assert_eq!(profile.successful_attempts, 3);
+}
+
 #[test]
 fn test_profile_trust_score_updates() {
     let (env, admin, player) = setup_env();
@@ -163,6 +412,142 @@ fn test_generate_captcha_challenge() {
     assert!(challenge.expires_at > challenge.created_at);
 }
 
+#[test]
+fn test_captcha_difficulty_scales_with_tier() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // One clean solve lifts a new player into the high-trust tier
+    let trusted = Address::generate(&env);
+    client.record_activity(&trusted, &1, &15000, &500, &true);
+    assert_eq!(client.get_reputation_tier(&trusted), 4);
+
+    // Two score reductions drop the other player to tier 0
+    for _ in 0..2 {
+        client.apply_penalty(
+            &admin,
+            &player,
+            &Some(PenaltyType::ScoreReduction),
+            &symbol_short!("bot_like"),
+            &6,
+        );
+    }
+    assert_eq!(client.get_reputation_tier(&player), 0);
+
+    let hard = client.generate_captcha_challenge(&player);
+    let easy = client.generate_captcha_challenge(&trusted);
+    assert_eq!(hard.difficulty, 4);
+    assert_eq!(easy.difficulty, 1);
+    assert!(hard.min_iterations > easy.min_iterations);
+
+    // Whitelisted players always get the easiest challenge
+    env.ledger().set_timestamp(1010);
+    client.whitelist_player(&player, &None);
+    assert_eq!(client.generate_captcha_challenge(&player).difficulty, 1);
+}
+
+#[test]
+fn test_trust_factor_caps_configurable() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    client.record_activity(&player, &1, &15000, &500, &true);
+
+    // Twenty days on, the account has maxed out the default age factor
+    env.ledger().set_timestamp(1000 + 20 * 86400);
+    let default_score = client.refresh_trust_score(&admin, &player);
+
+    let mut config = client.get_config();
+    assert_eq!(config.trust_factor_caps.account_age, 100);
+    config.trust_factor_caps.account_age = 40;
+    client.update_config(&config);
+
+    let capped_score = client.refresh_trust_score(&admin, &player);
+    assert_eq!(default_score - capped_score, 60);
+}
+
+#[test]
+fn test_detect_coordinated_attack() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let baseline = client.generate_captcha_challenge(&player).difficulty;
+
+    // Ten players solving within the same minute with near-identical times
+    for i in 0..10u64 {
+        let bot = Address::generate(&env);
+        client.record_activity(&bot, &7, &(8000 + i * 20), &500, &true);
+        env.ledger().set_timestamp(1000 + i);
+    }
+    assert!(client.detect_coordinated_attack(&7));
+
+    // The same crowd with spread-out times is not a swarm
+    for i in 0..10u64 {
+        let human = Address::generate(&env);
+        client.record_activity(&human, &8, &(8000 + i * 1500), &500, &true);
+    }
+    assert!(!client.detect_coordinated_attack(&8));
+
+    // Captcha difficulty is raised while the swarm boost lasts
+    assert_eq!(client.generate_captcha_challenge(&player).difficulty, baseline + 1);
+
+    env.ledger().set_timestamp(1000 + 3600 + 60);
+    assert!(!client.detect_coordinated_attack(&7));
+    assert_eq!(client.generate_captcha_challenge(&player).difficulty, baseline);
+}
+
+#[test]
+fn test_challenge_generation_rate_limited() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    client.generate_captcha_challenge(&player);
+
+    // A second request inside the cooldown is rejected
+    env.ledger().set_timestamp(1002);
+    let result = client.try_generate_captcha_challenge(&player);
+    assert_eq!(result.err(), Some(Ok(AntiBotError::RateLimitExceeded)));
+
+    // Other players are unaffected
+    let other = Address::generate(&env);
+    client.generate_captcha_challenge(&other);
+
+    env.ledger().set_timestamp(1005);
+    client.generate_captcha_challenge(&player);
+
+    // The per-window cap applies even with the cooldown respected
+    let mut config = client.get_config();
+    config.max_challenges_per_window = 2;
+    client.update_config(&config);
+
+    env.ledger().set_timestamp(1010);
+    let result = client.try_generate_captcha_challenge(&player);
+    assert_eq!(result.err(), Some(Ok(AntiBotError::RateLimitExceeded)));
+
+    env.ledger().set_timestamp(1000 + 3600);
+    client.generate_captcha_challenge(&player);
+}
+
 #[test]
 fn test_verify_captcha_proof() {
     let (env, admin, player) = setup_env();
@@ -181,11 +566,74 @@ fn test_verify_captcha_proof() {
     let challenge = client.generate_captcha_challenge(&player);
     
     // Create a valid proof
-    let proof = create_proof_of_work(&env, &player, &challenge);
+    let proof = create_proof_of_work(&env, &player, &challenge, 0);
     assert!(proof.is_some());
-    
-    // Note: In actual tests, the PoW verification might fail due to hash mismatch
-    // This test documents the expected behavior
+
+    assert!(client.verify_captcha_proof(&player, &proof.unwrap()));
+    assert_eq!(client.get_profile(&player).unwrap().status, PlayerStatus::Verified);
+}
+
+#[test]
+fn test_captcha_proof_replay_rejected() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let mut config = client.get_config();
+    config.captcha_difficulty = 1;
+    client.update_config(&config);
+
+    let challenge = client.generate_captcha_challenge(&player);
+    let proof = create_proof_of_work(&env, &player, &challenge, 0).unwrap();
+
+    assert!(client.verify_captcha_proof(&player, &proof));
+    assert_eq!(client.get_verification_nonce(&player), 1);
+
+    // The accepted proof carries a stale nonce now
+    let result = client.try_verify_captcha_proof(&player, &proof);
+    assert_eq!(result, Err(Ok(AntiBotError::InvalidProof)));
+
+    // Bumping the nonce without redoing the work breaks the hash
+    let mut bumped = proof.clone();
+    bumped.verification_nonce = 1;
+    let result = client.try_verify_captcha_proof(&player, &bumped);
+    assert_eq!(result, Err(Ok(AntiBotError::VerificationFailed)));
+
+    // A fresh proof against the current nonce is accepted
+    env.ledger().set_timestamp(1010);
+    let challenge = client.generate_captcha_challenge(&player);
+    let proof = create_proof_of_work(&env, &player, &challenge, 1).unwrap();
+    assert!(client.verify_captcha_proof(&player, &proof));
+    assert_eq!(client.get_verification_nonce(&player), 2);
+}
+
+#[test]
+fn test_captcha_proof_bound_to_player() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let mut config = client.get_config();
+    config.captcha_difficulty = 1;
+    client.update_config(&config);
+
+    let challenge = client.generate_captcha_challenge(&player);
+    let proof = create_proof_of_work(&env, &player, &challenge, 0).unwrap();
+
+    // Another account cannot replay player A's solution
+    let other = Address::generate(&env);
+    let result = client.try_verify_captcha_proof(&other, &proof);
+    assert_eq!(result, Err(Ok(AntiBotError::VerificationFailed)));
+
+    assert!(client.verify_captcha_proof(&player, &proof));
 }
 
 #[test]
@@ -208,6 +656,7 @@ fn test_expired_challenge_fails() {
     let proof = CaptchaProof {
         challenge_id: challenge.challenge_id,
         nonce: 0,
+        verification_nonce: 0,
         iterations: 100,
         proof_hash: dummy_hash,
     };
@@ -261,6 +710,39 @@ fn test_rate_limit_window_resets() {
     client.check_rate_limit(&player);
 }
 
+#[test]
+fn test_rate_limit_blocks_boundary_burst() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // One attempt opens the window, then a burst right before it would have reset
+    client.check_rate_limit(&player);
+    env.ledger().set_timestamp(1299);
+    for _ in 0..9 {
+        client.check_rate_limit(&player);
+    }
+
+    // A fixed window would reset here and allow 10 more; the sliding window
+    // only drops the attempt made at 1000
+    env.ledger().set_timestamp(1301);
+    client.check_rate_limit(&player);
+    let result = client.try_check_rate_limit(&player);
+    assert_eq!(result, Err(Ok(AntiBotError::RateLimitExceeded)));
+
+    let status = client.get_rate_limit_status(&player);
+    assert_eq!(status.attempt_count, 10);
+    assert_eq!(status.window_start, 1299);
+
+    // Once the burst ages out the player can attempt again
+    env.ledger().set_timestamp(1599);
+    client.check_rate_limit(&player);
+}
+
 #[test]
 fn test_get_rate_limit_status() {
     let (env, admin, player) = setup_env();
@@ -325,7 +807,7 @@ fn test_too_fast_solve_detected() {
 }
 
 #[test]
-fn test_behavioral_pattern_tracking() {
+fn test_personal_solve_time_outlier_flagged() {
     let (env, admin, player) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
@@ -334,18 +816,29 @@ fn test_behavioral_pattern_tracking() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Record multiple activities
-    for i in 0..5 {
-        env.ledger().set_timestamp(1000 + i as u64 * 60); // 1 minute intervals
-        client.record_activity(&player, &i, &10000, &500, &true);
+    // A human-paced history around 30 seconds
+    for (i, solve_time) in [29000u64, 31000, 30500, 28500, 30000, 31500].iter().enumerate() {
+        env.ledger().set_timestamp(1000 + i as u64 * 600);
+        client.record_activity(&player, &1, solve_time, &(450 + i as u64 * 80), &true);
     }
+    assert_eq!(client.get_suspicious_activities(&player, &0, &10).len(), 0);
 
-    let pattern = client.get_behavioral_pattern(&player).unwrap();
-    assert!(pattern.time_distribution.len() > 0);
+    // A normal solve within the player's spread is fine
+    env.ledger().set_timestamp(5000);
+    client.record_activity(&player, &1, &27500, &500, &true);
+    assert_eq!(client.get_suspicious_activities(&player, &0, &10).len(), 0);
+
+    // 12 seconds is well above the global threshold but far outside this player's history
+    env.ledger().set_timestamp(5600);
+    client.record_activity(&player, &1, &12000, &500, &true);
+
+    let activities = client.get_suspicious_activities(&player, &0, &10);
+    assert_eq!(activities.len(), 1);
+    assert_eq!(activities.get(0).unwrap().evidence, symbol_short!("outlier"));
 }
 
 #[test]
-fn test_analyze_player() {
+fn test_fast_solve_streak_resets_after_idle() {
     let (env, admin, player) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
@@ -354,22 +847,25 @@ fn test_analyze_player() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Create a good player profile
-    for i in 0..5 {
-        env.ledger().set_timestamp(1000 + i as u64 * 600); // 10 minute intervals
-        client.record_activity(&player, &i, &15000, &(520 + i as u64), &true);
-    }
+    let config = client.get_config();
+    let fast_time = config.min_solve_time_threshold_ms / 2;
 
-    let analysis = client.analyze_player(&player);
-    assert!(analysis.bot_probability < 50); // Should be low for normal behavior
-}
+    client.record_activity(&player, &1, &fast_time, &500, &true);
+    env.ledger().set_timestamp(1060);
+    client.record_activity(&player, &1, &fast_time, &520, &true);
+    assert_eq!(client.get_profile(&player).unwrap().consecutive_fast_solves, 2);
 
-// ============================================================================
-// SUSPICIOUS ACTIVITY TESTS
-// ============================================================================
+    // After a long break the old streak no longer counts
+    env.ledger().set_timestamp(1060 + config.fast_streak_reset_seconds + 1);
+    client.record_activity(&player, &1, &fast_time, &540, &true);
+
+    let profile = client.get_profile(&player).unwrap();
+    assert_eq!(profile.consecutive_fast_solves, 1);
+    assert_ne!(profile.status, PlayerStatus::Flagged);
+}
 
 #[test]
-fn test_flag_player() {
+fn test_behavioral_pattern_tracking() {
     let (env, admin, player) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
@@ -378,14 +874,162 @@ fn test_flag_player() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Create profile first
-    client.generate_captcha_challenge(&player);
+    // Record multiple activities
+    for i in 0..5 {
+        env.ledger().set_timestamp(1000 + i as u64 * 60); // 1 minute intervals
+        client.record_activity(&player, &i, &10000, &500, &true);
+    }
 
-    // Flag the player
-    client.flag_player(&player, &symbol_short!("suspct"), &8);
+    let pattern = client.get_behavioral_pattern(&player).unwrap();
+    assert!(pattern.time_distribution.len() > 0);
+}
+
+#[test]
+fn test_gas_pattern_variance() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let human = Address::generate(&env);
+    let human_gas = [480u64, 610, 395, 720, 530];
+    for i in 0..5u32 {
+        env.ledger().set_timestamp(1000 + i as u64 * 600);
+        client.record_activity(&player, &i, &15000, &777, &true);
+        client.record_activity(&human, &i, &15000, &human_gas[i as usize], &true);
+    }
+
+    // Identical gas on every call has no spread at all
+    let bot_pattern = client.get_behavioral_pattern(&player).unwrap();
+    assert_eq!(bot_pattern.gas_pattern_variance, 0);
+    assert_eq!(bot_pattern.gas_distribution.len(), 5);
+    assert!(client.analyze_player(&player).risk_factors.contains(&symbol_short!("gas_patt")));
+
+    let human_pattern = client.get_behavioral_pattern(&human).unwrap();
+    assert!(human_pattern.gas_pattern_variance > 200);
+    assert!(!client.analyze_player(&human).risk_factors.contains(&symbol_short!("gas_patt")));
+}
+
+#[test]
+fn test_analyze_player() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Create a good player profile
+    for i in 0..5 {
+        env.ledger().set_timestamp(1000 + i as u64 * 600); // 10 minute intervals
+        client.record_activity(&player, &i, &15000, &(420 + (i as u64 * 95) % 300), &true);
+    }
+
+    let analysis = client.analyze_player(&player);
+    assert!(analysis.bot_probability < 50); // Should be low for normal behavior
+}
+
+#[test]
+fn test_risk_weights_change_recommendation() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Three quick-but-not-instant solves with irregular spacing: only the
+    // fast-solve factor fires
+    for (i, ts) in [1000u64, 1100, 2200].iter().enumerate() {
+        env.ledger().set_timestamp(*ts);
+        client.record_activity(&player, &(i as u32), &3000, &(400 + i as u64 * 150), &true);
+    }
+
+    let analysis = client.analyze_player(&player);
+    assert_eq!(analysis.risk_factors, Vec::from_array(&env, [symbol_short!("fast_slv")]));
+    assert_eq!(analysis.bot_probability, 30);
+    assert_eq!(analysis.recommendation, symbol_short!("allow"));
+
+    let mut config = client.get_config();
+    config.risk_weights.fast_solves = 45;
+    client.update_config(&config);
+
+    let analysis = client.analyze_player(&player);
+    assert_eq!(analysis.bot_probability, 45);
+    assert_eq!(analysis.recommendation, symbol_short!("verify"));
+
+    // The probability stays capped at 100
+    config.risk_weights.fast_solves = 250;
+    client.update_config(&config);
+    let analysis = client.analyze_player(&player);
+    assert_eq!(analysis.bot_probability, 100);
+    assert_eq!(analysis.recommendation, symbol_short!("block"));
+}
+
+#[test]
+fn test_get_player_stats() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    client.set_time_window(&1, &5000, &60000, &1000, &100_000, &1000);
+
+    client.record_activity(&player, &1, &10000, &480, &true);
+    env.ledger().set_timestamp(1700);
+    client.record_activity(&player, &2, &20000, &615, &true);
+    env.ledger().set_timestamp(1900);
+    client.record_activity(&player, &3, &30000, &390, &false);
+
+    // One too-fast submission is logged as suspicious
+    client.validate_submission_time(&player, &1, &1000);
+    client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("violation"),
+        &5,
+    );
+
+    let stats = client.get_player_stats(&player);
+    assert_eq!(stats.total_attempts, 3);
+    assert_eq!(stats.successful_attempts, 2);
+    assert_eq!(stats.failed_attempts, 1);
+    assert_eq!(stats.avg_solve_time_ms, 15000);
+    assert_eq!(stats.suspicious_count, 1);
+    assert_eq!(stats.active_penalties, 1);
+    assert_eq!(stats.bot_probability, client.analyze_player(&player).bot_probability);
+}
+
+// ============================================================================
+// SUSPICIOUS ACTIVITY TESTS
+// ============================================================================
+
+#[test]
+fn test_flag_player() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Create profile first
+    client.generate_captcha_challenge(&player);
+
+    // Flag the player
+    client.flag_player(&admin, &player, &symbol_short!("suspct"), &8);
 
     let profile = client.get_profile(&player).unwrap();
-    assert_eq!(profile.status, 3); // Flagged
+    assert_eq!(profile.status, PlayerStatus::Flagged);
 }
 
 #[test]
@@ -400,13 +1044,173 @@ fn test_unflag_player() {
 
     // Create and flag player
     client.generate_captcha_challenge(&player);
-    client.flag_player(&player, &symbol_short!("suspct"), &8);
+    client.flag_player(&admin, &player, &symbol_short!("suspct"), &8);
 
     // Unflag
     client.unflag_player(&player);
 
     let profile = client.get_profile(&player).unwrap();
-    assert_eq!(profile.status, 1); // Verified
+    assert_eq!(profile.status, PlayerStatus::Verified);
+}
+
+#[test]
+fn test_unflag_suspicious_player() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    client.generate_captcha_challenge(&player);
+    let mut profile = client.get_profile(&player).unwrap();
+    profile.status = PlayerStatus::Suspicious;
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PlayerProfile(player.clone()), &profile);
+    });
+
+    client.unflag_player(&player);
+    assert_eq!(client.get_profile(&player).unwrap().status, PlayerStatus::Verified);
+}
+
+#[test]
+fn test_unflag_rejects_invalid_transitions() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Nothing to clear on a fresh or already verified player
+    assert_eq!(
+        client.try_unflag_player(&player),
+        Err(Ok(AntiBotError::InvalidStatusTransition))
+    );
+
+    // Penalized players must go through penalty removal
+    let penalized = Address::generate(&env);
+    client.apply_penalty(
+        &admin,
+        &penalized,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("bot_like"),
+        &7,
+    );
+    assert_eq!(client.get_profile(&penalized).unwrap().status, PlayerStatus::Penalized);
+    assert_eq!(
+        client.try_unflag_player(&penalized),
+        Err(Ok(AntiBotError::InvalidStatusTransition))
+    );
+
+    // Blacklisted players must go through blacklist removal
+    let blacklisted = Address::generate(&env);
+    client.generate_captcha_challenge(&blacklisted);
+    let mut profile = client.get_profile(&blacklisted).unwrap();
+    profile.status = PlayerStatus::Blacklisted;
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PlayerProfile(blacklisted.clone()), &profile);
+    });
+    assert_eq!(
+        client.try_unflag_player(&blacklisted),
+        Err(Ok(AntiBotError::InvalidStatusTransition))
+    );
+    assert_eq!(client.get_profile(&blacklisted).unwrap().status, PlayerStatus::Blacklisted);
+}
+
+#[test]
+fn test_old_suspicious_activity_stops_counting() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    client.set_time_window(&1, &5000, &60000, &1000, &100_000_000, &1000);
+
+    let repeat = Address::generate(&env);
+    for p in [&player, &repeat] {
+        // Five high-severity too-fast submissions trip the flag
+        for _ in 0..5 {
+            client.validate_submission_time(p, &1, &1000);
+        }
+        assert_eq!(client.get_profile(p).unwrap().status, PlayerStatus::Flagged);
+        client.unflag_player(p);
+    }
+
+    // Inside the lookback window one more infraction re-flags immediately
+    client.validate_submission_time(&repeat, &1, &1000);
+    assert_eq!(client.get_profile(&repeat).unwrap().status, PlayerStatus::Flagged);
+
+    // Past the 30 day window the old infractions no longer count
+    env.ledger().set_timestamp(1000 + 31 * 86400);
+    client.validate_submission_time(&player, &1, &1000);
+    assert_eq!(client.get_profile(&player).unwrap().status, PlayerStatus::Verified);
+
+    // History is still readable
+    assert_eq!(client.get_suspicious_activities(&player, &0, &10).len(), 6);
+}
+
+#[test]
+fn test_flag_players_batch() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let verifier = Address::generate(&env);
+    client.add_verifier(&verifier);
+
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    let banned = Address::generate(&env);
+    client.blacklist_player(&banned, &symbol_short!("conf_bot"));
+    let banned_status = client.get_profile(&banned).unwrap().status;
+
+    let players = Vec::from_array(&env, [player.clone(), second.clone(), third.clone(), banned.clone()]);
+    let flagged = client.flag_players(&verifier, &players, &symbol_short!("ring"), &6);
+
+    // The blacklisted account is skipped rather than aborting the batch
+    assert_eq!(flagged, 3);
+    for p in [&player, &second, &third] {
+        assert_eq!(client.get_profile(p).unwrap().status, PlayerStatus::Flagged);
+    }
+    assert_eq!(client.get_profile(&banned).unwrap().status, banned_status);
+
+    let unflagged = client.unflag_players(&players);
+    assert_eq!(unflagged, 3);
+    for p in [&player, &second, &third] {
+        assert_eq!(client.get_profile(p).unwrap().status, PlayerStatus::Verified);
+    }
+    assert_eq!(client.get_profile(&banned).unwrap().status, banned_status);
+}
+
+#[test]
+fn test_flag_players_batch_capped() {
+    let (env, admin, _) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let mut players = Vec::new(&env);
+    for _ in 0..51 {
+        players.push_back(Address::generate(&env));
+    }
+
+    let result = client.try_flag_players(&admin, &players, &symbol_short!("ring"), &6);
+    assert_eq!(result, Err(Ok(AntiBotError::BatchTooLarge)));
 }
 
 #[test]
@@ -433,6 +1237,69 @@ fn test_get_suspicious_activities() {
     assert!(activities.len() > 0);
 }
 
+#[test]
+fn test_flag_player_by_registered_verifier() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let verifier = Address::generate(&env);
+    client.add_verifier(&verifier);
+
+    client.flag_player(&verifier, &player, &symbol_short!("suspct"), &8);
+
+    let profile = client.get_profile(&player).unwrap();
+    assert_eq!(profile.status, PlayerStatus::Flagged);
+}
+
+#[test]
+fn test_flag_player_by_admin() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Admin is accepted without being in the verifiers list
+    assert!(client.get_config().verifiers.is_empty());
+    client.flag_player(&admin, &player, &symbol_short!("suspct"), &8);
+
+    let profile = client.get_profile(&player).unwrap();
+    assert_eq!(profile.status, PlayerStatus::Flagged);
+}
+
+#[test]
+fn test_non_verifier_rejected() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let outsider = Address::generate(&env);
+
+    let result = client.try_flag_player(&outsider, &player, &symbol_short!("suspct"), &8);
+    assert_eq!(result, Err(Ok(AntiBotError::Unauthorized)));
+
+    let result = client.try_apply_penalty(
+        &outsider,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("bot_like"),
+        &6,
+    );
+    assert_eq!(result, Err(Ok(AntiBotError::Unauthorized)));
+    assert!(client.get_profile(&player).is_none());
+}
+
 // ============================================================================
 // TIME WINDOW TESTS
 // ============================================================================
@@ -556,12 +1423,8 @@ fn test_reputation_tier_calculation() {
     assert!(new_tier >= 3); // Should be Good or higher
 }
 
-// ============================================================================
-// PENALTY SYSTEM TESTS
-// ============================================================================
-
 #[test]
-fn test_apply_penalty() {
+fn test_trust_score_decays_when_idle() {
     let (env, admin, player) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
@@ -570,26 +1433,31 @@ fn test_apply_penalty() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Create profile first
-    client.generate_captcha_challenge(&player);
-
-    let penalty_id = client.apply_penalty(
-        &player,
-        &PenaltyType::TemporaryBan,
-        &symbol_short!("bot_like"),
-        &6,
-    );
-
-    assert_eq!(penalty_id, 1);
-
-    let penalty = client.get_penalty(&penalty_id).unwrap();
-    assert!(penalty.active);
-    assert_eq!(penalty.penalty_type, PenaltyType::TemporaryBan);
-    assert_eq!(penalty.severity, 6);
+    for i in 0..5 {
+        env.ledger().set_timestamp(1000 + i as u64 * 86400);
+        client.record_activity(&player, &i, &15000, &500, &true);
+    }
+    let earned = client.get_trust_score(&player);
+    assert!(earned > 600);
+
+    // 30 idle days at the default 10 points per day
+    let last_activity = client.get_profile(&player).unwrap().last_activity;
+    env.ledger().set_timestamp(last_activity + 30 * 86400);
+    let decayed = client.decay_trust_score(&player);
+    assert_eq!(decayed, (earned - 300).max(500));
+    assert!(decayed < earned);
+
+    // Decay is checkpointed, so reading again the same day changes nothing
+    assert_eq!(client.decay_trust_score(&player), decayed);
+    assert_eq!(client.get_profile(&player).unwrap().trust_score, decayed);
+
+    // Long dormancy bottoms out at the neutral floor
+    env.ledger().set_timestamp(last_activity + 365 * 86400);
+    assert_eq!(client.decay_trust_score(&player), 500);
 }
 
 #[test]
-fn test_remove_penalty() {
+fn test_trust_decay_rate_configurable() {
     let (env, admin, player) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
@@ -598,14 +1466,297 @@ fn test_remove_penalty() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Create profile and penalty
-    client.generate_captcha_challenge(&player);
-    let penalty_id = client.apply_penalty(
-        &player,
-        &PenaltyType::TemporaryBan,
-        &symbol_short!("mistake"),
-        &4,
-    );
+    let mut config = client.get_config();
+    config.trust_decay_per_day = 0;
+    client.update_config(&config);
+
+    for i in 0..5 {
+        env.ledger().set_timestamp(1000 + i as u64 * 86400);
+        client.record_activity(&player, &i, &15000, &500, &true);
+    }
+    let earned = client.get_trust_score(&player);
+
+    env.ledger().set_timestamp(1000 + 60 * 86400);
+    assert_eq!(client.decay_trust_score(&player), earned);
+}
+
+#[test]
+fn test_trust_score_blends_external_reputation() {
+    let (env, admin, player) = setup_env();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    // Local-only baseline
+    let local_id = env.register_contract(None, AntiBot);
+    let local_client = AntiBotClient::new(&env, &local_id);
+    local_client.initialize(&admin);
+    local_client.record_activity(&player, &1, &15000, &500, &true);
+    let local_score = local_client.get_trust_score(&player);
+
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let mut config = client.get_config();
+    let reputation_id = env.register_contract(None, MockReputation);
+    MockReputationClient::new(&env, &reputation_id).set_score(&900);
+    config.reputation_contract = Some(reputation_id);
+    client.update_config(&config);
+
+    client.record_activity(&player, &1, &15000, &500, &true);
+
+    // Default weighting is 70% local, 30% external
+    let expected = (local_score * 70 + 900 * 30) / 100;
+    assert_eq!(client.get_trust_score(&player), expected);
+    assert!(expected > local_score);
+}
+
+#[test]
+fn test_trust_score_falls_back_when_reputation_fails() {
+    let (env, admin, player) = setup_env();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let local_id = env.register_contract(None, AntiBot);
+    let local_client = AntiBotClient::new(&env, &local_id);
+    local_client.initialize(&admin);
+    local_client.record_activity(&player, &1, &15000, &500, &true);
+
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let mut config = client.get_config();
+    config.reputation_contract = Some(env.register_contract(None, MockReputation));
+    client.update_config(&config);
+
+    client.record_activity(&player, &1, &15000, &500, &true);
+    assert_eq!(client.get_trust_score(&player), local_client.get_trust_score(&player));
+}
+
+#[test]
+fn test_refresh_trust_score_after_penalty_removed() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    client.record_activity(&player, &1, &15000, &500, &true);
+    let clean_score = client.get_trust_score(&player);
+
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("mistake"),
+        &4,
+    );
+    let penalized_score = client.refresh_trust_score(&admin, &player);
+    assert_eq!(penalized_score, clean_score - 50);
+    assert_eq!(client.get_trust_score(&player), penalized_score);
+
+    client.remove_penalty(&penalty_id);
+    assert_eq!(client.get_trust_score(&player), penalized_score);
+
+    // Refreshing picks up the removal without needing new activity
+    assert_eq!(client.refresh_trust_score(&admin, &player), clean_score);
+    assert_eq!(client.get_trust_score(&player), clean_score);
+
+    let outsider = Address::generate(&env);
+    let result = client.try_refresh_trust_score(&outsider, &player);
+    assert_eq!(result, Err(Ok(AntiBotError::Unauthorized)));
+}
+
+// ============================================================================
+// PENALTY SYSTEM TESTS
+// ============================================================================
+
+#[test]
+fn test_apply_penalty() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Create profile first
+    client.generate_captcha_challenge(&player);
+
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("bot_like"),
+        &6,
+    );
+
+    assert_eq!(penalty_id, 1);
+
+    let penalty = client.get_penalty(&penalty_id).unwrap();
+    assert!(penalty.active);
+    assert_eq!(penalty.penalty_type, PenaltyType::TemporaryBan);
+    assert_eq!(penalty.severity, 6);
+}
+
+#[test]
+fn test_penalty_ladder_escalates() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let verifier = Address::generate(&env);
+    client.add_verifier(&verifier);
+
+    let ladder = [
+        PenaltyType::Warning,
+        PenaltyType::TemporaryBan,
+        PenaltyType::ExtendedBan,
+        PenaltyType::PermanentBan,
+    ];
+    for expected in ladder.iter() {
+        assert_eq!(client.preview_penalty(&player), *expected);
+        let penalty_id = client.apply_penalty(&verifier, &player, &None, &symbol_short!("repeat"), &5);
+        assert_eq!(client.get_penalty(&penalty_id).unwrap().penalty_type, *expected);
+    }
+
+    // The top rung is sticky and permanently bans the player
+    assert_eq!(client.preview_penalty(&player), PenaltyType::PermanentBan);
+    assert!(client.is_blacklisted(&player));
+}
+
+#[test]
+fn test_penalty_ladder_thresholds_configurable() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let mut config = client.get_config();
+    config.penalty_ladder = PenaltyLadder {
+        temporary_ban_after: 2,
+        extended_ban_after: 4,
+        permanent_ban_after: 10,
+    };
+    client.update_config(&config);
+
+    for _ in 0..2 {
+        client.apply_penalty(&admin, &player, &None, &symbol_short!("repeat"), &3);
+    }
+    assert_eq!(client.preview_penalty(&player), PenaltyType::TemporaryBan);
+
+    for _ in 0..2 {
+        client.apply_penalty(&admin, &player, &None, &symbol_short!("repeat"), &3);
+    }
+    assert_eq!(client.preview_penalty(&player), PenaltyType::ExtendedBan);
+}
+
+#[test]
+fn test_only_admin_forces_penalty_type() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let verifier = Address::generate(&env);
+    client.add_verifier(&verifier);
+
+    let result = client.try_apply_penalty(
+        &verifier,
+        &player,
+        &Some(PenaltyType::PermanentBan),
+        &symbol_short!("bot_like"),
+        &9,
+    );
+    assert_eq!(result, Err(Ok(AntiBotError::Unauthorized)));
+
+    // Admin can skip the ladder for a first offense
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::ExtendedBan),
+        &symbol_short!("bot_like"),
+        &9,
+    );
+    assert_eq!(client.get_penalty(&penalty_id).unwrap().penalty_type, PenaltyType::ExtendedBan);
+}
+
+#[test]
+fn test_penalty_durations_configurable() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Defaults match the previous fixed schedule
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::ExtendedBan),
+        &symbol_short!("violation"),
+        &5,
+    );
+    assert_eq!(client.get_penalty(&penalty_id).unwrap().expires_at, 1000 + 7 * 24 * 3600);
+
+    let mut config = client.get_config();
+    config.temporary_ban_hours = 2;
+    config.extended_ban_hours = 48;
+    config.verification_required_hours = 6;
+    client.update_config(&config);
+
+    let cases = [
+        (PenaltyType::TemporaryBan, 2u64),
+        (PenaltyType::ExtendedBan, 48),
+        (PenaltyType::VerificationRequired, 6),
+    ];
+    for (penalty_type, hours) in cases.iter() {
+        let penalty_id = client.apply_penalty(
+            &admin,
+            &player,
+            &Some(penalty_type.clone()),
+            &symbol_short!("violation"),
+            &5,
+        );
+        assert_eq!(client.get_penalty(&penalty_id).unwrap().expires_at, 1000 + hours * 3600);
+    }
+}
+
+#[test]
+fn test_remove_penalty() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Create profile and penalty
+    client.generate_captcha_challenge(&player);
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("mistake"),
+        &4,
+    );
 
     // Remove penalty
     client.remove_penalty(&penalty_id);
@@ -631,8 +1782,9 @@ fn test_get_active_penalties() {
 
     // Add penalty
     client.apply_penalty(
+        &admin,
         &player,
-        &PenaltyType::TemporaryBan,
+        &Some(PenaltyType::TemporaryBan),
         &symbol_short!("violation"),
         &5,
     );
@@ -656,8 +1808,9 @@ fn test_check_penalty_status() {
     // Add penalty
     client.generate_captcha_challenge(&player);
     client.apply_penalty(
+        &admin,
         &player,
-        &PenaltyType::TemporaryBan,
+        &Some(PenaltyType::TemporaryBan),
         &symbol_short!("violation"),
         &5,
     );
@@ -683,8 +1836,9 @@ fn test_submit_appeal() {
     // Create profile and penalty
     client.generate_captcha_challenge(&player);
     let penalty_id = client.apply_penalty(
+        &admin,
         &player,
-        &PenaltyType::TemporaryBan,
+        &Some(PenaltyType::TemporaryBan),
         &symbol_short!("mistake"),
         &4,
     );
@@ -693,7 +1847,8 @@ fn test_submit_appeal() {
         &player,
         &penalty_id,
         &symbol_short!("wrong_acc"),
-        &symbol_short!("evidence1"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
     );
 
     assert_eq!(appeal_id, 1);
@@ -704,7 +1859,234 @@ fn test_submit_appeal() {
 }
 
 #[test]
-fn test_review_appeal_approve() {
+fn test_review_appeal_approve() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Create profile and penalty
+    client.generate_captcha_challenge(&player);
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("mistake"),
+        &4,
+    );
+
+    let appeal_id = client.submit_appeal(
+        &player,
+        &penalty_id,
+        &symbol_short!("wrong_acc"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
+    );
+
+    // Admin approves appeal
+    client.review_appeal(&appeal_id, &true, &symbol_short!("approved"));
+
+    let appeal = client.get_appeal(&appeal_id).unwrap();
+    assert!(matches!(appeal.status, AppealStatus::Approved));
+
+    let penalty = client.get_penalty(&penalty_id).unwrap();
+    assert!(!penalty.active);
+}
+
+#[test]
+fn test_review_appeal_reject() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Create profile and penalty
+    client.generate_captcha_challenge(&player);
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("violation"),
+        &7,
+    );
+
+    let appeal_id = client.submit_appeal(
+        &player,
+        &penalty_id,
+        &symbol_short!("explain"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
+    );
+
+    // Admin rejects appeal
+    client.review_appeal(&appeal_id, &false, &symbol_short!("insuff"));
+
+    let appeal = client.get_appeal(&appeal_id).unwrap();
+    assert!(matches!(appeal.status, AppealStatus::Rejected));
+
+    // Penalty should still be active
+    let penalty = client.get_penalty(&penalty_id).unwrap();
+    assert!(penalty.active);
+}
+
+#[test]
+fn test_approved_appeal_resolves_suspicious_history() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    client.set_time_window(&1, &5000, &60000, &1000, &100_000, &1000);
+
+    for _ in 0..5 {
+        client.validate_submission_time(&player, &1, &1000);
+    }
+    assert_eq!(client.get_profile(&player).unwrap().status, PlayerStatus::Flagged);
+
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("too_fast"),
+        &7,
+    );
+    let appeal_id = client.submit_appeal(
+        &player,
+        &penalty_id,
+        &symbol_short!("lag"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
+    );
+
+    env.ledger().set_timestamp(2000);
+    client.review_appeal(&appeal_id, &true, &symbol_short!("approved"));
+
+    // The records behind the penalty move to the resolved log
+    assert_eq!(client.get_suspicious_activities(&player, &0, &10).len(), 0);
+    assert_eq!(client.get_resolved_activities(&player, &0, &10).len(), 5);
+
+    // A single new infraction no longer re-flags the player
+    client.validate_submission_time(&player, &1, &1000);
+    assert_eq!(client.get_profile(&player).unwrap().status, PlayerStatus::Verified);
+    assert_eq!(client.get_suspicious_activities(&player, &0, &10).len(), 1);
+}
+
+#[test]
+fn test_appeal_stake_returned_on_approval() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    let (token, fee_recipient) = configure_appeal_stake(&env, &client, &player, 100);
+
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("mistake"),
+        &4,
+    );
+    let appeal_id = client.submit_appeal(
+        &player,
+        &penalty_id,
+        &symbol_short!("wrong_acc"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
+    );
+
+    // Stake sits in escrow while the appeal is pending
+    assert_eq!(token.balance(&player), 900);
+    assert_eq!(token.balance(&contract_id), 100);
+    assert_eq!(client.get_appeal(&appeal_id).unwrap().stake_amount, 100);
+
+    client.review_appeal(&appeal_id, &true, &symbol_short!("approved"));
+
+    assert_eq!(token.balance(&player), 1000);
+    assert_eq!(token.balance(&contract_id), 0);
+    assert_eq!(token.balance(&fee_recipient), 0);
+}
+
+#[test]
+fn test_appeal_stake_forfeited_on_rejection() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    let (token, fee_recipient) = configure_appeal_stake(&env, &client, &player, 100);
+
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("violation"),
+        &7,
+    );
+    let appeal_id = client.submit_appeal(
+        &player,
+        &penalty_id,
+        &symbol_short!("explain"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
+    );
+    assert_eq!(token.balance(&contract_id), 100);
+
+    client.review_appeal(&appeal_id, &false, &symbol_short!("insuff"));
+
+    assert_eq!(token.balance(&player), 900);
+    assert_eq!(token.balance(&contract_id), 0);
+    assert_eq!(token.balance(&fee_recipient), 100);
+}
+
+#[test]
+fn test_appeal_period_expired() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    // Create profile and penalty
+    client.generate_captcha_challenge(&player);
+    let penalty_id = client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("violation"),
+        &5,
+    );
+
+    // Move time forward past appeal period (7 days + 1 second)
+    env.ledger().set_timestamp(1000 + (7 * 86400) + 1);
+
+    let result = client.submit_appeal(
+        &player,
+        &penalty_id,
+        &symbol_short!("explain"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
+    );
+    // Appeal period expired - function returns u32, should not be 0 on success
+    // but would panic on error. For now just verify we can call it.
+}
+
+#[test]
+fn test_expire_stale_appeal() {
     let (env, admin, player) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
@@ -713,35 +2095,42 @@ fn test_review_appeal_approve() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Create profile and penalty
-    client.generate_captcha_challenge(&player);
     let penalty_id = client.apply_penalty(
+        &admin,
         &player,
-        &PenaltyType::TemporaryBan,
-        &symbol_short!("mistake"),
-        &4,
+        &Some(PenaltyType::PermanentBan),
+        &symbol_short!("violation"),
+        &5,
     );
-
     let appeal_id = client.submit_appeal(
         &player,
         &penalty_id,
-        &symbol_short!("wrong_acc"),
-        &symbol_short!("evidence1"),
+        &symbol_short!("explain"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
     );
+    assert_eq!(client.get_profile(&player).unwrap().status, PlayerStatus::AppealPending);
 
-    // Admin approves appeal
-    client.review_appeal(&appeal_id, &true, &symbol_short!("approved"));
+    // Still inside the 14 day review window
+    env.ledger().set_timestamp(1000 + 14 * 86400);
+    let result = client.try_expire_appeal(&appeal_id);
+    assert_eq!(result, Err(Ok(AntiBotError::ReviewWindowOpen)));
+
+    env.ledger().set_timestamp(1000 + 14 * 86400 + 1);
+    client.expire_appeal(&appeal_id);
 
     let appeal = client.get_appeal(&appeal_id).unwrap();
-    assert!(matches!(appeal.status, AppealStatus::Approved));
+    assert_eq!(appeal.status, AppealStatus::Rejected);
+    assert_eq!(appeal.decision_reason, Some(symbol_short!("timeout")));
+    assert!(appeal.reviewed_by.is_none());
 
-    let penalty = client.get_penalty(&penalty_id).unwrap();
-    assert!(!penalty.active);
+    // The ban outlives the appeal, so the player drops back to Penalized
+    assert_eq!(client.get_profile(&player).unwrap().status, PlayerStatus::Penalized);
 }
 
 #[test]
-fn test_review_appeal_reject() {
-    let (env, admin, player) = setup_env();
+fn test_pending_appeals_queue() {
+    let (env, admin, _) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
 
@@ -749,35 +2138,45 @@ fn test_review_appeal_reject() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Create profile and penalty
-    client.generate_captcha_challenge(&player);
-    let penalty_id = client.apply_penalty(
-        &player,
-        &PenaltyType::TemporaryBan,
-        &symbol_short!("violation"),
-        &7,
-    );
+    let mut appeal_ids = Vec::new(&env);
+    for _ in 0..3 {
+        let player = Address::generate(&env);
+        let penalty_id = client.apply_penalty(
+            &admin,
+            &player,
+            &Some(PenaltyType::PermanentBan),
+            &symbol_short!("violation"),
+            &5,
+        );
+        appeal_ids.push_back(client.submit_appeal(
+            &player,
+            &penalty_id,
+            &symbol_short!("explain"),
+            &String::from_str(&env, "ipfs://evidence1"),
+            &None,
+        ));
+    }
+    assert_eq!(client.get_pending_appeals(&0, &10).len(), 3);
 
-    let appeal_id = client.submit_appeal(
-        &player,
-        &penalty_id,
-        &symbol_short!("explain"),
-        &symbol_short!("evidence1"),
-    );
+    client.review_appeal(&appeal_ids.get(1).unwrap(), &true, &symbol_short!("approved"));
 
-    // Admin rejects appeal
-    client.review_appeal(&appeal_id, &false, &symbol_short!("insuff"));
+    let queue = client.get_pending_appeals(&0, &10);
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.get(0).unwrap().appeal_id, appeal_ids.get(0).unwrap());
+    assert_eq!(queue.get(1).unwrap().appeal_id, appeal_ids.get(2).unwrap());
 
-    let appeal = client.get_appeal(&appeal_id).unwrap();
-    assert!(matches!(appeal.status, AppealStatus::Rejected));
+    // Timed-out appeals leave the queue too
+    env.ledger().set_timestamp(1000 + 14 * 86400 + 1);
+    client.expire_appeal(&appeal_ids.get(0).unwrap());
 
-    // Penalty should still be active
-    let penalty = client.get_penalty(&penalty_id).unwrap();
-    assert!(penalty.active);
+    let queue = client.get_pending_appeals(&0, &10);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.get(0).unwrap().appeal_id, appeal_ids.get(2).unwrap());
+    assert_eq!(client.get_pending_appeals(&1, &10).len(), 0);
 }
 
 #[test]
-fn test_appeal_period_expired() {
+fn test_reviewed_appeal_cannot_expire() {
     let (env, admin, player) = setup_env();
     let contract_id = env.register_contract(None, AntiBot);
     let client = AntiBotClient::new(&env, &contract_id);
@@ -786,26 +2185,29 @@ fn test_appeal_period_expired() {
     env.ledger().set_timestamp(1000);
     client.initialize(&admin);
 
-    // Create profile and penalty
-    client.generate_captcha_challenge(&player);
     let penalty_id = client.apply_penalty(
+        &admin,
         &player,
-        &PenaltyType::TemporaryBan,
+        &Some(PenaltyType::TemporaryBan),
         &symbol_short!("violation"),
         &5,
     );
-
-    // Move time forward past appeal period (7 days + 1 second)
-    env.ledger().set_timestamp(1000 + (7 * 86400) + 1);
-
-    let result = client.submit_appeal(
+    let appeal_id = client.submit_appeal(
         &player,
         &penalty_id,
         &symbol_short!("explain"),
-        &symbol_short!("evidence1"),
+        &String::from_str(&env, "ipfs://evidence1"),
+        &None,
+    );
+    client.review_appeal(&appeal_id, &false, &symbol_short!("insuff"));
+
+    env.ledger().set_timestamp(1000 + 30 * 86400);
+    let result = client.try_expire_appeal(&appeal_id);
+    assert_eq!(result, Err(Ok(AntiBotError::AppealNotFound)));
+    assert_eq!(
+        client.get_appeal(&appeal_id).unwrap().decision_reason,
+        Some(symbol_short!("insuff"))
     );
-    // Appeal period expired - function returns u32, should not be 0 on success
-    // but would panic on error. For now just verify we can call it.
 }
 
 // ============================================================================
@@ -821,13 +2223,41 @@ fn test_whitelist_player() {
     env.mock_all_auths();
     client.initialize(&admin);
 
-    client.whitelist_player(&player);
+    client.whitelist_player(&player, &None);
 
     assert!(client.is_whitelisted(&player));
     
     let profile = client.get_profile(&player).unwrap();
     assert_eq!(profile.trust_score, 1000);
-    assert!(profile.status == 6); // Whitelisted
+    assert!(profile.status == PlayerStatus::Whitelisted);
+}
+
+#[test]
+fn test_whitelisted_player_never_flagged() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    client.set_time_window(&1, &5000, &60000, &1000, &5000, &1000);
+
+    client.whitelist_player(&player, &None);
+
+    // Repeated fast solves would normally trip the bot-pattern flag
+    for i in 0..5 {
+        env.ledger().set_timestamp(1000 + i as u64 * 10);
+        client.record_activity(&player, &1, &1000, &1000, &true);
+        client.validate_submission_time(&player, &1, &1000);
+    }
+
+    let profile = client.get_profile(&player).unwrap();
+    assert_eq!(profile.status, PlayerStatus::Whitelisted);
+    assert_eq!(profile.trust_score, 1000);
+    assert_eq!(profile.total_attempts, 5);
+    assert_eq!(profile.successful_attempts, 5);
+    assert_eq!(profile.consecutive_fast_solves, 5);
 }
 
 #[test]
@@ -839,7 +2269,7 @@ fn test_remove_whitelist() {
     env.mock_all_auths();
     client.initialize(&admin);
 
-    client.whitelist_player(&player);
+    client.whitelist_player(&player, &None);
     client.remove_whitelist(&player);
 
     assert!(!client.is_whitelisted(&player));
@@ -866,6 +2296,55 @@ fn test_blacklist_player() {
     client.check_penalty_status(&player);
 }
 
+#[test]
+fn test_honeypot_solve_blacklists_player() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    client.register_honeypot(&admin, &99);
+    assert!(client.is_honeypot(&99));
+
+    client.record_activity(&player, &99, &15000, &500, &true);
+
+    assert!(client.is_blacklisted(&player));
+    assert_eq!(client.get_active_penalties(&player), 1);
+    assert_eq!(client.get_blacklisted_players(&0, &10).len(), 1);
+
+    // Only the admin can lay traps
+    let other = Address::generate(&env);
+    assert_eq!(
+        client.try_register_honeypot(&other, &100),
+        Err(Ok(AntiBotError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_normal_puzzle_not_honeypot() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    client.register_honeypot(&admin, &99);
+    assert!(!client.is_honeypot(&1));
+
+    client.record_activity(&player, &1, &15000, &500, &true);
+
+    // A failed attempt on the trap is not proof of a bot either
+    client.record_activity(&player, &99, &15000, &500, &false);
+
+    assert!(!client.is_blacklisted(&player));
+    assert_eq!(client.get_active_penalties(&player), 0);
+}
+
 #[test]
 fn test_remove_blacklist() {
     let (env, admin, player) = setup_env();
@@ -882,6 +2361,70 @@ fn test_remove_blacklist() {
     assert!(!client.is_blacklisted(&player));
 }
 
+#[test]
+fn test_flagged_players_list() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let second = Address::generate(&env);
+    client.flag_player(&admin, &player, &symbol_short!("suspct"), &8);
+    client.flag_player(&admin, &second, &symbol_short!("suspct"), &8);
+    // Re-flagging does not duplicate the entry
+    client.flag_player(&admin, &player, &symbol_short!("suspct"), &8);
+    assert_eq!(
+        client.get_flagged_players(&0, &10),
+        Vec::from_array(&env, [player.clone(), second.clone()])
+    );
+
+    client.unflag_player(&player);
+    assert_eq!(client.get_flagged_players(&0, &10), Vec::from_array(&env, [second.clone()]));
+
+    client.flag_player(&admin, &player, &symbol_short!("again"), &8);
+    assert_eq!(
+        client.get_flagged_players(&0, &10),
+        Vec::from_array(&env, [second.clone(), player.clone()])
+    );
+    assert_eq!(client.get_flagged_players(&1, &10), Vec::from_array(&env, [player.clone()]));
+    assert_eq!(client.get_flagged_players(&5, &10).len(), 0);
+
+    // Moving off Flagged by any route drops the player from the list
+    client.apply_penalty(&admin, &second, &None, &symbol_short!("repeat"), &5);
+    assert_eq!(client.get_flagged_players(&0, &10), Vec::from_array(&env, [player.clone()]));
+}
+
+#[test]
+fn test_blacklisted_players_list() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let second = Address::generate(&env);
+    client.blacklist_player(&player, &symbol_short!("conf_bot"));
+    client.blacklist_player(&second, &symbol_short!("conf_bot"));
+    assert_eq!(
+        client.get_blacklisted_players(&0, &10),
+        Vec::from_array(&env, [player.clone(), second.clone()])
+    );
+
+    client.remove_blacklist(&player);
+    assert_eq!(client.get_blacklisted_players(&0, &10), Vec::from_array(&env, [second.clone()]));
+
+    client.remove_blacklist(&second);
+    assert_eq!(client.get_blacklisted_players(&0, &10).len(), 0);
+
+    client.blacklist_player(&player, &symbol_short!("relapse"));
+    assert_eq!(client.get_blacklisted_players(&0, &10), Vec::from_array(&env, [player.clone()]));
+}
+
 // ============================================================================
 // COMPREHENSIVE VERIFICATION TESTS
 // ============================================================================
@@ -897,7 +2440,7 @@ fn test_verify_player_whitelisted() {
     client.initialize(&admin);
 
     client.set_time_window(&1, &5000, &60000, &1000, &5000, &1000);
-    client.whitelist_player(&player);
+    client.whitelist_player(&player, &None);
 
     let result = client.verify_player(&player, &1, &10000);
     
@@ -906,6 +2449,86 @@ fn test_verify_player_whitelisted() {
     assert_eq!(result.bot_probability, 0);
 }
 
+#[test]
+fn test_whitelist_expires() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    client.set_time_window(&1, &5000, &60000, &1000, &100_000, &1000);
+    client.whitelist_player(&player, &Some(1000 + 3600));
+    assert!(client.is_whitelisted(&player));
+
+    // While whitelisted, verification bypasses the rate limiter entirely
+    client.verify_player(&player, &1, &10000);
+    assert_eq!(client.get_rate_limit_status(&player).attempt_count, 0);
+
+    env.ledger().set_timestamp(1000 + 3600);
+    assert!(!client.is_whitelisted(&player));
+
+    client.verify_player(&player, &1, &10000);
+    assert_eq!(client.get_rate_limit_status(&player).attempt_count, 1);
+}
+
+#[test]
+fn test_legacy_whitelist_never_expires() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Whitelisted(player.clone()), &true);
+    });
+
+    env.ledger().set_timestamp(1000 + 365 * 86400);
+    assert!(client.is_whitelisted(&player));
+}
+
+#[test]
+fn test_risk_summary_through_consumer_contract() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+
+    let consumer_id = env.register_contract(None, MockConsumer);
+    let consumer = MockConsumerClient::new(&env, &consumer_id);
+
+    // Unknown players get the neutral score and no profile is created
+    assert_eq!(client.get_risk_summary(&player), (true, 500));
+    assert!(client.get_profile(&player).is_none());
+    assert!(consumer.gated_action(&contract_id, &player));
+
+    // An active penalty closes the gate
+    client.apply_penalty(
+        &admin,
+        &player,
+        &Some(PenaltyType::TemporaryBan),
+        &symbol_short!("bot_like"),
+        &7,
+    );
+    assert!(!consumer.gated_action(&contract_id, &player));
+
+    // Blacklisted players are denied with a zero score
+    let bot = Address::generate(&env);
+    client.blacklist_player(&bot, &symbol_short!("conf_bot"));
+    assert_eq!(client.get_risk_summary(&bot), (false, 0));
+    assert!(!consumer.gated_action(&contract_id, &bot));
+}
+
 #[test]
 fn test_verify_player_blacklisted() {
     let (env, admin, player) = setup_env();
@@ -936,8 +2559,9 @@ fn test_verify_player_with_penalty() {
     // Create profile and apply penalty
     client.generate_captcha_challenge(&player);
     client.apply_penalty(
+        &admin,
         &player,
-        &PenaltyType::TemporaryBan,
+        &Some(PenaltyType::TemporaryBan),
         &symbol_short!("violation"),
         &5,
     );
@@ -960,7 +2584,7 @@ fn test_verify_player_normal() {
     // Create a normal player with some history
     for i in 0..3 {
         env.ledger().set_timestamp(1000 + i as u64 * 600);
-        client.record_activity(&player, &i, &10000, &500, &true);
+        client.record_activity(&player, &i, &10000, &(450 + i as u64 * 80), &true);
     }
 
     let result = client.verify_player(&player, &1, &10000);
@@ -968,6 +2592,105 @@ fn test_verify_player_normal() {
     assert!(result.bot_probability < 50);
 }
 
+// Fails every attempt and boosts the failure-rate weight past the auto-penalty threshold
+fn setup_high_risk_player(client: &AntiBotClient, player: &Address) {
+    client.set_time_window(&1, &5000, &60000, &1000, &5000, &1000);
+
+    let mut config = client.get_config();
+    config.risk_weights.high_failure_rate = 90;
+    client.update_config(&config);
+
+    for _ in 0..3 {
+        client.record_activity(player, &2, &10000, &500, &false);
+    }
+    assert!(client.analyze_player(player).bot_probability > 85);
+}
+
+#[test]
+fn test_verify_player_auto_penalty_mode() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    setup_high_risk_player(&client, &player);
+
+    let mut config = client.get_config();
+    config.auto_penalty_type = PenaltyType::ExtendedBan;
+    client.update_config(&config);
+
+    let result = client.verify_player(&player, &1, &10000);
+    assert!(!result.allowed);
+    assert_eq!(result.required_action, symbol_short!("blocked"));
+
+    let penalty = client.get_penalty(&1).unwrap();
+    assert_eq!(penalty.penalty_type, PenaltyType::ExtendedBan);
+    assert_eq!(client.get_active_penalties(&player), 1);
+}
+
+#[test]
+fn test_verify_player_recommend_only_mode() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    setup_high_risk_player(&client, &player);
+
+    let mut config = client.get_config();
+    config.auto_penalty_enabled = false;
+    client.update_config(&config);
+
+    // Same probability, but the player is only held for review
+    let result = client.verify_player(&player, &1, &10000);
+    assert!(!result.allowed);
+    assert_eq!(result.required_action, symbol_short!("review"));
+    assert_eq!(client.get_active_penalties(&player), 0);
+    assert!(client.get_penalty(&1).is_none());
+}
+
+#[test]
+fn test_purge_player_keeps_blacklist() {
+    let (env, admin, player) = setup_env();
+    let contract_id = env.register_contract(None, AntiBot);
+    let client = AntiBotClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+    client.initialize(&admin);
+    client.set_time_window(&1, &5000, &60000, &1000, &100_000, &1000);
+
+    for i in 0..3u64 {
+        env.ledger().set_timestamp(1000 + i * 600);
+        client.record_activity(&player, &1, &1000, &(450 + i * 80), &true);
+    }
+    client.check_rate_limit(&player);
+    client.blacklist_player(&player, &symbol_short!("conf_bot"));
+    assert!(client.get_suspicious_activities(&player, &0, &10).len() > 0);
+
+    client.purge_player(&admin, &player);
+
+    assert!(client.get_profile(&player).is_none());
+    assert!(client.get_behavioral_pattern(&player).is_none());
+    assert_eq!(client.get_suspicious_activities(&player, &0, &10).len(), 0);
+    assert_eq!(client.get_rate_limit_status(&player).attempt_count, 0);
+    assert_eq!(client.get_flagged_players(&0, &10).len(), 0);
+
+    // The ban survives so the purge can't be used to evade it
+    assert!(client.is_blacklisted(&player));
+    assert_eq!(client.get_active_penalties(&player), 1);
+
+    let other = Address::generate(&env);
+    assert_eq!(
+        client.try_purge_player(&other, &player),
+        Err(Ok(AntiBotError::Unauthorized))
+    );
+}
+
 // ============================================================================
 // CONFIGURATION TESTS
 // ============================================================================
@@ -1046,7 +2769,7 @@ fn test_full_bot_detection_flow() {
 
     // Check if player is flagged
     let profile = client.get_profile(&bot_player).unwrap();
-    assert!(profile.status == 3 || profile.consecutive_fast_solves >= 3);
+    assert!(profile.status == PlayerStatus::Flagged || profile.consecutive_fast_solves >= 3);
 }
 
 #[test]
@@ -1065,7 +2788,7 @@ fn test_legitimate_player_not_blocked() {
         // Varied timing (human-like)
         let time_variation = if i % 2 == 0 { 12000 } else { 15000 };
         env.ledger().set_timestamp(1000 + i as u64 * 1200); // Varied intervals
-        client.record_activity(&legit_player, &i, &time_variation, &(520 + i as u64), &true);
+        client.record_activity(&legit_player, &i, &time_variation, &(420 + (i as u64 * 95) % 300), &true);
     }
 
     // Verify legitimate player
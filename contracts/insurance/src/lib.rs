@@ -1,6 +1,8 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, String, Vec};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, token, Address, BytesN, Env, String, Vec,
+};
 
 //
 // ──────────────────────────────────────────────────────────
@@ -20,6 +22,8 @@ pub enum DataKey {
     TotalPolicies,             // u64 counter
     TotalClaims,               // u64 counter
     FraudFlags(Address),       // FraudMetrics per user
+    PuzzleClaim(Address, u32), // Claim ID filed for a failed puzzle attempt
+    AutoClaimed(Address),      // start_time of the policy that last used auto_claim
 }
 
 //
@@ -83,6 +87,9 @@ pub struct InsuranceConfig {
     pub max_claims_per_period: u32,    // Fraud detection: max claims per 30 days
     pub claim_cooldown: u64,           // Fraud detection: time between claims
     pub paused: bool,
+    pub verification_oracle: Option<Address>, // puzzle_verification contract used by auto_claim
+    pub auto_payout_threshold: i128,   // Max coverage paid out without review (0 = disabled)
+    pub renewal_window: u64,           // How long before end_time a policy may be renewed
 }
 
 #[contracttype]
@@ -124,6 +131,30 @@ pub struct FraudMetrics {
     pub flag_reason: String,
 }
 
+/// Puzzle metadata as stored by the puzzle_verification contract
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PuzzleMeta {
+    pub id: u32,
+    pub solution_hash: BytesN<32>,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub difficulty: u32,
+    pub reward_points: i128,
+}
+
+//
+// ──────────────────────────────────────────────────────────
+// EXTERNAL CLIENTS
+// ──────────────────────────────────────────────────────────
+//
+
+#[contractclient(name = "PuzzleVerificationClient")]
+pub trait PuzzleVerification {
+    fn is_completed(env: Env, player: Address, puzzle_id: u32) -> bool;
+    fn get_puzzle(env: Env, puzzle_id: u32) -> Option<PuzzleMeta>;
+}
+
 //
 // ──────────────────────────────────────────────────────────
 // CONSTANTS
@@ -179,6 +210,9 @@ impl InsuranceContract {
             max_claims_per_period: 3,         // Max 3 claims per 30 days
             claim_cooldown: 7 * SECONDS_PER_DAY,          // 7 days between claims
             paused: false,
+            verification_oracle: None,
+            auto_payout_threshold: 0,         // Auto payouts disabled until configured
+            renewal_window: 7 * SECONDS_PER_DAY,          // Renewable in the last 7 days
         };
 
         env.storage().persistent().set(&DataKey::Config, &config);
@@ -270,7 +304,13 @@ impl InsuranceContract {
         env.storage().persistent().set(&DataKey::TotalPolicies, &(total + 1));
     }
 
-    /// Renew an existing policy
+    /// Renew an active policy that is within its renewal window
+    ///
+    /// The extension is charged a premium calculated at current rates and is
+    /// added on to the existing end time. Policies here are held one per owner
+    /// and cover assets rather than individual puzzles, so renewal takes no
+    /// puzzle id and has no attempt allowance to top up; failed puzzle attempts
+    /// are claimed per puzzle through `auto_claim`.
     ///
     /// # Arguments
     /// * `owner` - Policy owner
@@ -285,16 +325,24 @@ impl InsuranceContract {
             .expect("Policy not found");
 
         // Validations
-        if policy.status != PolicyStatus::Active && policy.status != PolicyStatus::Expired {
+        if policy.status != PolicyStatus::Active {
             panic!("Policy cannot be renewed");
         }
 
         let current_time = env.ledger().timestamp();
-        let new_end_time = if policy.end_time > current_time {
-            policy.end_time + additional_period
-        } else {
-            current_time + additional_period
-        };
+        if current_time > policy.end_time {
+            panic!("Policy has expired");
+        }
+
+        if current_time + config.renewal_window < policy.end_time {
+            panic!("Outside renewal window");
+        }
+
+        if additional_period < config.min_coverage_period || additional_period > config.max_coverage_period {
+            panic!("Invalid coverage period");
+        }
+
+        let new_end_time = policy.end_time + additional_period;
 
         let total_period = new_end_time - policy.start_time;
         if total_period > config.max_coverage_period {
@@ -317,7 +365,6 @@ impl InsuranceContract {
         // Update policy
         policy.end_time = new_end_time;
         policy.premium_paid += additional_premium;
-        policy.status = PolicyStatus::Active;
 
         env.storage().persistent().set(&DataKey::Policy(owner), &policy);
 
@@ -467,6 +514,143 @@ impl InsuranceContract {
         new_claim_id
     }
 
+    /// Claim coverage for a failed puzzle attempt
+    ///
+    /// The attempt is verified against the puzzle_verification oracle: the
+    /// puzzle must exist and have closed, the attempt must fall inside its
+    /// window, and the claimant must not have completed it. The first auto
+    /// claim on a policy whose coverage is at or below `auto_payout_threshold`
+    /// is approved and paid from the premium pool immediately; anything larger,
+    /// and every later claim on the same policy, is left `Submitted` for
+    /// manual review.
+    ///
+    /// # Arguments
+    /// * `claimant` - Policy owner
+    /// * `puzzle_id` - Puzzle the attempt was made on
+    /// * `attempt_timestamp` - Time of the failed attempt
+    ///
+    /// # Returns
+    /// * Claim ID
+    pub fn auto_claim(env: Env, claimant: Address, puzzle_id: u32, attempt_timestamp: u64) -> u64 {
+        claimant.require_auth();
+        Self::assert_not_paused(&env);
+
+        let config: InsuranceConfig = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let oracle = config.verification_oracle.clone().expect("Verification oracle not set");
+
+        // Get policy
+        let policy: InsurancePolicy = env.storage().persistent()
+            .get(&DataKey::Policy(claimant.clone()))
+            .expect("No active policy found");
+
+        // Validations
+        let current_time = env.ledger().timestamp();
+
+        if policy.status != PolicyStatus::Active {
+            panic!("Policy is not active");
+        }
+
+        if current_time < policy.start_time || current_time > policy.end_time {
+            panic!("Outside coverage period");
+        }
+
+        if attempt_timestamp < policy.start_time || attempt_timestamp > current_time {
+            panic!("Attempt outside coverage period");
+        }
+
+        let puzzle_claim_key = DataKey::PuzzleClaim(claimant.clone(), puzzle_id);
+        if env.storage().persistent().has(&puzzle_claim_key) {
+            panic!("Attempt already claimed");
+        }
+
+        // Verify the attempt failed with the oracle
+        let verifier = PuzzleVerificationClient::new(&env, &oracle);
+        let puzzle = verifier.get_puzzle(&puzzle_id).expect("Puzzle not found");
+
+        if attempt_timestamp < puzzle.start_ts || attempt_timestamp > puzzle.end_ts {
+            panic!("Attempt outside puzzle window");
+        }
+
+        // Completion is only final once the puzzle has closed
+        if current_time <= puzzle.end_ts {
+            panic!("Puzzle window still open");
+        }
+
+        if verifier.is_completed(&claimant, &puzzle_id) {
+            panic!("Puzzle was completed");
+        }
+
+        // Fraud checks
+        Self::check_fraud(&env, &claimant);
+
+        // Generate claim ID
+        let claim_id: u64 = env.storage().persistent().get(&DataKey::ClaimCounter).unwrap_or(0);
+        let new_claim_id = claim_id + 1;
+        env.storage().persistent().set(&DataKey::ClaimCounter, &new_claim_id);
+
+        let asset_type = match policy.coverage_type {
+            CoverageType::NFT => AssetType::NFT,
+            _ => AssetType::Token,
+        };
+
+        let mut claim = Claim {
+            claim_id: new_claim_id,
+            policy_owner: claimant.clone(),
+            asset_type,
+            asset_address: policy.asset_address.clone(),
+            claim_amount: policy.coverage_amount,
+            description: String::from_str(&env, "Failed puzzle attempt"),
+            submission_time: current_time,
+            status: ClaimStatus::Submitted,
+            review_notes: String::from_str(&env, ""),
+            payout_amount: 0,
+            payout_time: 0,
+        };
+
+        // A policy's first small claim is paid straight away; large ones, later
+        // ones, or any the pool cannot cover yet, wait for review_claim/process_payout
+        let auto_claimed_key = DataKey::AutoClaimed(claimant.clone());
+        let first_auto_claim = env.storage().persistent()
+            .get::<DataKey, u64>(&auto_claimed_key) != Some(policy.start_time);
+        env.storage().persistent().set(&auto_claimed_key, &policy.start_time);
+
+        let pool: i128 = env.storage().persistent().get(&DataKey::PremiumPool).unwrap_or(0);
+        if first_auto_claim
+            && claim.claim_amount <= config.auto_payout_threshold
+            && pool >= claim.claim_amount
+        {
+            let token_client = token::Client::new(&env, &config.payment_token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &claimant,
+                &claim.claim_amount,
+            );
+
+            claim.status = ClaimStatus::Paid;
+            claim.review_notes = String::from_str(&env, "Auto-approved by verification oracle");
+            claim.payout_amount = claim.claim_amount;
+            claim.payout_time = current_time;
+
+            env.storage().persistent().set(&DataKey::PremiumPool, &(pool - claim.payout_amount));
+        }
+
+        // Store claim
+        env.storage().persistent().set(&DataKey::Claim(new_claim_id), &claim);
+        env.storage().persistent().set(&puzzle_claim_key, &new_claim_id);
+
+        // Add to user's claims list
+        Self::add_to_user_claims(&env, claimant.clone(), new_claim_id);
+
+        // Update fraud metrics
+        Self::update_fraud_metrics(&env, claimant, new_claim_id, current_time);
+
+        // Increment total claims
+        let total: u64 = env.storage().persistent().get(&DataKey::TotalClaims).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::TotalClaims, &(total + 1));
+
+        new_claim_id
+    }
+
     /// Review a claim (admin only)
     ///
     /// # Arguments
@@ -764,6 +948,16 @@ impl InsuranceContract {
         env.storage().persistent().set(&DataKey::Config, &config);
     }
 
+    /// Update how long before expiry a policy may be renewed (admin only)
+    pub fn update_renewal_window(env: Env, admin: Address, renewal_window: u64) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        let mut config: InsuranceConfig = env.storage().persistent().get(&DataKey::Config).unwrap();
+        config.renewal_window = renewal_window;
+        env.storage().persistent().set(&DataKey::Config, &config);
+    }
+
     /// Update fraud detection parameters (admin only)
     pub fn update_fraud_params(
         env: Env,
@@ -782,6 +976,33 @@ impl InsuranceContract {
         env.storage().persistent().set(&DataKey::Config, &config);
     }
 
+    /// Update automated claim parameters (admin only)
+    ///
+    /// # Arguments
+    /// * `admin` - Admin address
+    /// * `oracle` - puzzle_verification contract used to verify failed attempts
+    /// * `auto_payout_threshold` - Max coverage paid out without review (0 disables)
+    pub fn update_auto_claim_params(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        auto_payout_threshold: i128,
+    ) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        if auto_payout_threshold < 0 {
+            panic!("Invalid auto payout threshold");
+        }
+
+        let mut config: InsuranceConfig = env.storage().persistent().get(&DataKey::Config).unwrap();
+
+        config.verification_oracle = Some(oracle);
+        config.auto_payout_threshold = auto_payout_threshold;
+
+        env.storage().persistent().set(&DataKey::Config, &config);
+    }
+
     /// Pause/unpause contract (admin only)
     pub fn set_paused(env: Env, admin: Address, paused: bool) {
         admin.require_auth();
@@ -14,6 +14,37 @@ fn create_token_contract<'a>(env: &Env, admin: &Address) -> (Address, TokenClien
     (address.clone(), TokenClient::new(env, &address))
 }
 
+// Stand-in for the puzzle_verification contract
+#[contract]
+pub struct MockVerifier;
+
+#[contractimpl]
+impl MockVerifier {
+    pub fn set_puzzle(env: Env, puzzle_id: u32, start_ts: u64, end_ts: u64) {
+        let meta = PuzzleMeta {
+            id: puzzle_id,
+            solution_hash: BytesN::from_array(&env, &[0u8; 32]),
+            start_ts,
+            end_ts,
+            difficulty: 1,
+            reward_points: 0,
+        };
+        env.storage().instance().set(&puzzle_id, &meta);
+    }
+
+    pub fn set_completed(env: Env, player: Address, puzzle_id: u32) {
+        env.storage().instance().set(&(player, puzzle_id), &true);
+    }
+
+    pub fn is_completed(env: Env, player: Address, puzzle_id: u32) -> bool {
+        env.storage().instance().get(&(player, puzzle_id)).unwrap_or(false)
+    }
+
+    pub fn get_puzzle(env: Env, puzzle_id: u32) -> Option<PuzzleMeta> {
+        env.storage().instance().get(&puzzle_id)
+    }
+}
+
 fn setup_insurance_contract(env: &Env) -> (
     InsuranceContractClient,
     Address,
@@ -298,7 +329,8 @@ fn test_renew_policy() {
     let initial_policy = client.get_policy(&user).unwrap();
     let initial_premium = initial_policy.premium_paid;
 
-    // Renew for another 30 days
+    // Renew for another 30 days once inside the renewal window
+    env.ledger().set_timestamp(1000 + 25 * 86_400);
     let additional_period = 30 * 86_400u64;
     client.renew_policy(&user, &additional_period);
 
@@ -309,6 +341,83 @@ fn test_renew_policy() {
 }
 
 #[test]
+fn test_renew_policy_just_before_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, _, user, _, payment_token_client, payment_admin_client) =
+        setup_insurance_contract(&env);
+
+    payment_admin_client.mint(&user, &10_000_000_000i128);
+
+    let coverage_amount = 1_000_000_000i128;
+    let coverage_period = 30 * 86_400u64;
+
+    client.purchase_policy(
+        &user,
+        &CoverageType::Token,
+        &coverage_amount,
+        &coverage_period,
+        &Address::generate(&env),
+    );
+
+    let initial_premium = client.get_policy(&user).unwrap().premium_paid;
+
+    // Rates change before renewal; the extension is priced at the new rate
+    let admin = client.get_config().admin;
+    client.update_premium_rates(&admin, &200, &150, &100, &180);
+
+    let additional_period = 60 * 86_400u64;
+    let expected_premium = client.calculate_premium(
+        &CoverageType::Token,
+        &coverage_amount,
+        &additional_period,
+    );
+
+    // One second before expiry
+    env.ledger().set_timestamp(1000 + coverage_period - 1);
+    let pool_before = client.get_premium_pool();
+    let balance_before = payment_token_client.balance(&user);
+
+    client.renew_policy(&user, &additional_period);
+
+    let renewed_policy = client.get_policy(&user).unwrap();
+    assert_eq!(renewed_policy.end_time, 1000 + coverage_period + additional_period);
+    assert_eq!(renewed_policy.start_time, 1000);
+    assert_eq!(renewed_policy.premium_paid, initial_premium + expected_premium);
+    assert_eq!(renewed_policy.status, PolicyStatus::Active);
+
+    assert_eq!(payment_token_client.balance(&user), balance_before - expected_premium);
+    assert_eq!(client.get_premium_pool(), pool_before + expected_premium);
+}
+
+#[test]
+#[should_panic(expected = "Outside renewal window")]
+fn test_renew_policy_too_early() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, _, user, _, _, payment_admin_client) = setup_insurance_contract(&env);
+
+    payment_admin_client.mint(&user, &10_000_000_000i128);
+
+    client.purchase_policy(
+        &user,
+        &CoverageType::Token,
+        &1_000_000_000i128,
+        &(30 * 86_400u64),
+        &Address::generate(&env),
+    );
+
+    // 10 days left, window is 7
+    env.ledger().set_timestamp(1000 + 20 * 86_400);
+    client.renew_policy(&user, &(30 * 86_400u64));
+}
+
+#[test]
+#[should_panic(expected = "Policy has expired")]
 fn test_renew_expired_policy() {
     let env = Env::default();
     env.mock_all_auths();
@@ -332,11 +441,8 @@ fn test_renew_expired_policy() {
     // Fast forward past expiration
     env.ledger().set_timestamp(1000 + coverage_period + 1000);
 
-    // Renew should still work
+    // Lapsed policies must be bought fresh
     client.renew_policy(&user, &(30 * 86_400u64));
-
-    let renewed_policy = client.get_policy(&user).unwrap();
-    assert_eq!(renewed_policy.status, PolicyStatus::Active);
 }
 
 // ───────────── POLICY CANCELLATION TESTS ─────────────
@@ -879,6 +985,211 @@ fn test_cannot_payout_insufficient_pool() {
     client.process_payout(&admin, &claim_id);
 }
 
+// ───────────── AUTO CLAIM TESTS ─────────────
+
+fn setup_auto_claim(
+    env: &Env,
+    client: &InsuranceContractClient,
+    admin: &Address,
+    auto_payout_threshold: i128,
+) -> MockVerifierClient<'static> {
+    let oracle_id = env.register_contract(None, MockVerifier);
+    let oracle = MockVerifierClient::new(env, &oracle_id);
+
+    // Puzzle 1 closes a day before the claims made below
+    oracle.set_puzzle(&1, &0, &(1000 + 9 * 86_400));
+
+    client.update_auto_claim_params(admin, &oracle_id, &auto_payout_threshold);
+
+    oracle
+}
+
+#[test]
+fn test_auto_claim_small_claim_paid() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, admin, user, _, payment_token_client, payment_admin_client) =
+        setup_insurance_contract(&env);
+    setup_auto_claim(&env, &client, &admin, 100_000_000i128);
+
+    payment_admin_client.mint(&admin, &10_000_000_000i128);
+    client.add_to_pool(&admin, &5_000_000_000i128);
+    payment_admin_client.mint(&user, &10_000_000_000i128);
+
+    let coverage_amount = 100_000_000i128;
+    client.purchase_policy(
+        &user,
+        &CoverageType::Token,
+        &coverage_amount,
+        &(30 * 86_400u64),
+        &Address::generate(&env),
+    );
+
+    env.ledger().set_timestamp(1000 + 10 * 86_400);
+
+    let pool_before = client.get_premium_pool();
+    let balance_before = payment_token_client.balance(&user);
+
+    let claim_id = client.auto_claim(&user, &1, &(1000 + 9 * 86_400));
+
+    // Approved and paid in the same call
+    let claim = client.get_claim(&claim_id).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Paid);
+    assert_eq!(claim.claim_amount, coverage_amount);
+    assert_eq!(claim.payout_amount, coverage_amount);
+    assert_eq!(claim.payout_time, 1000 + 10 * 86_400);
+
+    assert_eq!(client.get_premium_pool(), pool_before - coverage_amount);
+    assert_eq!(payment_token_client.balance(&user), balance_before + coverage_amount);
+    assert_eq!(client.get_total_claims(), 1);
+}
+
+#[test]
+fn test_auto_claim_large_claim_deferred_to_review() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, admin, user, _, payment_token_client, payment_admin_client) =
+        setup_insurance_contract(&env);
+    setup_auto_claim(&env, &client, &admin, 100_000_000i128);
+
+    payment_admin_client.mint(&admin, &10_000_000_000i128);
+    client.add_to_pool(&admin, &5_000_000_000i128);
+    payment_admin_client.mint(&user, &10_000_000_000i128);
+
+    client.purchase_policy(
+        &user,
+        &CoverageType::Token,
+        &1_000_000_000i128,
+        &(30 * 86_400u64),
+        &Address::generate(&env),
+    );
+
+    env.ledger().set_timestamp(1000 + 10 * 86_400);
+
+    let pool_before = client.get_premium_pool();
+    let balance_before = payment_token_client.balance(&user);
+
+    let claim_id = client.auto_claim(&user, &1, &(1000 + 9 * 86_400));
+
+    // Above the threshold: nothing paid, waiting on the admin
+    let claim = client.get_claim(&claim_id).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Submitted);
+    assert_eq!(claim.payout_amount, 0);
+    assert_eq!(client.get_premium_pool(), pool_before);
+    assert_eq!(payment_token_client.balance(&user), balance_before);
+
+    // Manual review path still works
+    client.review_claim(
+        &admin,
+        &claim_id,
+        &true,
+        &String::from_str(&env, "Approved"),
+        &400_000_000i128,
+    );
+    client.process_payout(&admin, &claim_id);
+
+    assert_eq!(client.get_claim(&claim_id).unwrap().status, ClaimStatus::Paid);
+    assert_eq!(payment_token_client.balance(&user), balance_before + 400_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Puzzle was completed")]
+fn test_auto_claim_completed_puzzle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, admin, user, _, _, payment_admin_client) = setup_insurance_contract(&env);
+    let oracle = setup_auto_claim(&env, &client, &admin, 100_000_000i128);
+
+    payment_admin_client.mint(&user, &10_000_000_000i128);
+
+    client.purchase_policy(
+        &user,
+        &CoverageType::Token,
+        &100_000_000i128,
+        &(30 * 86_400u64),
+        &Address::generate(&env),
+    );
+
+    oracle.set_completed(&user, &1);
+    env.ledger().set_timestamp(1000 + 10 * 86_400);
+
+    client.auto_claim(&user, &1, &(1000 + 9 * 86_400));
+}
+
+#[test]
+#[should_panic(expected = "Puzzle window still open")]
+fn test_auto_claim_inside_puzzle_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, admin, user, _, _, payment_admin_client) = setup_insurance_contract(&env);
+    setup_auto_claim(&env, &client, &admin, 100_000_000i128);
+
+    payment_admin_client.mint(&user, &10_000_000_000i128);
+
+    client.purchase_policy(
+        &user,
+        &CoverageType::Token,
+        &100_000_000i128,
+        &(30 * 86_400u64),
+        &Address::generate(&env),
+    );
+
+    // The player could still complete the puzzle
+    env.ledger().set_timestamp(1000 + 5 * 86_400);
+
+    client.auto_claim(&user, &1, &(1000 + 4 * 86_400));
+}
+
+#[test]
+fn test_auto_claim_second_claim_deferred_to_review() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, admin, user, _, payment_token_client, payment_admin_client) =
+        setup_insurance_contract(&env);
+    let oracle = setup_auto_claim(&env, &client, &admin, 100_000_000i128);
+    oracle.set_puzzle(&2, &0, &(1000 + 17 * 86_400));
+
+    payment_admin_client.mint(&admin, &10_000_000_000i128);
+    client.add_to_pool(&admin, &5_000_000_000i128);
+    payment_admin_client.mint(&user, &10_000_000_000i128);
+
+    client.purchase_policy(
+        &user,
+        &CoverageType::Token,
+        &100_000_000i128,
+        &(30 * 86_400u64),
+        &Address::generate(&env),
+    );
+
+    env.ledger().set_timestamp(1000 + 10 * 86_400);
+    let first_id = client.auto_claim(&user, &1, &(1000 + 9 * 86_400));
+    assert_eq!(client.get_claim(&first_id).unwrap().status, ClaimStatus::Paid);
+
+    // Past the cooldown, a second small claim on the same policy is not auto-paid
+    env.ledger().set_timestamp(1000 + 18 * 86_400);
+
+    let pool_before = client.get_premium_pool();
+    let balance_before = payment_token_client.balance(&user);
+
+    let second_id = client.auto_claim(&user, &2, &(1000 + 16 * 86_400));
+
+    let claim = client.get_claim(&second_id).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Submitted);
+    assert_eq!(claim.payout_amount, 0);
+    assert_eq!(client.get_premium_pool(), pool_before);
+    assert_eq!(payment_token_client.balance(&user), balance_before);
+}
+
 // ───────────── FRAUD DETECTION TESTS ─────────────
 
 #[test]
@@ -3,7 +3,8 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, Map, Symbol, Vec, log,
+    contract, contractimpl, contracttype, token, Address, Env, IntoVal, Map, String, Symbol, Vec,
+    log,
 };
 
 // ============================================================
@@ -31,6 +32,32 @@ pub enum DataKey {
     RentalHistory(u64),
     /// All active listing ids (marketplace discovery)
     ActiveListings,
+    /// Admin-managed contract configuration
+    Config,
+    /// Lifetime rental revenue paid out to an owner
+    OwnerRevenue(Address),
+    /// Active listing ids for an NFT contract (collection discovery)
+    ListingsByNftContract(Address),
+    /// Whether a renter is whitelisted for a restricted listing
+    RenterAllowed(u64, Address),
+    /// Aggregate owner ratings of a renter as (sum of scores, number of ratings)
+    RenterRating(Address),
+    /// Whether a closed rental has already been rated
+    RentalRated(u64),
+    /// Pending reservation on a listing
+    Reservation(u64),
+    /// Number of Active rentals on a listing
+    ListingActiveRentals(u64),
+    /// All rental ids ever opened for an NFT (nft_contract, token_id)
+    RentalsByNft(Address, u64),
+    /// Active listing ids carrying a tag (tag search)
+    ListingsByTag(Symbol),
+    /// Bond locked for a listing as (bond token, amount)
+    ListingBond(u64),
+    /// Number of rentals a listing has had (demand-based pricing)
+    DemandCounter(u64),
+    /// Incrementally maintained marketplace totals
+    Stats,
 }
 
 // ============================================================
@@ -44,6 +71,7 @@ pub enum ListingStatus {
     Active,
     Paused,
     Cancelled,
+    Expired,
 }
 
 /// Status of a rental
@@ -55,6 +83,48 @@ pub enum RentalStatus {
     Terminated,
 }
 
+/// Why a rental ended in `Terminated`
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum TerminationReason {
+    /// Not terminated
+    None,
+    /// Ended early by the renter or owner via `terminate_rental`
+    Early,
+    /// Cancelled by the owner before it started
+    OwnerCancelled,
+    /// Refunded because the owner no longer held the NFT
+    CustodyLost,
+    /// Refunded because the admin removed the listing
+    ListingRemoved,
+}
+
+/// How a listing bills its renters
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum BillingMode {
+    /// All periods are paid up front
+    Prepaid,
+    /// One period is paid up front and each further period via `pay_next_period`
+    PayPerPeriod,
+}
+
+/// Lifecycle of a rental's security deposit
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum DepositStatus {
+    /// No deposit was required
+    None,
+    /// Deposit is held in the contract
+    Held,
+    /// Deposit was returned to the renter
+    Returned,
+    /// Deposit was claimed by the owner
+    Claimed,
+    /// Deposit was split between the parties by dispute resolution
+    Resolved,
+}
+
 /// A rental listing created by an NFT owner
 #[contracttype]
 #[derive(Clone)]
@@ -83,6 +153,46 @@ pub struct RentalListing {
     pub allow_extensions: bool,
     /// Early termination refund percentage (0-100)
     pub early_termination_refund_pct: u32,
+    /// Security deposit collected from the renter on top of the rental fee
+    pub deposit_amount: i128,
+    /// Royalty payees and their cut of rental revenue in basis points
+    pub royalty_payees: Vec<(Address, u32)>,
+    /// Minimum number of periods per rental
+    pub min_periods: u32,
+    /// Ledger timestamp after which the listing can no longer be rented (0 = never)
+    pub listing_expires_at: u64,
+    /// Whether only whitelisted renters may rent this listing
+    pub restricted: bool,
+    /// Whether renters may transfer their remaining rental time to another address
+    pub subleasable: bool,
+    /// Long-term discounts as (minimum periods, discount bps), sorted by threshold
+    pub discount_tiers: Vec<(u32, u32)>,
+    /// Whether renters prepay or pay per period
+    pub billing_mode: BillingMode,
+    /// Seconds a pay-per-period renter may be late with the next payment
+    pub payment_grace_seconds: u64,
+    /// How many rentals of this listing may run at once (1 = exclusive)
+    pub max_concurrent_rentals: u32,
+    /// Seconds after `end_time` the renter has to hand the NFT back
+    pub return_grace_seconds: u64,
+    /// Additional NFTs rented together with the primary one (empty = single NFT)
+    pub extra_tokens: Vec<(Address, u64)>,
+    /// Alternative payment tokens and their price per period, on top of
+    /// `payment_token` at `price_per_period`
+    pub accepted_tokens: Vec<(Address, i128)>,
+    /// Search tags such as "weapon" or "land" (at most `MAX_TAGS`)
+    pub tags: Vec<Symbol>,
+    /// Off-chain metadata describing the listing (empty if none)
+    pub metadata_uri: String,
+    /// Co-owners and their share of the owner's payout in basis points
+    /// (empty = the owner receives everything)
+    pub co_owners: Vec<(Address, u32)>,
+    /// Earliest timestamp a rental may start (0 = no lower bound)
+    pub available_from: u64,
+    /// Latest timestamp a rental may run until (0 = no upper bound)
+    pub available_until: u64,
+    /// Whether the price rises with the number of past rentals
+    pub dynamic_pricing: bool,
 }
 
 /// An active or historical rental agreement
@@ -117,6 +227,38 @@ pub struct RentalAgreement {
     pub status: RentalStatus,
     /// Early termination refund percentage
     pub early_termination_refund_pct: u32,
+    /// Security deposit held in escrow for this rental
+    pub deposit_amount: i128,
+    /// What happened to the security deposit
+    pub deposit_status: DepositStatus,
+    /// Royalty payees locked in from the listing when the rental started
+    pub royalty_payees: Vec<(Address, u32)>,
+    /// Platform fee in basis points locked in when the rental started
+    pub fee_bps: u32,
+    /// Billing mode locked in when the rental started
+    pub billing_mode: BillingMode,
+    /// Number of periods paid for so far (equals `periods` when prepaid)
+    pub periods_paid: u32,
+    /// Grace window for late pay-per-period payments, in seconds
+    pub payment_grace_seconds: u64,
+    /// Grace window for handing the NFT back after `end_time`, in seconds
+    pub return_grace_seconds: u64,
+    /// Ledger timestamp the owner confirmed the NFT was returned (0 = not yet)
+    pub returned_at: u64,
+    /// Additional NFTs covered by this rental when the listing is a bundle
+    pub extra_tokens: Vec<(Address, u64)>,
+    /// Whether a dispute is open; blocks termination and deposit release
+    pub disputed: bool,
+    /// Reason given by the party that opened the most recent dispute
+    pub dispute_reason: Option<Symbol>,
+    /// Whether keepers may renew this rental from the renter's allowance
+    pub auto_renew: bool,
+    /// Periods bought by each renewal (the rental's initial term)
+    pub renewal_periods: u32,
+    /// Co-owner payout shares locked in from the listing when the rental started
+    pub co_owners: Vec<(Address, u32)>,
+    /// Why the rental was terminated, if it was
+    pub termination_reason: TerminationReason,
 }
 
 /// Historical record stored after rental closes
@@ -135,6 +277,56 @@ pub struct RentalRecord {
     pub final_status: RentalStatus,
 }
 
+/// A short-lived hold on a listing while a renter arranges funds
+#[contracttype]
+#[derive(Clone)]
+pub struct Reservation {
+    pub listing_id: u64,
+    pub renter: Address,
+    /// Ledger timestamp at which the hold lapses
+    pub expires_at: u64,
+}
+
+/// Admin-managed contract configuration
+#[contracttype]
+#[derive(Clone)]
+pub struct Config {
+    /// Maximum number of Active rentals a single renter may hold (0 = unlimited)
+    pub max_active_rentals_per_renter: u32,
+    /// Platform fee taken from rental payouts, in basis points
+    pub fee_bps: u32,
+    /// Address receiving platform fees
+    pub fee_recipient: Address,
+    /// NFT contract function used to verify custody; called as
+    /// `fn(token_id: u64) -> Address` and expected to return the holder
+    pub custody_fn: Symbol,
+    /// Bond locked by owners for each new listing (0 = no bond)
+    pub listing_bond: i128,
+    /// Token the listing bond is paid in
+    pub bond_token: Option<Address>,
+    /// Price increase per past rental for dynamically priced listings, in bps
+    pub demand_step_bps: u32,
+    /// Ceiling on the dynamic price multiplier, in bps (10000 = base price)
+    pub demand_cap_bps: u32,
+}
+
+/// Parameters for one listing in `create_listings_batch`; mirrors the
+/// arguments of `create_listing`.
+#[contracttype]
+#[derive(Clone)]
+pub struct RentalListingInput {
+    pub nft_contract: Address,
+    pub nft_token_id: u64,
+    pub payment_token: Address,
+    pub price_per_period: i128,
+    pub period_duration: u64,
+    pub max_periods: u32,
+    pub allow_extensions: bool,
+    pub early_termination_refund_pct: u32,
+    pub tags: Vec<Symbol>,
+    pub metadata_uri: String,
+}
+
 /// Marketplace page result
 #[contracttype]
 #[derive(Clone)]
@@ -143,12 +335,144 @@ pub struct MarketplacePage {
     pub total: u64,
 }
 
+/// One window of a price search over the marketplace index
+#[contracttype]
+#[derive(Clone)]
+pub struct PriceSearchPage {
+    pub listings: Vec<RentalListing>,
+    /// Index to pass as `start` for the next window; `None` once the whole
+    /// index has been scanned
+    pub next_start: Option<u32>,
+}
+
+/// Marketplace-wide aggregates. Volume and fees are summed in raw token
+/// units across every payment token.
+#[contracttype]
+#[derive(Clone)]
+pub struct MarketplaceStats {
+    /// Listings ever created
+    pub total_listings: u64,
+    /// Listings currently in the marketplace index
+    pub active_listings: u64,
+    /// Rentals ever started
+    pub total_rentals: u64,
+    /// Sum of `total_paid` across all rentals, including extensions
+    pub total_volume: i128,
+    /// Platform fees paid out to the fee recipient
+    pub total_fees: i128,
+}
+
+/// Page of a renter's closed rentals
+#[contracttype]
+#[derive(Clone)]
+pub struct RentalHistoryPage {
+    pub records: Vec<RentalRecord>,
+    pub total: u64,
+}
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// Maximum number of listings returned by a single marketplace page.
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of ActiveListings entries inspected by a filtered search.
+const MAX_SCAN_WINDOW: u32 = 200;
+
+/// Maximum number of listings accepted by `create_listings_batch`.
+const MAX_BATCH_SIZE: u32 = 25;
+
+/// Maximum number of extra NFTs that can be bundled into one listing.
+const MAX_BUNDLE_SIZE: u32 = 10;
+
+/// Maximum number of alternative payment tokens a listing may accept.
+const MAX_ACCEPTED_TOKENS: u32 = 5;
+
+/// Maximum number of search tags per listing.
+const MAX_TAGS: u32 = 5;
+
+/// Longest hold a renter may place on a listing, in seconds.
+const MAX_RESERVATION_SECONDS: u64 = 86_400;
+
+/// Default NFT function queried to verify listing custody.
+const DEFAULT_CUSTODY_FN: &str = "owner_of";
+
+/// Default price increase per past rental for dynamically priced listings.
+const DEFAULT_DEMAND_STEP_BPS: u32 = 500;
+
+/// Default ceiling on the dynamic price multiplier (2x).
+const DEFAULT_DEMAND_CAP_BPS: u32 = 20_000;
+
+/// Basis-point denominator (100%).
+const BPS_DENOMINATOR: i128 = 10_000;
+
 // ============================================================
 // Events
 // ============================================================
+//
+// Every event has a single topic (the name below). Lifecycle events carry
+// a tuple led by the affected id and ending with the ledger timestamp:
+//   listing_created    (listing_id, owner, price_per_period, timestamp)
+//   listing_cancelled  (listing_id, owner, timestamp)
+//   listing_paused     (listing_id, timestamp)
+//   listing_resumed    (listing_id, timestamp)
+//   listing_expired    (listing_id, timestamp)
+//   listing_removed    (listing_id, reason, timestamp)
+//   rental_started     (rental_id, listing_id, renter, total_paid, start_time, end_time)
+//   rental_extended    (rental_id, extension_cost, end_time)
+//   rental_terminated  (rental_id, refund, timestamp)
+//   rental_expired     (rental_id, total_paid, timestamp)
+//   rental_renewed     (rental_id, renewal_cost, end_time)
+//   rental_cancelled   (rental_id, refund, timestamp)
+//   rental_failed      (rental_id, refund, timestamp)
+//   deposit_returned   (rental_id, amount, timestamp)
+//   deposit_claimed    (rental_id, amount, timestamp)
+//   nft_returned       (rental_id, timestamp)
+//   dispute_opened     (rental_id, caller, reason, timestamp)
+//   dispute_resolved   (rental_id, renter_share, owner_share, timestamp)
 mod events {
     pub const LISTING_CREATED: &str = "listing_created";
     pub const LISTING_CANCELLED: &str = "listing_cancelled";
+    pub const LISTING_PAUSED: &str = "listing_paused";
+    pub const LISTING_RESUMED: &str = "listing_resumed";
+    pub const LISTING_PRICE_UPDATED: &str = "listing_price_updated";
+    pub const LISTING_DEPOSIT_SET: &str = "listing_deposit_set";
+    pub const LISTING_ROYALTIES_SET: &str = "listing_royalties_set";
+    pub const LISTING_TERMS_SET: &str = "listing_terms_set";
+    pub const LISTING_EXPIRED: &str = "listing_expired";
+    pub const LISTING_RESTRICTED: &str = "listing_restricted";
+    pub const RENTER_ALLOWED: &str = "renter_allowed";
+    pub const RENTER_DISALLOWED: &str = "renter_disallowed";
+    pub const FEE_UPDATED: &str = "fee_updated";
+    pub const LISTING_SUBLEASABLE: &str = "listing_subleasable";
+    pub const RENTAL_TRANSFERRED: &str = "rental_transferred";
+    pub const LISTING_DISCOUNTS_SET: &str = "listing_discounts_set";
+    pub const RENTAL_RATED: &str = "rental_rated";
+    pub const LISTING_RESERVED: &str = "listing_reserved";
+    pub const RESERVATION_RELEASED: &str = "reservation_released";
+    pub const RENTAL_CANCELLED: &str = "rental_cancelled";
+    pub const LISTING_BILLING_SET: &str = "listing_billing_set";
+    pub const PERIOD_PAID: &str = "period_paid";
+    pub const LISTING_CAPACITY_SET: &str = "listing_capacity_set";
+    pub const LISTING_RETURN_GRACE_SET: &str = "listing_return_grace_set";
+    pub const NFT_RETURNED: &str = "nft_returned";
+    pub const LISTING_BUNDLE_SET: &str = "listing_bundle_set";
+    pub const DISPUTE_OPENED: &str = "dispute_opened";
+    pub const DISPUTE_RESOLVED: &str = "dispute_resolved";
+    pub const AUTO_RENEW_SET: &str = "auto_renew_set";
+    pub const RENTAL_RENEWED: &str = "rental_renewed";
+    pub const LISTING_TOKENS_SET: &str = "listing_tokens_set";
+    pub const LISTING_METADATA_SET: &str = "listing_metadata_set";
+    pub const LISTING_CO_OWNERS_SET: &str = "listing_co_owners_set";
+    pub const RENTAL_FAILED: &str = "rental_failed";
+    pub const LISTING_BOND_UPDATED: &str = "listing_bond_updated";
+    pub const DEMAND_PRICING_UPDATED: &str = "demand_pricing_updated";
+    pub const LISTING_REMOVED: &str = "listing_removed";
+    pub const LISTING_AVAILABILITY_SET: &str = "listing_availability_set";
+    pub const LISTING_DYNAMIC_PRICING_SET: &str = "listing_dynamic_pricing_set";
+    pub const DEPOSIT_RETURNED: &str = "deposit_returned";
+    pub const DEPOSIT_CLAIMED: &str = "deposit_claimed";
     pub const RENTAL_STARTED: &str = "rental_started";
     pub const RENTAL_EXTENDED: &str = "rental_extended";
     pub const RENTAL_EXPIRED: &str = "rental_expired";
@@ -176,6 +500,19 @@ impl PuzzleRentalContract {
         }
         admin.require_auth();
         env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(
+            &DataKey::Config,
+            &Config {
+                max_active_rentals_per_renter: 0,
+                fee_bps: 0,
+                fee_recipient: admin.clone(),
+                custody_fn: Symbol::new(&env, DEFAULT_CUSTODY_FN),
+                listing_bond: 0,
+                bond_token: None,
+                demand_step_bps: DEFAULT_DEMAND_STEP_BPS,
+                demand_cap_bps: DEFAULT_DEMAND_CAP_BPS,
+            },
+        );
         env.storage().instance().set(&DataKey::NextListingId, &1u64);
         env.storage().instance().set(&DataKey::NextRentalId, &1u64);
         env.storage()
@@ -183,6 +520,97 @@ impl PuzzleRentalContract {
             .set(&DataKey::ActiveListings, &Vec::<u64>::new(&env));
     }
 
+    // ----------------------------------------------------------
+    // Admin Configuration
+    // ----------------------------------------------------------
+
+    /// Set the maximum number of simultaneously Active rentals per renter.
+    /// A value of 0 disables the cap.
+    pub fn set_max_active_rentals(env: Env, admin: Address, max: u32) {
+        Self::require_admin(&env, &admin);
+
+        let mut config = Self::load_config(&env);
+        config.max_active_rentals_per_renter = max;
+        env.storage().instance().set(&DataKey::Config, &config);
+    }
+
+    /// Set the platform fee (basis points of every rental payout) and the
+    /// address that receives it. Only affects rentals started afterwards.
+    pub fn set_fee(env: Env, admin: Address, fee_bps: u32, fee_recipient: Address) {
+        Self::require_admin(&env, &admin);
+
+        if fee_bps as i128 > BPS_DENOMINATOR {
+            panic!("fee bps exceed 10000");
+        }
+
+        let mut config = Self::load_config(&env);
+        config.fee_bps = fee_bps;
+        config.fee_recipient = fee_recipient.clone();
+        env.storage().instance().set(&DataKey::Config, &config);
+
+        env.events().publish(
+            (Symbol::new(&env, events::FEE_UPDATED),),
+            (fee_bps, fee_recipient),
+        );
+    }
+
+    /// Set the NFT contract function used to verify custody when listing.
+    /// It must take the token id (u64) and return the holder's address.
+    pub fn set_custody_fn(env: Env, admin: Address, custody_fn: Symbol) {
+        Self::require_admin(&env, &admin);
+
+        let mut config = Self::load_config(&env);
+        config.custody_fn = custody_fn;
+        env.storage().instance().set(&DataKey::Config, &config);
+    }
+
+    /// Require owners to lock `listing_bond` of `bond_token` per new listing
+    /// (admin only). The bond comes back on cancellation and is forfeited if
+    /// the admin removes the listing. Existing listings keep their bonds.
+    pub fn set_listing_bond(env: Env, admin: Address, bond_token: Address, listing_bond: i128) {
+        Self::require_admin(&env, &admin);
+
+        if listing_bond < 0 {
+            panic!("bond must be >= 0");
+        }
+
+        let mut config = Self::load_config(&env);
+        config.listing_bond = listing_bond;
+        config.bond_token = Some(bond_token.clone());
+        env.storage().instance().set(&DataKey::Config, &config);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_BOND_UPDATED),),
+            (bond_token, listing_bond),
+        );
+    }
+
+    /// Configure demand-based pricing (admin only). Each past rental raises a
+    /// dynamically priced listing by `demand_step_bps`, up to a multiplier of
+    /// `demand_cap_bps` (at least 10000, i.e. the base price).
+    pub fn set_demand_pricing(env: Env, admin: Address, demand_step_bps: u32, demand_cap_bps: u32) {
+        Self::require_admin(&env, &admin);
+
+        if (demand_cap_bps as i128) < BPS_DENOMINATOR {
+            panic!("cap below base price");
+        }
+
+        let mut config = Self::load_config(&env);
+        config.demand_step_bps = demand_step_bps;
+        config.demand_cap_bps = demand_cap_bps;
+        env.storage().instance().set(&DataKey::Config, &config);
+
+        env.events().publish(
+            (Symbol::new(&env, events::DEMAND_PRICING_UPDATED),),
+            (demand_step_bps, demand_cap_bps),
+        );
+    }
+
+    /// Get the current contract configuration.
+    pub fn get_config(env: Env) -> Config {
+        Self::load_config(&env)
+    }
+
     // ----------------------------------------------------------
     // Listing Management
     // ----------------------------------------------------------
@@ -203,70 +631,42 @@ impl PuzzleRentalContract {
     ) -> u64 {
         owner.require_auth();
 
-        if price_per_period <= 0 {
-            panic!("price must be positive");
-        }
-        if period_duration == 0 {
-            panic!("period duration must be > 0");
-        }
-        if max_periods == 0 {
-            panic!("max periods must be > 0");
-        }
-        if early_termination_refund_pct > 100 {
-            panic!("refund pct must be 0-100");
-        }
-
-        let listing_id = Self::next_listing_id(&env);
-        let now = env.ledger().timestamp();
-
-        let listing = RentalListing {
-            listing_id,
+        let input = RentalListingInput {
             nft_contract,
             nft_token_id,
-            owner: owner.clone(),
+            payment_token,
             price_per_period,
             period_duration,
             max_periods,
-            payment_token,
-            status: ListingStatus::Active,
-            created_at: now,
             allow_extensions,
             early_termination_refund_pct,
+            tags: Vec::new(&env),
+            metadata_uri: String::from_str(&env, ""),
         };
+        Self::insert_listings(&env, &owner, &Vec::from_array(&env, [input]))
+            .get(0)
+            .unwrap()
+    }
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Listing(listing_id), &listing);
-
-        // Track owner's listings
-        let mut owner_listings: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerListings(owner.clone()))
-            .unwrap_or_else(|| Vec::new(&env));
-        owner_listings.push_back(listing_id);
-        env.storage()
-            .persistent()
-            .set(&DataKey::OwnerListings(owner), &owner_listings);
-
-        // Add to active listings marketplace index
-        let mut active: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::ActiveListings)
-            .unwrap_or_else(|| Vec::new(&env));
-        active.push_back(listing_id);
-        env.storage()
-            .instance()
-            .set(&DataKey::ActiveListings, &active);
+    /// Create several listings in one invocation, e.g. for a whole collection.
+    /// Every entry is validated before anything is written; ids are assigned
+    /// sequentially and returned in input order. At most `MAX_BATCH_SIZE`
+    /// entries are accepted per call.
+    pub fn create_listings_batch(
+        env: Env,
+        owner: Address,
+        listings: Vec<RentalListingInput>,
+    ) -> Vec<u64> {
+        owner.require_auth();
 
-        env.events().publish(
-            (Symbol::new(&env, events::LISTING_CREATED),),
-            listing_id,
-        );
+        if listings.is_empty() {
+            panic!("batch is empty");
+        }
+        if listings.len() > MAX_BATCH_SIZE {
+            panic!("batch too large");
+        }
 
-        log!(&env, "Listing created: {}", listing_id);
-        listing_id
+        Self::insert_listings(&env, &owner, &listings)
     }
 
     /// Cancel a listing (owner only). Cannot cancel if there's an active rental.
@@ -289,376 +689,2017 @@ impl PuzzleRentalContract {
             .set(&DataKey::Listing(listing_id), &listing);
 
         // Remove from active listings index
-        Self::remove_from_active_listings(&env, listing_id);
+        Self::remove_from_active_listings(&env, &listing);
+        Self::release_listing_bond(&env, listing_id, &owner);
 
         env.events().publish(
             (Symbol::new(&env, events::LISTING_CANCELLED),),
-            listing_id,
+            (listing_id, owner, env.ledger().timestamp()),
         );
     }
 
-    // ----------------------------------------------------------
-    // Rental Operations
-    // ----------------------------------------------------------
-
-    /// Accept a rental listing and pay for access.
-    /// `periods` is how many rental periods the renter wants.
-    pub fn rent(
-        env: Env,
-        renter: Address,
-        listing_id: u64,
-        periods: u32,
-    ) -> u64 {
-        renter.require_auth();
-
-        if periods == 0 {
-            panic!("periods must be > 0");
-        }
+    /// Cancel a scam or stolen-NFT listing (admin only). Any listing bond is
+    /// forfeited to the fee recipient instead of returned to the owner, and
+    /// every in-flight rental is terminated with the renter refunded the
+    /// unused share of what they paid plus any held deposit.
+    pub fn admin_remove_listing(env: Env, admin: Address, listing_id: u64, reason: Symbol) {
+        Self::require_admin(&env, &admin);
 
-        let listing: RentalListing = env
+        let mut listing: RentalListing = env
             .storage()
             .persistent()
             .get(&DataKey::Listing(listing_id))
             .unwrap_or_else(|| panic!("listing not found"));
 
-        if listing.status != ListingStatus::Active {
-            panic!("listing not active");
-        }
-        if periods > listing.max_periods {
-            panic!("exceeds max periods");
-        }
-        if listing.owner == renter {
-            panic!("owner cannot rent own listing");
+        if listing.status == ListingStatus::Cancelled {
+            panic!("listing is cancelled");
         }
 
-        let total_cost = listing.price_per_period * periods as i128;
-        let now = env.ledger().timestamp();
-        let end_time = now + listing.period_duration * periods as u64;
-
-        // Escrow payment in the contract; disbursed to owner on expiry/termination.
-        let payment_client = token::Client::new(&env, &listing.payment_token);
-        let contract_address = env.current_contract_address();
-        payment_client.transfer(&renter, &contract_address, &total_cost);
-
-        let rental_id = Self::next_rental_id(&env);
-
-        let rental = RentalAgreement {
-            rental_id,
-            listing_id,
-            nft_contract: listing.nft_contract.clone(),
-            nft_token_id: listing.nft_token_id,
-            owner: listing.owner.clone(),
-            renter: renter.clone(),
-            payment_token: listing.payment_token.clone(),
-            total_paid: total_cost,
-            price_per_period: listing.price_per_period,
-            periods,
-            start_time: now,
-            end_time,
-            status: RentalStatus::Active,
-            early_termination_refund_pct: listing.early_termination_refund_pct,
-        };
-
+        listing.status = ListingStatus::Cancelled;
         env.storage()
             .persistent()
-            .set(&DataKey::Rental(rental_id), &rental);
+            .set(&DataKey::Listing(listing_id), &listing);
 
-        // Track renter's rentals
-        let mut renter_rentals: Vec<u64> = env
+        Self::remove_from_active_listings(&env, &listing);
+        let config = Self::load_config(&env);
+        Self::release_listing_bond(&env, listing_id, &config.fee_recipient);
+
+        let now = env.ledger().timestamp();
+        let rental_ids: Vec<u64> = env
             .storage()
             .persistent()
-            .get(&DataKey::RenterRentals(renter.clone()))
+            .get(&DataKey::RentalsByNft(listing.nft_contract.clone(), listing.nft_token_id))
             .unwrap_or_else(|| Vec::new(&env));
-        renter_rentals.push_back(rental_id);
-        env.storage()
-            .persistent()
-            .set(&DataKey::RenterRentals(renter), &renter_rentals);
+        for rental_id in rental_ids.iter() {
+            let mut rental: RentalAgreement = match env
+                .storage()
+                .persistent()
+                .get(&DataKey::Rental(rental_id))
+            {
+                Some(r) => r,
+                None => continue,
+            };
+            if rental.listing_id == listing_id && rental.status == RentalStatus::Active {
+                Self::refund_removed_rental(&env, &mut rental, now);
+            }
+        }
 
         env.events().publish(
-            (Symbol::new(&env, events::RENTAL_STARTED),),
-            (rental_id, listing_id, total_cost),
+            (Symbol::new(&env, events::LISTING_REMOVED),),
+            (listing_id, reason, now),
         );
-
-        log!(&env, "Rental started: id={} listing={} cost={}", rental_id, listing_id, total_cost);
-        rental_id
     }
 
-    /// Extend an active rental by additional periods.
-    pub fn extend_rental(env: Env, renter: Address, rental_id: u64, additional_periods: u32) {
-        renter.require_auth();
+    /// Get the bond locked for a listing as (token, amount), if any.
+    pub fn get_listing_bond(env: Env, listing_id: u64) -> Option<(Address, i128)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ListingBond(listing_id))
+    }
 
-        if additional_periods == 0 {
-            panic!("additional periods must be > 0");
-        }
+    /// Temporarily pause an active listing (owner only). Paused listings are
+    /// hidden from the marketplace and cannot be rented until resumed.
+    pub fn pause_listing(env: Env, owner: Address, listing_id: u64) {
+        owner.require_auth();
 
-        let mut rental: RentalAgreement = env
+        let mut listing: RentalListing = env
             .storage()
             .persistent()
-            .get(&DataKey::Rental(rental_id))
-            .unwrap_or_else(|| panic!("rental not found"));
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
 
-        if rental.renter != renter {
-            panic!("not the renter");
+        if listing.owner != owner {
+            panic!("not the owner");
         }
-
-        Self::auto_expire_if_needed(&env, &mut rental);
-
-        if rental.status != RentalStatus::Active {
-            panic!("rental is not active");
+        if listing.status == ListingStatus::Cancelled {
+            panic!("listing is cancelled");
+        }
+        if listing.status != ListingStatus::Active {
+            panic!("listing not active");
         }
 
-        let listing: RentalListing = env
-            .storage()
+        listing.status = ListingStatus::Paused;
+        env.storage()
             .persistent()
-            .get(&DataKey::Listing(rental.listing_id))
-            .unwrap_or_else(|| panic!("listing not found"));
+            .set(&DataKey::Listing(listing_id), &listing);
 
-        if !listing.allow_extensions {
-            panic!("extensions not allowed for this listing");
-        }
+        Self::remove_from_active_listings(&env, &listing);
 
-        let new_total_periods = rental.periods + additional_periods;
-        if new_total_periods > listing.max_periods {
-            panic!("exceeds max periods");
-        }
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_PAUSED),),
+            (listing_id, env.ledger().timestamp()),
+        );
+    }
 
-        let extension_cost = listing.price_per_period * additional_periods as i128;
+    /// Resume a paused listing (owner only), returning it to the marketplace.
+    pub fn resume_listing(env: Env, owner: Address, listing_id: u64) {
+        owner.require_auth();
 
-        // Escrow additional payment in the contract.
-        let payment_client = token::Client::new(&env, &listing.payment_token);
-        let contract_address = env.current_contract_address();
-        payment_client.transfer(&renter, &contract_address, &extension_cost);
+        let mut listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
 
-        rental.end_time += listing.period_duration * additional_periods as u64;
-        rental.total_paid += extension_cost;
-        rental.periods = new_total_periods;
+        if listing.owner != owner {
+            panic!("not the owner");
+        }
+        if listing.status == ListingStatus::Cancelled {
+            panic!("listing is cancelled");
+        }
+        if listing.status != ListingStatus::Paused {
+            panic!("listing not paused");
+        }
 
+        listing.status = ListingStatus::Active;
         env.storage()
             .persistent()
-            .set(&DataKey::Rental(rental_id), &rental);
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        Self::add_to_active_listings(&env, &listing);
 
         env.events().publish(
-            (Symbol::new(&env, events::RENTAL_EXTENDED),),
-            (rental_id, additional_periods, extension_cost),
+            (Symbol::new(&env, events::LISTING_RESUMED),),
+            (listing_id, env.ledger().timestamp()),
         );
-
-        log!(&env, "Rental extended: id={} added_periods={} cost={}", rental_id, additional_periods, extension_cost);
     }
 
-    /// Terminate a rental early. Renter gets a partial refund based on
-    /// the listing's `early_termination_refund_pct` of unused time.
-    pub fn terminate_rental(env: Env, renter: Address, rental_id: u64) {
-        renter.require_auth();
+    /// Change the per-period price of an Active or Paused listing (owner only).
+    /// Existing rentals keep the price they were started with.
+    pub fn update_listing_price(env: Env, owner: Address, listing_id: u64, new_price: i128) {
+        owner.require_auth();
 
-        let mut rental: RentalAgreement = env
+        if new_price <= 0 {
+            panic!("price must be positive");
+        }
+
+        let mut listing: RentalListing = env
             .storage()
             .persistent()
-            .get(&DataKey::Rental(rental_id))
-            .unwrap_or_else(|| panic!("rental not found"));
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
 
-        if rental.renter != renter {
-            panic!("not the renter");
+        if listing.owner != owner {
+            panic!("not the owner");
         }
+        Self::ensure_listing_open(&listing);
 
-        Self::auto_expire_if_needed(&env, &mut rental);
-
-        if rental.status != RentalStatus::Active {
-            panic!("rental is not active");
-        }
+        let old_price = listing.price_per_period;
+        listing.price_per_period = new_price;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
 
-        let now = env.ledger().timestamp();
-        let remaining_time = if now < rental.end_time {
-            rental.end_time - now
-        } else {
-            0
-        };
-        let total_duration = rental.end_time - rental.start_time;
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_PRICE_UPDATED),),
+            (listing_id, old_price, new_price),
+        );
+    }
 
-        // Calculate refund for unused portion
-        let refund = if remaining_time > 0 && total_duration > 0 && rental.early_termination_refund_pct > 0 {
-            let unused_ratio_numerator = remaining_time as i128;
-            let unused_ratio_denominator = total_duration as i128;
-            let raw_refund = rental.total_paid * unused_ratio_numerator / unused_ratio_denominator;
-            raw_refund * rental.early_termination_refund_pct as i128 / 100
-        } else {
-            0
-        };
+    /// Set the security deposit renters must post for future rentals (owner only).
+    /// A deposit of 0 disables it.
+    pub fn set_listing_deposit(env: Env, owner: Address, listing_id: u64, deposit_amount: i128) {
+        owner.require_auth();
 
-        let payment_client = token::Client::new(&env, &rental.payment_token);
-        let contract_address = env.current_contract_address();
-        let owner_share = rental.total_paid - refund;
-        // Pay owner their earned portion from escrow
-        if owner_share > 0 {
-            payment_client.transfer(&contract_address, &rental.owner, &owner_share);
-        }
-        // Refund unused portion to renter from escrow
-        if refund > 0 {
-            payment_client.transfer(&contract_address, &rental.renter, &refund);
+        if deposit_amount < 0 {
+            panic!("deposit must be non-negative");
         }
 
-        rental.status = RentalStatus::Terminated;
+        let mut listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
 
-        // Archive to history
-        Self::archive_rental(&env, &rental);
+        if listing.owner != owner {
+            panic!("not the owner");
+        }
+        Self::ensure_listing_open(&listing);
 
+        listing.deposit_amount = deposit_amount;
         env.storage()
             .persistent()
-            .set(&DataKey::Rental(rental_id), &rental);
+            .set(&DataKey::Listing(listing_id), &listing);
 
         env.events().publish(
-            (Symbol::new(&env, events::RENTAL_TERMINATED),),
-            (rental_id, refund),
+            (Symbol::new(&env, events::LISTING_DEPOSIT_SET),),
+            (listing_id, deposit_amount),
         );
-
-        log!(&env, "Rental terminated: id={} refund={}", rental_id, refund);
     }
 
-    /// Mark a rental as expired if its end_time has passed.
-    /// Anyone can call this to trigger expiration.
-    pub fn expire_rental(env: Env, rental_id: u64) {
-        let mut rental: RentalAgreement = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Rental(rental_id))
-            .unwrap_or_else(|| panic!("rental not found"));
+    /// Configure royalty payees (address, basis points) that share in this
+    /// listing's future rental revenue (owner only). The owner keeps whatever
+    /// is left after all cuts; total basis points may not exceed 10000.
+    pub fn set_royalty_payees(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        royalty_payees: Vec<(Address, u32)>,
+    ) {
+        owner.require_auth();
 
-        if rental.status != RentalStatus::Active {
-            panic!("rental already closed");
+        let mut total_bps: u32 = 0;
+        for (_, bps) in royalty_payees.iter() {
+            total_bps = total_bps.saturating_add(bps);
+        }
+        if total_bps as i128 > BPS_DENOMINATOR {
+            panic!("royalty bps exceed 10000");
         }
 
-        let now = env.ledger().timestamp();
-        if now < rental.end_time {
-            panic!("rental has not expired yet");
+        let mut listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
+
+        if listing.owner != owner {
+            panic!("not the owner");
         }
+        Self::ensure_listing_open(&listing);
 
-        rental.status = RentalStatus::Expired;
+        listing.royalty_payees = royalty_payees;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
 
-        // Release escrowed funds to owner on normal expiry
-        let payment_client = token::Client::new(&env, &rental.payment_token);
-        let contract_address = env.current_contract_address();
-        payment_client.transfer(&contract_address, &rental.owner, &rental.total_paid);
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_ROYALTIES_SET),),
+            (listing_id, total_bps),
+        );
+    }
 
-        // Archive to history
-        Self::archive_rental(&env, &rental);
+    /// Turn demand-based pricing on or off for a listing (owner only).
+    pub fn set_dynamic_pricing(env: Env, owner: Address, listing_id: u64, dynamic_pricing: bool) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
 
+        listing.dynamic_pricing = dynamic_pricing;
         env.storage()
             .persistent()
-            .set(&DataKey::Rental(rental_id), &rental);
+            .set(&DataKey::Listing(listing_id), &listing);
 
         env.events().publish(
-            (Symbol::new(&env, events::RENTAL_EXPIRED),),
-            rental_id,
+            (Symbol::new(&env, events::LISTING_DYNAMIC_PRICING_SET),),
+            (listing_id, dynamic_pricing),
         );
+    }
 
-        log!(&env, "Rental expired: id={}", rental_id);
+    /// Number of rentals a listing has had, which drives dynamic pricing.
+    pub fn get_demand_counter(env: Env, listing_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DemandCounter(listing_id))
+            .unwrap_or(0)
     }
 
-    // ----------------------------------------------------------
-    // Access Control
-    // ----------------------------------------------------------
+    /// Restrict when a listing can be rented (owner only). Rentals must start
+    /// at or after `available_from` and end by `available_until`; 0 leaves
+    /// that side of the window open.
+    pub fn set_availability(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        available_from: u64,
+        available_until: u64,
+    ) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        if available_until != 0 && available_until <= available_from {
+            panic!("invalid availability window");
+        }
 
-    /// Check if a renter currently has valid access to an NFT puzzle.
-    /// Returns true if access is granted, false otherwise.
-    pub fn has_access(env: Env, renter: Address, nft_contract: Address, nft_token_id: u64) -> bool {
-        let rental_ids: Vec<u64> = env
-            .storage()
+        listing.available_from = available_from;
+        listing.available_until = available_until;
+        env.storage()
             .persistent()
-            .get(&DataKey::RenterRentals(renter.clone()))
-            .unwrap_or_else(|| Vec::new(&env));
-
-        let now = env.ledger().timestamp();
+            .set(&DataKey::Listing(listing_id), &listing);
 
-        for rental_id in rental_ids.iter() {
-            let rental: RentalAgreement = match env
-                .storage()
-                .persistent()
-                .get(&DataKey::Rental(rental_id))
-            {
-                Some(r) => r,
-                None => continue,
-            };
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_AVAILABILITY_SET),),
+            (listing_id, available_from, available_until),
+        );
+    }
 
-            if rental.status == RentalStatus::Active
-                && rental.nft_contract == nft_contract
-                && rental.nft_token_id == nft_token_id
-                && now < rental.end_time
-            {
-                env.events().publish(
-                    (Symbol::new(&env, events::ACCESS_CHECKED),),
-                    (renter, true),
-                );
-                return true;
+    /// Split the owner's payout among co-owners by basis-point share (owner
+    /// only). Shares must sum to exactly 10000; pass an empty vec to pay the
+    /// owner alone. The owner keeps sole control of the listing either way.
+    pub fn set_co_owners(env: Env, owner: Address, listing_id: u64, co_owners: Vec<(Address, u32)>) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        if !co_owners.is_empty() {
+            let mut total_bps: u32 = 0;
+            for (_, bps) in co_owners.iter() {
+                total_bps = total_bps.saturating_add(bps);
+            }
+            if total_bps as i128 != BPS_DENOMINATOR {
+                panic!("co-owner shares must sum to 10000");
             }
         }
 
+        listing.co_owners = co_owners.clone();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
         env.events().publish(
-            (Symbol::new(&env, events::ACCESS_CHECKED),),
-            (renter, false),
+            (Symbol::new(&env, events::LISTING_CO_OWNERS_SET),),
+            (listing_id, co_owners.len()),
         );
-        false
     }
 
-    // ----------------------------------------------------------
-    // Marketplace Discovery
-    // ----------------------------------------------------------
+    /// Set the minimum rental length and an optional expiration timestamp for
+    /// a listing (owner only). `listing_expires_at` of 0 means never expires.
+    pub fn set_listing_terms(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        min_periods: u32,
+        listing_expires_at: u64,
+    ) {
+        owner.require_auth();
 
-    /// Get a page of active listings for marketplace discovery.
-    /// `offset` and `limit` allow pagination.
-    pub fn get_active_listings(env: Env, offset: u64, limit: u32) -> MarketplacePage {
-        let active_ids: Vec<u64> = env
+        let mut listing: RentalListing = env
             .storage()
-            .instance()
-            .get(&DataKey::ActiveListings)
-            .unwrap_or_else(|| Vec::new(&env));
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
 
-        let total = active_ids.len() as u64;
-        let mut listings = Vec::new(&env);
+        if listing.owner != owner {
+            panic!("not the owner");
+        }
+        Self::ensure_listing_open(&listing);
+        if min_periods == 0 || min_periods > listing.max_periods {
+            panic!("min periods must be 1..=max periods");
+        }
+        if listing_expires_at != 0 && listing_expires_at <= env.ledger().timestamp() {
+            panic!("expiration must be in the future");
+        }
 
-        let start = offset as u32;
-        let end = (offset as u32 + limit).min(active_ids.len());
+        listing.min_periods = min_periods;
+        listing.listing_expires_at = listing_expires_at;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
 
-        for i in start..end {
-            let listing_id = active_ids.get(i).unwrap();
-            if let Some(listing) = env
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_TERMS_SET),),
+            (listing_id, min_periods, listing_expires_at),
+        );
+    }
+
+    /// Batch-expire listings whose `listing_expires_at` has passed, removing
+    /// them from the marketplace indexes. Anyone can call this; ids that are
+    /// unknown, already closed, or not yet due are skipped.
+    /// Returns the number of listings that were expired.
+    pub fn expire_listings(env: Env, listing_ids: Vec<u64>) -> u32 {
+        let now = env.ledger().timestamp();
+        let mut expired = 0u32;
+
+        for listing_id in listing_ids.iter() {
+            let mut listing: RentalListing = match env
                 .storage()
                 .persistent()
-                .get::<DataKey, RentalListing>(&DataKey::Listing(listing_id))
+                .get(&DataKey::Listing(listing_id))
             {
-                if listing.status == ListingStatus::Active {
-                    listings.push_back(listing);
-                }
+                Some(l) => l,
+                None => continue,
+            };
+
+            if listing.status == ListingStatus::Cancelled
+                || listing.status == ListingStatus::Expired
+                || !Self::is_listing_past_expiry(&listing, now)
+            {
+                continue;
             }
+
+            listing.status = ListingStatus::Expired;
+            env.storage()
+                .persistent()
+                .set(&DataKey::Listing(listing_id), &listing);
+            Self::remove_from_active_listings(&env, &listing);
+
+            env.events().publish(
+                (Symbol::new(&env, events::LISTING_EXPIRED),),
+                (listing_id, now),
+            );
+            expired += 1;
         }
 
-        MarketplacePage { listings, total }
+        expired
     }
 
-    /// Get all listing ids for a given owner.
-    pub fn get_owner_listings(env: Env, owner: Address) -> Vec<u64> {
-        env.storage()
-            .persistent()
-            .get(&DataKey::OwnerListings(owner))
-            .unwrap_or_else(|| Vec::new(&env))
-    }
+    /// Allow or forbid renters of this listing from transferring their
+    /// rentals to another address (owner only).
+    pub fn set_listing_subleasable(env: Env, owner: Address, listing_id: u64, subleasable: bool) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
 
-    /// Get all rental ids for a given renter.
-    pub fn get_renter_rentals(env: Env, renter: Address) -> Vec<u64> {
+        listing.subleasable = subleasable;
         env.storage()
             .persistent()
-            .get(&DataKey::RenterRentals(renter))
-            .unwrap_or_else(|| Vec::new(&env))
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_SUBLEASABLE),),
+            (listing_id, subleasable),
+        );
     }
 
-    // ----------------------------------------------------------
-    // Query Helpers
-    // ----------------------------------------------------------
+    /// Configure long-term rental discounts for a listing (owner only).
+    /// Each tier is (minimum periods, discount bps); thresholds must be
+    /// strictly increasing and discounts may not exceed 10000 bps.
+    pub fn set_discount_tiers(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        discount_tiers: Vec<(u32, u32)>,
+    ) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        let mut prev_threshold = 0u32;
+        for (threshold, bps) in discount_tiers.iter() {
+            if threshold <= prev_threshold {
+                panic!("discount tiers must be sorted");
+            }
+            if bps as i128 > BPS_DENOMINATOR {
+                panic!("discount bps exceed 10000");
+            }
+            prev_threshold = threshold;
+        }
 
-    /// Get a listing by id.
-    pub fn get_listing(env: Env, listing_id: u64) -> RentalListing {
+        listing.discount_tiers = discount_tiers;
         env.storage()
             .persistent()
-            .get(&DataKey::Listing(listing_id))
-            .unwrap_or_else(|| panic!("listing not found"))
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_DISCOUNTS_SET),),
+            listing_id,
+        );
+    }
+
+    /// Choose between prepaid and pay-per-period billing for future rentals
+    /// (owner only). `payment_grace_seconds` is how late a pay-per-period
+    /// renter may be with the next payment before the owner can terminate.
+    pub fn set_billing_mode(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        billing_mode: BillingMode,
+        payment_grace_seconds: u64,
+    ) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        listing.billing_mode = billing_mode.clone();
+        listing.payment_grace_seconds = payment_grace_seconds;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_BILLING_SET),),
+            (listing_id, billing_mode, payment_grace_seconds),
+        );
+    }
+
+    /// Set how many rentals of a listing may run concurrently (owner only).
+    /// Use 1 for exclusive NFT rentals or more for multi-seat licenses.
+    pub fn set_max_concurrent_rentals(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        max_concurrent_rentals: u32,
+    ) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        if max_concurrent_rentals == 0 {
+            panic!("max concurrent rentals must be > 0");
+        }
+
+        listing.max_concurrent_rentals = max_concurrent_rentals;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_CAPACITY_SET),),
+            (listing_id, max_concurrent_rentals),
+        );
+    }
+
+    /// Set how long renters have to hand the NFT back after their rental
+    /// ends before the deposit becomes claimable (owner only).
+    pub fn set_return_grace(env: Env, owner: Address, listing_id: u64, return_grace_seconds: u64) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        listing.return_grace_seconds = return_grace_seconds;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_RETURN_GRACE_SET),),
+            (listing_id, return_grace_seconds),
+        );
+    }
+
+    /// Bundle additional NFTs into a listing so they are rented together
+    /// under its price (owner only). The owner must hold every token, and
+    /// the bundle cannot change while rentals of the listing are running.
+    /// Pass an empty vec to turn the listing back into a single-NFT one.
+    pub fn set_bundle_tokens(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        extra_tokens: Vec<(Address, u64)>,
+    ) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        if extra_tokens.len() > MAX_BUNDLE_SIZE {
+            panic!("bundle too large");
+        }
+        if Self::listing_active_rentals(&env, listing_id) > 0 {
+            panic!("listing has active rentals");
+        }
+
+        let config = Self::load_config(&env);
+        for (i, (nft_contract, nft_token_id)) in extra_tokens.iter().enumerate() {
+            let is_primary =
+                nft_contract == listing.nft_contract && nft_token_id == listing.nft_token_id;
+            let seen_before = extra_tokens
+                .iter()
+                .take(i)
+                .any(|(c, id)| c == nft_contract && id == nft_token_id);
+            if is_primary || seen_before {
+                panic!("duplicate token in bundle");
+            }
+            Self::verify_custody(&env, &config, &owner, &nft_contract, nft_token_id);
+        }
+
+        listing.extra_tokens = extra_tokens.clone();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_BUNDLE_SET),),
+            (listing_id, extra_tokens.len()),
+        );
+    }
+
+    /// Accept additional payment tokens, each with its own price per period
+    /// (owner only). The listing's `payment_token` stays accepted at
+    /// `price_per_period`; pass an empty vec to accept only that.
+    pub fn set_accepted_tokens(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        accepted_tokens: Vec<(Address, i128)>,
+    ) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        if accepted_tokens.len() > MAX_ACCEPTED_TOKENS {
+            panic!("too many accepted tokens");
+        }
+        for (i, (token, price)) in accepted_tokens.iter().enumerate() {
+            if price <= 0 {
+                panic!("price must be > 0");
+            }
+            let seen_before = accepted_tokens.iter().take(i).any(|(t, _)| t == token);
+            if token == listing.payment_token || seen_before {
+                panic!("duplicate payment token");
+            }
+        }
+
+        listing.accepted_tokens = accepted_tokens.clone();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_TOKENS_SET),),
+            (listing_id, accepted_tokens.len()),
+        );
+    }
+
+    /// Replace a listing's search tags and metadata URI (owner only).
+    pub fn set_listing_metadata(
+        env: Env,
+        owner: Address,
+        listing_id: u64,
+        tags: Vec<Symbol>,
+        metadata_uri: String,
+    ) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+        Self::validate_tags(&tags);
+
+        // Only Active listings are present in the tag index.
+        let indexed = listing.status == ListingStatus::Active;
+        if indexed {
+            Self::remove_from_tag_indexes(&env, &listing);
+        }
+        listing.tags = tags;
+        listing.metadata_uri = metadata_uri;
+        if indexed {
+            Self::add_to_tag_indexes(&env, &listing);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_METADATA_SET),),
+            (listing_id, listing.tags.clone()),
+        );
+    }
+
+    /// Number of rentals of a listing currently counted against its capacity.
+    pub fn get_listing_active_rentals(env: Env, listing_id: u64) -> u32 {
+        Self::listing_active_rentals(&env, listing_id)
+    }
+
+    // ----------------------------------------------------------
+    // Private Listings
+    // ----------------------------------------------------------
+
+    /// Toggle whether a listing is restricted to whitelisted renters (owner only).
+    /// The whitelist is kept when a listing is reopened, so it can be
+    /// restricted again later without re-adding renters.
+    pub fn set_listing_restricted(env: Env, owner: Address, listing_id: u64, restricted: bool) {
+        let mut listing = Self::load_owned_listing(&env, &owner, listing_id);
+        Self::ensure_listing_open(&listing);
+
+        listing.restricted = restricted;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(listing_id), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_RESTRICTED),),
+            (listing_id, restricted),
+        );
+    }
+
+    /// Whitelist a renter for a listing (owner only).
+    pub fn allow_renter(env: Env, owner: Address, listing_id: u64, renter: Address) {
+        Self::load_owned_listing(&env, &owner, listing_id);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::RenterAllowed(listing_id, renter.clone()), &true);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTER_ALLOWED),),
+            (listing_id, renter),
+        );
+    }
+
+    /// Remove a renter from a listing's whitelist (owner only).
+    /// Rentals already in progress are unaffected.
+    pub fn disallow_renter(env: Env, owner: Address, listing_id: u64, renter: Address) {
+        Self::load_owned_listing(&env, &owner, listing_id);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::RenterAllowed(listing_id, renter.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTER_DISALLOWED),),
+            (listing_id, renter),
+        );
+    }
+
+    /// Check whether a renter is on a listing's whitelist.
+    pub fn is_renter_allowed(env: Env, listing_id: u64, renter: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RenterAllowed(listing_id, renter))
+            .unwrap_or(false)
+    }
+
+    // ----------------------------------------------------------
+    // Reservations
+    // ----------------------------------------------------------
+
+    /// Hold an active, un-rented listing for `hold_seconds` so only `renter`
+    /// can rent it until the hold lapses. Returns the hold's expiry timestamp.
+    pub fn reserve_listing(env: Env, renter: Address, listing_id: u64, hold_seconds: u64) -> u64 {
+        renter.require_auth();
+
+        if hold_seconds == 0 || hold_seconds > MAX_RESERVATION_SECONDS {
+            panic!("invalid hold duration");
+        }
+
+        let listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
+
+        if listing.status != ListingStatus::Active {
+            panic!("listing not active");
+        }
+        if listing.owner == renter {
+            panic!("owner cannot rent own listing");
+        }
+        if Self::listing_active_rentals(&env, listing_id) >= listing.max_concurrent_rentals {
+            panic!("listing already rented");
+        }
+        let now = env.ledger().timestamp();
+        if Self::active_reservation(&env, listing_id, now).is_some() {
+            panic!("listing reserved");
+        }
+
+        let expires_at = now + hold_seconds;
+        env.storage().persistent().set(
+            &DataKey::Reservation(listing_id),
+            &Reservation {
+                listing_id,
+                renter: renter.clone(),
+                expires_at,
+            },
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, events::LISTING_RESERVED),),
+            (listing_id, renter, expires_at),
+        );
+        expires_at
+    }
+
+    /// Release a hold before it lapses (reserving renter only).
+    pub fn release_reservation(env: Env, renter: Address, listing_id: u64) {
+        renter.require_auth();
+
+        let reservation = Self::active_reservation(&env, listing_id, env.ledger().timestamp())
+            .unwrap_or_else(|| panic!("no active reservation"));
+        if reservation.renter != renter {
+            panic!("not the reserving renter");
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Reservation(listing_id));
+
+        env.events().publish(
+            (Symbol::new(&env, events::RESERVATION_RELEASED),),
+            (listing_id, renter),
+        );
+    }
+
+    /// Get the live reservation on a listing, if any. Lapsed holds are ignored.
+    pub fn get_reservation(env: Env, listing_id: u64) -> Option<Reservation> {
+        Self::active_reservation(&env, listing_id, env.ledger().timestamp())
+    }
+
+    // ----------------------------------------------------------
+    // Rental Operations
+    // ----------------------------------------------------------
+
+    /// Accept a rental listing and pay for access.
+    /// `periods` is how many rental periods the renter wants.
+    pub fn rent(
+        env: Env,
+        renter: Address,
+        listing_id: u64,
+        periods: u32,
+    ) -> u64 {
+        let now = env.ledger().timestamp();
+        Self::open_rental(env, renter, listing_id, periods, now, None)
+    }
+
+    /// Rent a listing paying in `chosen_token`, which must be the listing's
+    /// `payment_token` or one of its `accepted_tokens`. Refunds, extensions
+    /// and any deposit use the same token.
+    pub fn rent_with_token(
+        env: Env,
+        renter: Address,
+        listing_id: u64,
+        periods: u32,
+        chosen_token: Address,
+    ) -> u64 {
+        let now = env.ledger().timestamp();
+        Self::open_rental(env, renter, listing_id, periods, now, Some(chosen_token))
+    }
+
+    /// Book a rental that starts at a future `start_time`. Payment is taken
+    /// now; access begins at `start_time`. The owner may cancel it with a full
+    /// refund until it starts.
+    pub fn rent_scheduled(
+        env: Env,
+        renter: Address,
+        listing_id: u64,
+        periods: u32,
+        start_time: u64,
+    ) -> u64 {
+        if start_time < env.ledger().timestamp() {
+            panic!("start time is in the past");
+        }
+        Self::open_rental(env, renter, listing_id, periods, start_time, None)
+    }
+
+    /// Owner cancels a rental that has not started yet. The renter is refunded
+    /// the full rental fee and any held deposit, since no time was consumed.
+    pub fn owner_cancel_rental(env: Env, owner: Address, rental_id: u64) {
+        owner.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.owner != owner {
+            panic!("not the owner");
+        }
+        if rental.status != RentalStatus::Active {
+            panic!("rental is not active");
+        }
+        if rental.disputed {
+            panic!("rental is disputed");
+        }
+        if env.ledger().timestamp() >= rental.start_time {
+            panic!("rental already started");
+        }
+
+        let mut refund = rental.total_paid;
+        if rental.deposit_status == DepositStatus::Held {
+            refund += rental.deposit_amount;
+            rental.deposit_status = DepositStatus::Returned;
+        }
+        let payment_client = token::Client::new(&env, &rental.payment_token);
+        payment_client.transfer(&env.current_contract_address(), &rental.renter, &refund);
+
+        rental.status = RentalStatus::Terminated;
+        rental.termination_reason = TerminationReason::OwnerCancelled;
+        Self::adjust_listing_active_rentals(&env, rental.listing_id, false);
+
+        Self::archive_rental(&env, &rental);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_CANCELLED),),
+            (rental_id, refund, env.ledger().timestamp()),
+        );
+    }
+
+    /// Renter reclaims a rental the owner can no longer deliver because they
+    /// no longer hold the NFT (or any NFT in the bundle). Custody is checked
+    /// at call time and only before `end_time`, since the owner may move the
+    /// NFT once the rental is over. The renter is refunded the unused share of
+    /// the paid time plus any held deposit and the rental is terminated as
+    /// `CustodyLost`.
+    pub fn claim_failed_rental(env: Env, renter: Address, rental_id: u64) {
+        renter.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.renter != renter {
+            panic!("not the renter");
+        }
+        if rental.status != RentalStatus::Active {
+            panic!("rental is not active");
+        }
+        if rental.disputed {
+            panic!("rental is disputed");
+        }
+        let now = env.ledger().timestamp();
+        if now >= rental.end_time {
+            panic!("rental period has ended");
+        }
+
+        let config = Self::load_config(&env);
+        let mut held = Self::nft_holder(&env, &config, &rental.nft_contract, rental.nft_token_id)
+            == rental.owner;
+        for (nft_contract, nft_token_id) in rental.extra_tokens.iter() {
+            held = held && Self::nft_holder(&env, &config, &nft_contract, nft_token_id) == rental.owner;
+        }
+        if held {
+            panic!("owner still holds nft");
+        }
+
+        let refund = Self::refund_unused_time(&env, &mut rental, now);
+
+        rental.status = RentalStatus::Terminated;
+        rental.termination_reason = TerminationReason::CustodyLost;
+        Self::adjust_listing_active_rentals(&env, rental.listing_id, false);
+
+        Self::archive_rental(&env, &rental);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_FAILED),),
+            (rental_id, refund, now),
+        );
+    }
+
+    fn open_rental(
+        env: Env,
+        renter: Address,
+        listing_id: u64,
+        periods: u32,
+        start_time: u64,
+        chosen_token: Option<Address>,
+    ) -> u64 {
+        renter.require_auth();
+
+        if periods == 0 {
+            panic!("periods must be > 0");
+        }
+
+        let listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
+
+        if listing.status != ListingStatus::Active {
+            panic!("listing not active");
+        }
+        if Self::is_listing_past_expiry(&listing, env.ledger().timestamp()) {
+            panic!("listing expired");
+        }
+        if periods > listing.max_periods {
+            panic!("exceeds max periods");
+        }
+        if periods < listing.min_periods {
+            panic!("below min periods");
+        }
+        if listing.owner == renter {
+            panic!("owner cannot rent own listing");
+        }
+        if listing.restricted && !Self::is_renter_allowed(env.clone(), listing_id, renter.clone()) {
+            panic!("renter not whitelisted");
+        }
+        if Self::listing_active_rentals(&env, listing_id) >= listing.max_concurrent_rentals {
+            panic!("listing at capacity");
+        }
+
+        let config = Self::load_config(&env);
+        if config.max_active_rentals_per_renter > 0
+            && Self::count_active_rentals(&env, &renter) >= config.max_active_rentals_per_renter
+        {
+            panic!("renter at active rental cap");
+        }
+
+        // The owner must still hold every NFT being rented out.
+        Self::verify_custody(&env, &config, &listing.owner, &listing.nft_contract, listing.nft_token_id);
+        for (nft_contract, nft_token_id) in listing.extra_tokens.iter() {
+            Self::verify_custody(&env, &config, &listing.owner, &nft_contract, nft_token_id);
+        }
+
+        // Honor a live hold: only the reserving renter may proceed, consuming it.
+        if let Some(reservation) =
+            Self::active_reservation(&env, listing_id, env.ledger().timestamp())
+        {
+            if reservation.renter != renter {
+                panic!("listing reserved");
+            }
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Reservation(listing_id));
+
+        let payment_token = chosen_token.unwrap_or_else(|| listing.payment_token.clone());
+        let mut price_per_period = Self::price_in(&listing, &payment_token)
+            .unwrap_or_else(|| panic!("payment token not accepted"));
+
+        // Popular listings cost more: each past rental adds a step, up to the cap.
+        let demand = Self::get_demand_counter(env.clone(), listing_id);
+        if listing.dynamic_pricing {
+            let multiplier_bps = (BPS_DENOMINATOR
+                + demand as i128 * config.demand_step_bps as i128)
+                .min(config.demand_cap_bps as i128);
+            price_per_period = price_per_period * multiplier_bps / BPS_DENOMINATOR;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::DemandCounter(listing_id), &(demand + 1));
+
+        // Pay-per-period rentals are charged one undiscounted period up front.
+        let (total_cost, periods_paid) = match listing.billing_mode {
+            BillingMode::Prepaid => {
+                let gross_cost = price_per_period * periods as i128;
+                let discount_bps = Self::discount_bps_for(&listing, periods);
+                (gross_cost - gross_cost * discount_bps as i128 / BPS_DENOMINATOR, periods)
+            }
+            BillingMode::PayPerPeriod => (price_per_period, 1),
+        };
+        let end_time = start_time + listing.period_duration * periods as u64;
+        if start_time < listing.available_from
+            || (listing.available_until != 0 && start_time >= listing.available_until)
+        {
+            panic!("outside availability window");
+        }
+        if Self::exceeds_availability(&listing, end_time) {
+            panic!("rental exceeds availability window");
+        }
+
+        // Escrow payment in the contract; disbursed to owner on expiry/termination.
+        // The security deposit is held alongside the fee until the owner settles it.
+        let payment_client = token::Client::new(&env, &payment_token);
+        let contract_address = env.current_contract_address();
+        payment_client.transfer(&renter, &contract_address, &(total_cost + listing.deposit_amount));
+
+        let deposit_status = if listing.deposit_amount > 0 {
+            DepositStatus::Held
+        } else {
+            DepositStatus::None
+        };
+
+        let rental_id = Self::next_rental_id(&env);
+        Self::adjust_listing_active_rentals(&env, listing_id, true);
+        Self::bump_stats(&env, 0, 1, total_cost, 0);
+
+        let rental = RentalAgreement {
+            rental_id,
+            listing_id,
+            nft_contract: listing.nft_contract.clone(),
+            nft_token_id: listing.nft_token_id,
+            owner: listing.owner.clone(),
+            renter: renter.clone(),
+            payment_token,
+            total_paid: total_cost,
+            price_per_period,
+            periods,
+            start_time,
+            end_time,
+            status: RentalStatus::Active,
+            early_termination_refund_pct: listing.early_termination_refund_pct,
+            deposit_amount: listing.deposit_amount,
+            deposit_status,
+            royalty_payees: listing.royalty_payees.clone(),
+            fee_bps: config.fee_bps,
+            billing_mode: listing.billing_mode.clone(),
+            periods_paid,
+            payment_grace_seconds: listing.payment_grace_seconds,
+            return_grace_seconds: listing.return_grace_seconds,
+            returned_at: 0,
+            extra_tokens: listing.extra_tokens.clone(),
+            disputed: false,
+            dispute_reason: None,
+            auto_renew: false,
+            renewal_periods: periods,
+            co_owners: listing.co_owners.clone(),
+            termination_reason: TerminationReason::None,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        // Track renter's rentals
+        let mut renter_rentals: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RenterRentals(renter.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        renter_rentals.push_back(rental_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::RenterRentals(renter.clone()), &renter_rentals);
+
+        // Track rentals per NFT so buyers can check for attached obligations
+        Self::add_to_nft_index(&env, &listing.nft_contract, listing.nft_token_id, rental_id);
+        for (nft_contract, nft_token_id) in listing.extra_tokens.iter() {
+            Self::add_to_nft_index(&env, &nft_contract, nft_token_id, rental_id);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_STARTED),),
+            (rental_id, listing_id, renter.clone(), total_cost, start_time, end_time),
+        );
+
+        log!(&env, "Rental started: id={} listing={} cost={}", rental_id, listing_id, total_cost);
+        rental_id
+    }
+
+    /// Extend an active rental by additional periods.
+    pub fn extend_rental(env: Env, renter: Address, rental_id: u64, additional_periods: u32) {
+        renter.require_auth();
+
+        if additional_periods == 0 {
+            panic!("additional periods must be > 0");
+        }
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.renter != renter {
+            panic!("not the renter");
+        }
+
+        Self::auto_expire_if_needed(&env, &mut rental);
+
+        if rental.status != RentalStatus::Active {
+            panic!("rental is not active");
+        }
+
+        let listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(rental.listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
+
+        if !listing.allow_extensions {
+            panic!("extensions not allowed for this listing");
+        }
+
+        let new_total_periods = rental.periods + additional_periods;
+        if new_total_periods > listing.max_periods {
+            panic!("exceeds max periods");
+        }
+        let new_end_time = rental.end_time + listing.period_duration * additional_periods as u64;
+        if Self::exceeds_availability(&listing, new_end_time) {
+            panic!("rental exceeds availability window");
+        }
+
+        // Extensions keep the price locked in on the agreement. Pay-per-period
+        // rentals only lengthen the term; each period is paid later through
+        // `pay_next_period`.
+        let extension_cost = match rental.billing_mode {
+            BillingMode::Prepaid => rental.price_per_period * additional_periods as i128,
+            BillingMode::PayPerPeriod => 0,
+        };
+
+        // Escrow additional payment in the contract.
+        let payment_client = token::Client::new(&env, &rental.payment_token);
+        let contract_address = env.current_contract_address();
+        if extension_cost > 0 {
+            payment_client.transfer(&renter, &contract_address, &extension_cost);
+        }
+
+        rental.end_time = new_end_time;
+        rental.total_paid += extension_cost;
+        Self::bump_stats(&env, 0, 0, extension_cost, 0);
+        rental.periods = new_total_periods;
+        if rental.billing_mode == BillingMode::Prepaid {
+            rental.periods_paid = new_total_periods;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_EXTENDED),),
+            (rental_id, extension_cost, rental.end_time),
+        );
+
+        log!(&env, "Rental extended: id={} added_periods={} cost={}", rental_id, additional_periods, extension_cost);
+    }
+
+    /// Terminate a rental early. Either the renter or the owner may call this.
+    /// The renter gets a partial refund of the unused periods scaled by the
+    /// listing's `early_termination_refund_pct`; the rest goes to the owner.
+    /// Rentals past their `end_time` must be closed via `expire_rental`.
+    pub fn terminate_rental(env: Env, caller: Address, rental_id: u64) {
+        caller.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if caller != rental.renter && caller != rental.owner {
+            panic!("not a party to this rental");
+        }
+        if rental.status != RentalStatus::Active {
+            panic!("rental is not active");
+        }
+        if rental.disputed {
+            panic!("rental is disputed");
+        }
+
+        let now = env.ledger().timestamp();
+        if now >= rental.end_time {
+            panic!("rental period has ended; use expire_rental");
+        }
+
+        // Only time that has been paid for can be refunded.
+        let paid_until = Self::paid_until(&rental);
+        let total_duration = paid_until - rental.start_time;
+        let remaining_time = paid_until.saturating_sub(now).min(total_duration);
+
+        // refund = total_paid * pct / 100 * remaining_periods / periods, where the
+        // remaining fraction of paid periods is measured in ledger seconds.
+        let refund = if total_duration > 0 && rental.early_termination_refund_pct > 0 {
+            let raw_refund = rental.total_paid * remaining_time as i128 / total_duration as i128;
+            raw_refund * rental.early_termination_refund_pct as i128 / 100
+        } else {
+            0
+        };
+
+        let payment_client = token::Client::new(&env, &rental.payment_token);
+        let contract_address = env.current_contract_address();
+        let owner_share = rental.total_paid - refund;
+        // Pay owner their earned portion from escrow
+        if owner_share > 0 {
+            Self::pay_owner(&env, &rental, owner_share);
+        }
+        // Refund unused portion to renter from escrow
+        if refund > 0 {
+            payment_client.transfer(&contract_address, &rental.renter, &refund);
+        }
+
+        rental.status = RentalStatus::Terminated;
+        rental.termination_reason = TerminationReason::Early;
+        Self::adjust_listing_active_rentals(&env, rental.listing_id, false);
+
+        // Archive to history
+        Self::archive_rental(&env, &rental);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_TERMINATED),),
+            (rental_id, refund, now),
+        );
+
+        log!(&env, "Rental terminated: id={} refund={}", rental_id, refund);
+    }
+
+    /// Hand the remaining time of an active rental to another address.
+    /// The rental payment stays with the agreement; a held security deposit is
+    /// paid by the new renter straight back to the current one, so whoever
+    /// holds the rental has funded its deposit. Auto-renew is switched off,
+    /// since the new renter has not opted in to it.
+    pub fn transfer_rental(env: Env, current_renter: Address, rental_id: u64, new_renter: Address) {
+        current_renter.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.renter != current_renter {
+            panic!("not the renter");
+        }
+        if rental.status != RentalStatus::Active
+            || env.ledger().timestamp() >= rental.end_time
+        {
+            panic!("rental is not active");
+        }
+        if new_renter == current_renter || new_renter == rental.owner {
+            panic!("invalid new renter");
+        }
+        if rental.disputed {
+            panic!("rental is disputed");
+        }
+
+        let listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(rental.listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
+
+        if !listing.subleasable {
+            panic!("listing does not allow subleasing");
+        }
+        if listing.restricted
+            && !Self::is_renter_allowed(env.clone(), listing.listing_id, new_renter.clone())
+        {
+            panic!("renter not whitelisted");
+        }
+        let config = Self::load_config(&env);
+        if config.max_active_rentals_per_renter > 0
+            && Self::count_active_rentals(&env, &new_renter) >= config.max_active_rentals_per_renter
+        {
+            panic!("renter at active rental cap");
+        }
+
+        // The deposit is later returned to `rental.renter`, so the new
+        // renter takes it over from the current one
+        if rental.deposit_status == DepositStatus::Held && rental.deposit_amount > 0 {
+            new_renter.require_auth();
+            token::Client::new(&env, &rental.payment_token).transfer(
+                &new_renter,
+                &current_renter,
+                &rental.deposit_amount,
+            );
+        }
+
+        // Move the rental between the renters' indexes
+        let old_key = DataKey::RenterRentals(current_renter.clone());
+        let old_rentals: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&old_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        env.storage()
+            .persistent()
+            .set(&old_key, &Self::without_id(&env, &old_rentals, rental_id));
+
+        let new_key = DataKey::RenterRentals(new_renter.clone());
+        let mut new_rentals: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&new_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        new_rentals.push_back(rental_id);
+        env.storage().persistent().set(&new_key, &new_rentals);
+
+        rental.renter = new_renter.clone();
+        rental.auto_renew = false;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_TRANSFERRED),),
+            (rental_id, current_renter, new_renter),
+        );
+    }
+
+    /// Pay for the next period of a pay-per-period rental. Must be paid before
+    /// the current paid-through time plus the grace window lapses.
+    pub fn pay_next_period(env: Env, renter: Address, rental_id: u64) {
+        renter.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.renter != renter {
+            panic!("not the renter");
+        }
+        if rental.status != RentalStatus::Active {
+            panic!("rental is not active");
+        }
+        if rental.billing_mode != BillingMode::PayPerPeriod {
+            panic!("rental is prepaid");
+        }
+        if rental.periods_paid >= rental.periods {
+            panic!("all periods paid");
+        }
+        if Self::is_overdue_payment(&env, &rental) {
+            panic!("payment window lapsed");
+        }
+
+        let payment_client = token::Client::new(&env, &rental.payment_token);
+        payment_client.transfer(
+            &renter,
+            &env.current_contract_address(),
+            &rental.price_per_period,
+        );
+
+        rental.periods_paid += 1;
+        rental.total_paid += rental.price_per_period;
+        Self::bump_stats(&env, 0, 0, rental.price_per_period, 0);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::PERIOD_PAID),),
+            (rental_id, rental.periods_paid, rental.price_per_period, Self::paid_until(&rental)),
+        );
+    }
+
+    /// Whether a pay-per-period rental has gone unpaid past its grace window.
+    /// The owner may then end it via `terminate_rental`.
+    pub fn is_payment_overdue(env: Env, rental_id: u64) -> bool {
+        let rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+        rental.status == RentalStatus::Active && Self::is_overdue_payment(&env, &rental)
+    }
+
+    /// Opt a prepaid rental in or out of automatic renewal (renter only).
+    /// Renewals are paid through a token allowance the renter grants this
+    /// contract.
+    pub fn set_auto_renew(env: Env, renter: Address, rental_id: u64, auto_renew: bool) {
+        renter.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.renter != renter {
+            panic!("not the renter");
+        }
+        if rental.status != RentalStatus::Active {
+            panic!("rental is not active");
+        }
+        if rental.billing_mode != BillingMode::Prepaid {
+            panic!("auto-renew requires prepaid billing");
+        }
+
+        rental.auto_renew = auto_renew;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::AUTO_RENEW_SET),),
+            (rental_id, auto_renew),
+        );
+    }
+
+    /// Renew an auto-renewing rental once its `end_time` has passed. Anyone
+    /// (typically a keeper) may call this. Another `renewal_periods` worth of
+    /// payment, at the price locked in on the agreement, is pulled from the
+    /// renter's allowance and the rental is extended from its old end. If the listing is no longer rentable, the
+    /// term would exceed `max_periods` or the availability window, or the
+    /// payment fails, the rental is expired instead. Returns whether the
+    /// rental was renewed.
+    pub fn process_rental_renewal(env: Env, rental_id: u64) -> bool {
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.status != RentalStatus::Active {
+            panic!("rental already closed");
+        }
+        if !rental.auto_renew {
+            panic!("auto-renew not enabled");
+        }
+        let now = env.ledger().timestamp();
+        if now < rental.end_time {
+            panic!("rental has not ended yet");
+        }
+
+        let listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(rental.listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
+
+        let new_total_periods = rental.periods + rental.renewal_periods;
+        let new_end_time =
+            rental.end_time + listing.period_duration * rental.renewal_periods as u64;
+        let renewable = listing.status == ListingStatus::Active
+            && !Self::is_listing_past_expiry(&listing, now)
+            && new_total_periods <= listing.max_periods
+            && !Self::exceeds_availability(&listing, new_end_time);
+
+        let renewal_cost = rental.price_per_period * rental.renewal_periods as i128;
+        let contract_address = env.current_contract_address();
+        let paid = renewable
+            && token::Client::new(&env, &rental.payment_token)
+                .try_transfer_from(&contract_address, &rental.renter, &contract_address, &renewal_cost)
+                .is_ok();
+
+        if !paid {
+            Self::close_expired_rental(&env, &mut rental);
+            return false;
+        }
+
+        rental.end_time = new_end_time;
+        rental.total_paid += renewal_cost;
+        Self::bump_stats(&env, 0, 0, renewal_cost, 0);
+        rental.periods = new_total_periods;
+        rental.periods_paid = new_total_periods;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_RENEWED),),
+            (rental_id, renewal_cost, rental.end_time),
+        );
+        true
+    }
+
+    /// Mark a rental as expired if its end_time has passed.
+    /// Anyone can call this to trigger expiration.
+    pub fn expire_rental(env: Env, rental_id: u64) {
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.status != RentalStatus::Active {
+            panic!("rental already closed");
+        }
+
+        let now = env.ledger().timestamp();
+        if now < rental.end_time {
+            panic!("rental has not expired yet");
+        }
+
+        Self::close_expired_rental(&env, &mut rental);
+    }
+
+    /// Batch-expire overdue rentals. Anyone (e.g. a keeper bot) can call this.
+    /// Ids that are unknown, already closed, or not yet due are skipped.
+    /// Returns the number of rentals that were expired.
+    pub fn expire_rentals(env: Env, rental_ids: Vec<u64>) -> u32 {
+        let now = env.ledger().timestamp();
+        let mut expired = 0u32;
+
+        for rental_id in rental_ids.iter() {
+            let mut rental: RentalAgreement = match env
+                .storage()
+                .persistent()
+                .get(&DataKey::Rental(rental_id))
+            {
+                Some(r) => r,
+                None => continue,
+            };
+
+            if rental.status != RentalStatus::Active || now < rental.end_time {
+                continue;
+            }
+
+            Self::close_expired_rental(&env, &mut rental);
+            expired += 1;
+        }
+
+        expired
+    }
+
+    // ----------------------------------------------------------
+    // Security Deposits
+    // ----------------------------------------------------------
+
+    /// Release a rental's held deposit back to the renter once the NFT has
+    /// been returned (owner only).
+    pub fn return_deposit(env: Env, owner: Address, rental_id: u64) {
+        let mut rental = Self::load_held_deposit(&env, &owner, rental_id);
+
+        let payment_client = token::Client::new(&env, &rental.payment_token);
+        payment_client.transfer(
+            &env.current_contract_address(),
+            &rental.renter,
+            &rental.deposit_amount,
+        );
+
+        rental.deposit_status = DepositStatus::Returned;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::DEPOSIT_RETURNED),),
+            (rental_id, rental.deposit_amount, env.ledger().timestamp()),
+        );
+    }
+
+    /// Claim a rental's held deposit for late or non-return (owner only).
+    /// Only possible once the NFT is overdue or was handed back after the
+    /// return grace window.
+    pub fn claim_deposit(env: Env, owner: Address, rental_id: u64) {
+        let mut rental = Self::load_held_deposit(&env, &owner, rental_id);
+
+        if env.ledger().timestamp() < rental.end_time {
+            panic!("rental period not over");
+        }
+        if !Self::is_returned_late(&env, &rental) {
+            if rental.returned_at != 0 {
+                panic!("nft returned within grace");
+            }
+            panic!("return grace period not over");
+        }
+
+        let payment_client = token::Client::new(&env, &rental.payment_token);
+        payment_client.transfer(
+            &env.current_contract_address(),
+            &rental.owner,
+            &rental.deposit_amount,
+        );
+
+        rental.deposit_status = DepositStatus::Claimed;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::DEPOSIT_CLAIMED),),
+            (rental_id, rental.deposit_amount, env.ledger().timestamp()),
+        );
+    }
+
+    /// Record that the renter handed the NFT back (owner only).
+    pub fn mark_returned(env: Env, owner: Address, rental_id: u64) {
+        owner.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.owner != owner {
+            panic!("not the owner");
+        }
+        if rental.returned_at != 0 {
+            panic!("nft already returned");
+        }
+
+        let now = env.ledger().timestamp();
+        rental.returned_at = now;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::NFT_RETURNED),),
+            (rental_id, now),
+        );
+    }
+
+    /// Whether the NFT is still out past the rental's return grace window.
+    pub fn is_overdue(env: Env, rental_id: u64) -> bool {
+        let rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+        rental.returned_at == 0 && Self::is_returned_late(&env, &rental)
+    }
+
+    // ----------------------------------------------------------
+    // Disputes
+    // ----------------------------------------------------------
+
+    /// Flag a rental as disputed (renter or owner). Termination and deposit
+    /// release are blocked until the admin resolves it.
+    pub fn open_dispute(env: Env, caller: Address, rental_id: u64, reason: Symbol) {
+        caller.require_auth();
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if caller != rental.renter && caller != rental.owner {
+            panic!("not a party to this rental");
+        }
+        if rental.disputed {
+            panic!("rental is disputed");
+        }
+        if matches!(
+            rental.deposit_status,
+            DepositStatus::Returned | DepositStatus::Claimed | DepositStatus::Resolved
+        ) {
+            panic!("deposit already settled");
+        }
+
+        rental.disputed = true;
+        rental.dispute_reason = Some(reason.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::DISPUTE_OPENED),),
+            (rental_id, caller, reason, env.ledger().timestamp()),
+        );
+    }
+
+    /// Close a dispute (admin only), sending `refund_to_renter_bps` of any
+    /// held deposit to the renter and the remainder to the owner.
+    pub fn resolve_dispute(env: Env, admin: Address, rental_id: u64, refund_to_renter_bps: u32) {
+        Self::require_admin(&env, &admin);
+
+        if refund_to_renter_bps as i128 > BPS_DENOMINATOR {
+            panic!("refund bps exceed 10000");
+        }
+
+        let mut rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if !rental.disputed {
+            panic!("rental not disputed");
+        }
+
+        let (renter_share, owner_share) = if rental.deposit_status == DepositStatus::Held {
+            let renter_share =
+                rental.deposit_amount * refund_to_renter_bps as i128 / BPS_DENOMINATOR;
+            (renter_share, rental.deposit_amount - renter_share)
+        } else {
+            (0, 0)
+        };
+
+        let payment_client = token::Client::new(&env, &rental.payment_token);
+        let contract_address = env.current_contract_address();
+        if renter_share > 0 {
+            payment_client.transfer(&contract_address, &rental.renter, &renter_share);
+        }
+        if owner_share > 0 {
+            payment_client.transfer(&contract_address, &rental.owner, &owner_share);
+        }
+
+        if rental.deposit_status == DepositStatus::Held {
+            rental.deposit_status = DepositStatus::Resolved;
+        }
+        rental.disputed = false;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental_id), &rental);
+
+        env.events().publish(
+            (Symbol::new(&env, events::DISPUTE_RESOLVED),),
+            (rental_id, renter_share, owner_share, env.ledger().timestamp()),
+        );
+    }
+
+    // ----------------------------------------------------------
+    // Renter Reputation
+    // ----------------------------------------------------------
+
+    /// Rate the renter of a closed rental from 1 to 5 (owner only, once per rental).
+    pub fn rate_rental(env: Env, owner: Address, rental_id: u64, score: u32) {
+        owner.require_auth();
+
+        if !(1..=5).contains(&score) {
+            panic!("score must be 1-5");
+        }
+
+        let rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.owner != owner {
+            panic!("not the owner");
+        }
+        if rental.status == RentalStatus::Active {
+            panic!("rental not closed");
+        }
+        if env.storage().persistent().has(&DataKey::RentalRated(rental_id)) {
+            panic!("rental already rated");
+        }
+
+        let key = DataKey::RenterRating(rental.renter.clone());
+        let (sum, count): (u64, u32) = env.storage().persistent().get(&key).unwrap_or((0, 0));
+        env.storage()
+            .persistent()
+            .set(&key, &(sum + score as u64, count + 1));
+        env.storage()
+            .persistent()
+            .set(&DataKey::RentalRated(rental_id), &true);
+
+        env.events().publish(
+            (Symbol::new(&env, events::RENTAL_RATED),),
+            (rental_id, rental.renter, score),
+        );
+    }
+
+    /// Get a renter's aggregate rating as (sum of scores, number of ratings).
+    pub fn get_renter_rating(env: Env, renter: Address) -> (u64, u32) {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RenterRating(renter))
+            .unwrap_or((0, 0))
+    }
+
+    // ----------------------------------------------------------
+    // Access Control
+    // ----------------------------------------------------------
+
+    /// Check if a renter currently has valid access to an NFT puzzle.
+    /// Returns true if access is granted, false otherwise.
+    pub fn has_access(env: Env, renter: Address, nft_contract: Address, nft_token_id: u64) -> bool {
+        let rental_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RenterRentals(renter.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let now = env.ledger().timestamp();
+
+        for rental_id in rental_ids.iter() {
+            let rental: RentalAgreement = match env
+                .storage()
+                .persistent()
+                .get(&DataKey::Rental(rental_id))
+            {
+                Some(r) => r,
+                None => continue,
+            };
+
+            if rental.status == RentalStatus::Active
+                && Self::rental_covers(&rental, &nft_contract, nft_token_id)
+                && now >= rental.start_time
+                && now < rental.end_time
+                && now < Self::paid_until(&rental)
+            {
+                env.events().publish(
+                    (Symbol::new(&env, events::ACCESS_CHECKED),),
+                    (renter, true),
+                );
+                return true;
+            }
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, events::ACCESS_CHECKED),),
+            (renter, false),
+        );
+        false
+    }
+
+    // ----------------------------------------------------------
+    // Marketplace Discovery
+    // ----------------------------------------------------------
+
+    /// Get a page of active listings for marketplace discovery.
+    /// `offset` and `limit` allow pagination; `limit` is capped at
+    /// `MAX_PAGE_SIZE` and an offset past the end yields an empty page.
+    pub fn get_marketplace_page(env: Env, offset: u64, limit: u32) -> MarketplacePage {
+        let active_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveListings)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        Self::build_page(&env, &active_ids, offset, limit)
+    }
+
+    /// Alias of `get_marketplace_page`, kept for existing integrations.
+    pub fn get_active_listings(env: Env, offset: u64, limit: u32) -> MarketplacePage {
+        Self::get_marketplace_page(env, offset, limit)
+    }
+
+    /// Get a page of active listings for a single NFT collection.
+    /// `total` is the number of active listings in that collection.
+    pub fn get_listings_by_nft_contract(
+        env: Env,
+        nft_contract: Address,
+        offset: u64,
+        limit: u32,
+    ) -> MarketplacePage {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ListingsByNftContract(nft_contract))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        Self::build_page(&env, &ids, offset, limit)
+    }
+
+    /// Get a page of active listings carrying `tag`.
+    /// `total` is the number of active listings with that tag.
+    pub fn get_listings_by_tag(env: Env, tag: Symbol, offset: u64, limit: u32) -> MarketplacePage {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ListingsByTag(tag))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        Self::build_page(&env, &ids, offset, limit)
+    }
+
+    /// Search active listings accepting `payment_token` whose price per period
+    /// in that token lies within `[min_price, max_price]`. This is a linear
+    /// scan of the marketplace index starting at `start`: it inspects at most
+    /// `MAX_SCAN_WINDOW` entries and stops early once `limit` (capped at
+    /// `MAX_PAGE_SIZE`) matches are found. Pass the returned `next_start` to
+    /// continue; pages may come back short or empty before the index is done.
+    pub fn get_listings_by_price(
+        env: Env,
+        payment_token: Address,
+        min_price: i128,
+        max_price: i128,
+        start: u32,
+        limit: u32,
+    ) -> PriceSearchPage {
+        let active_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveListings)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let scan_end = active_ids.len().min(start.saturating_add(MAX_SCAN_WINDOW));
+        let mut listings = Vec::new(&env);
+        let mut next = start;
+
+        while next < scan_end && listings.len() < limit {
+            let listing_id = active_ids.get(next).unwrap();
+            next += 1;
+            let listing: RentalListing = match env
+                .storage()
+                .persistent()
+                .get(&DataKey::Listing(listing_id))
+            {
+                Some(l) => l,
+                None => continue,
+            };
+
+            let in_range = match Self::price_in(&listing, &payment_token) {
+                Some(price) => price >= min_price && price <= max_price,
+                None => false,
+            };
+            if listing.status == ListingStatus::Active && in_range {
+                listings.push_back(listing);
+            }
+        }
+
+        PriceSearchPage {
+            listings,
+            next_start: if next < active_ids.len() { Some(next) } else { None },
+        }
+    }
+
+    /// Get all listing ids for a given owner.
+    pub fn get_owner_listings(env: Env, owner: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OwnerListings(owner))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get every rental id (active and historical) opened for an NFT.
+    pub fn get_rentals_for_nft(env: Env, nft_contract: Address, nft_token_id: u64) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RentalsByNft(nft_contract, nft_token_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Whether an NFT currently has an Active, unexpired rental attached.
+    pub fn has_active_rental_for_nft(env: Env, nft_contract: Address, nft_token_id: u64) -> bool {
+        let rental_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RentalsByNft(nft_contract, nft_token_id))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let now = env.ledger().timestamp();
+        for rental_id in rental_ids.iter() {
+            if let Some(rental) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, RentalAgreement>(&DataKey::Rental(rental_id))
+            {
+                if rental.status == RentalStatus::Active && now < rental.end_time {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Get all rental ids for a given renter.
+    pub fn get_renter_rentals(env: Env, renter: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RenterRentals(renter))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // ----------------------------------------------------------
+    // Query Helpers
+    // ----------------------------------------------------------
+
+    /// Get an owner's lifetime rental revenue: the escrow released to them
+    /// across all expired and terminated rentals (refunds excluded).
+    pub fn get_owner_revenue(env: Env, owner: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OwnerRevenue(owner))
+            .unwrap_or(0)
+    }
+
+    /// Get a listing by id.
+    pub fn get_listing(env: Env, listing_id: u64) -> RentalListing {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"))
     }
 
     /// Get a rental by id.
@@ -669,17 +2710,134 @@ impl PuzzleRentalContract {
             .unwrap_or_else(|| panic!("rental not found"))
     }
 
-    /// Get rental history record.
-    pub fn get_rental_history(env: Env, rental_id: u64) -> RentalRecord {
+    /// Get marketplace-wide totals for dashboards. Totals are maintained
+    /// incrementally; the active count is the size of the marketplace index.
+    pub fn get_marketplace_stats(env: Env) -> MarketplaceStats {
+        let mut stats = Self::load_stats(&env);
+        let active: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveListings)
+            .unwrap_or_else(|| Vec::new(&env));
+        stats.active_listings = active.len() as u64;
+        stats
+    }
+
+    /// Get rental history record.
+    pub fn get_rental_history(env: Env, rental_id: u64) -> RentalRecord {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RentalHistory(rental_id))
+            .unwrap_or_else(|| panic!("rental history not found"))
+    }
+
+    /// Get a page of a renter's history records, newest first. `offset` and
+    /// `limit` (capped at `MAX_PAGE_SIZE`) window over all of the renter's
+    /// rental ids and `total` is their count; rentals that are still open
+    /// have no record yet and are skipped, so pages may come back short.
+    pub fn get_renter_rental_history(
+        env: Env,
+        renter: Address,
+        offset: u64,
+        limit: u32,
+    ) -> RentalHistoryPage {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RenterRentals(renter))
+            .unwrap_or_else(|| Vec::new(&env));
+        let total = ids.len() as u64;
+        let mut records = Vec::new(&env);
+
+        if offset >= total {
+            return RentalHistoryPage { records, total };
+        }
+
+        let start = offset as u32;
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(ids.len());
+
+        for i in start..end {
+            let rental_id = ids.get(ids.len() - 1 - i).unwrap();
+            if let Some(record) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, RentalRecord>(&DataKey::RentalHistory(rental_id))
+            {
+                records.push_back(record);
+            }
+        }
+
+        RentalHistoryPage { records, total }
+    }
+
+    // ----------------------------------------------------------
+    // Internal Helpers
+    // ----------------------------------------------------------
+
+    fn load_held_deposit(env: &Env, owner: &Address, rental_id: u64) -> RentalAgreement {
+        owner.require_auth();
+
+        let rental: RentalAgreement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Rental(rental_id))
+            .unwrap_or_else(|| panic!("rental not found"));
+
+        if rental.owner != *owner {
+            panic!("not the owner");
+        }
+        if rental.disputed {
+            panic!("rental is disputed");
+        }
+        match rental.deposit_status {
+            DepositStatus::Held => rental,
+            DepositStatus::None => panic!("no deposit held"),
+            _ => panic!("deposit already settled"),
+        }
+    }
+
+    fn require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic!("not initialized"));
+        if *admin != stored {
+            panic!("not the admin");
+        }
+    }
+
+    fn load_config(env: &Env) -> Config {
         env.storage()
-            .persistent()
-            .get(&DataKey::RentalHistory(rental_id))
-            .unwrap_or_else(|| panic!("rental history not found"))
+            .instance()
+            .get(&DataKey::Config)
+            .unwrap_or_else(|| panic!("not initialized"))
     }
 
-    // ----------------------------------------------------------
-    // Internal Helpers
-    // ----------------------------------------------------------
+    fn count_active_rentals(env: &Env, renter: &Address) -> u32 {
+        let rental_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RenterRentals(renter.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut count = 0u32;
+        for rental_id in rental_ids.iter() {
+            if let Some(rental) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, RentalAgreement>(&DataKey::Rental(rental_id))
+            {
+                if rental.status == RentalStatus::Active {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
 
     fn next_listing_id(env: &Env) -> u64 {
         let id: u64 = env
@@ -693,6 +2851,29 @@ impl PuzzleRentalContract {
         id
     }
 
+    fn load_stats(env: &Env) -> MarketplaceStats {
+        env.storage()
+            .instance()
+            .get(&DataKey::Stats)
+            .unwrap_or(MarketplaceStats {
+                total_listings: 0,
+                active_listings: 0,
+                total_rentals: 0,
+                total_volume: 0,
+                total_fees: 0,
+            })
+    }
+
+    /// Add to the running marketplace totals.
+    fn bump_stats(env: &Env, listings: u64, rentals: u64, volume: i128, fees: i128) {
+        let mut stats = Self::load_stats(env);
+        stats.total_listings += listings;
+        stats.total_rentals += rentals;
+        stats.total_volume += volume;
+        stats.total_fees += fees;
+        env.storage().instance().set(&DataKey::Stats, &stats);
+    }
+
     fn next_rental_id(env: &Env) -> u64 {
         let id: u64 = env
             .storage()
@@ -705,6 +2886,102 @@ impl PuzzleRentalContract {
         id
     }
 
+    /// Load a listing after checking that `owner` authorized the call and owns it.
+    fn load_owned_listing(env: &Env, owner: &Address, listing_id: u64) -> RentalListing {
+        owner.require_auth();
+
+        let listing: RentalListing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+            .unwrap_or_else(|| panic!("listing not found"));
+
+        if listing.owner != *owner {
+            panic!("not the owner");
+        }
+        listing
+    }
+
+    /// Panic unless the listing is still Active or Paused.
+    fn ensure_listing_open(listing: &RentalListing) {
+        match listing.status {
+            ListingStatus::Cancelled => panic!("listing is cancelled"),
+            ListingStatus::Expired => panic!("listing expired"),
+            _ => {}
+        }
+    }
+
+    /// Discount of the highest tier whose threshold `periods` meets.
+    fn discount_bps_for(listing: &RentalListing, periods: u32) -> u32 {
+        let mut discount = 0u32;
+        for (threshold, bps) in listing.discount_tiers.iter() {
+            if periods >= threshold {
+                discount = bps;
+            }
+        }
+        discount
+    }
+
+    /// Timestamp up to which a rental has been paid for.
+    fn paid_until(rental: &RentalAgreement) -> u64 {
+        if rental.periods_paid >= rental.periods {
+            return rental.end_time;
+        }
+        let period_duration = (rental.end_time - rental.start_time) / rental.periods as u64;
+        rental.start_time + period_duration * rental.periods_paid as u64
+    }
+
+    fn is_overdue_payment(env: &Env, rental: &RentalAgreement) -> bool {
+        rental.periods_paid < rental.periods
+            && env.ledger().timestamp()
+                >= Self::paid_until(rental).saturating_add(rental.payment_grace_seconds)
+    }
+
+    /// Whether the NFT came back (or, if still out, is now) past the grace window.
+    fn is_returned_late(env: &Env, rental: &RentalAgreement) -> bool {
+        let returned_at = if rental.returned_at != 0 {
+            rental.returned_at
+        } else {
+            env.ledger().timestamp()
+        };
+        returned_at > rental.end_time.saturating_add(rental.return_grace_seconds)
+    }
+
+    fn active_reservation(env: &Env, listing_id: u64, now: u64) -> Option<Reservation> {
+        let reservation: Reservation = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Reservation(listing_id))?;
+        if now < reservation.expires_at {
+            Some(reservation)
+        } else {
+            None
+        }
+    }
+
+    fn listing_active_rentals(env: &Env, listing_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ListingActiveRentals(listing_id))
+            .unwrap_or(0)
+    }
+
+    fn adjust_listing_active_rentals(env: &Env, listing_id: u64, opened: bool) {
+        let count = Self::listing_active_rentals(env, listing_id);
+        let count = if opened { count + 1 } else { count.saturating_sub(1) };
+        env.storage()
+            .persistent()
+            .set(&DataKey::ListingActiveRentals(listing_id), &count);
+    }
+
+    fn exceeds_availability(listing: &RentalListing, end_time: u64) -> bool {
+        listing.available_until != 0 && end_time > listing.available_until
+    }
+
+    fn is_listing_past_expiry(listing: &RentalListing, now: u64) -> bool {
+        listing.listing_expires_at != 0 && now >= listing.listing_expires_at
+    }
+
     fn auto_expire_if_needed(env: &Env, rental: &mut RentalAgreement) {
         if rental.status == RentalStatus::Active {
             let now = env.ledger().timestamp();
@@ -714,6 +2991,85 @@ impl PuzzleRentalContract {
         }
     }
 
+    /// Mark a due rental Expired, release escrow to the owner and archive it.
+    fn close_expired_rental(env: &Env, rental: &mut RentalAgreement) {
+        rental.status = RentalStatus::Expired;
+        Self::adjust_listing_active_rentals(env, rental.listing_id, false);
+
+        // Release escrowed funds to owner on normal expiry
+        Self::pay_owner(env, rental, rental.total_paid);
+
+        // Archive to history
+        Self::archive_rental(env, rental);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental.rental_id), rental);
+
+        env.events().publish(
+            (Symbol::new(env, events::RENTAL_EXPIRED),),
+            (rental.rental_id, rental.total_paid, env.ledger().timestamp()),
+        );
+
+        log!(env, "Rental expired: id={}", rental.rental_id);
+    }
+
+    /// Release `amount` of a rental's escrow: the platform fee is taken off the
+    /// top, each royalty payee receives its basis-point cut of the rest and the
+    /// owner receives the remainder, including any rounding dust. If the
+    /// listing has co-owners, that remainder is split among them instead and
+    /// the first co-owner also takes the dust of the split.
+    fn pay_owner(env: &Env, rental: &RentalAgreement, amount: i128) {
+        let payment_client = token::Client::new(env, &rental.payment_token);
+        let contract_address = env.current_contract_address();
+
+        let fee = amount * rental.fee_bps as i128 / BPS_DENOMINATOR;
+        if fee > 0 {
+            let config = Self::load_config(env);
+            payment_client.transfer(&contract_address, &config.fee_recipient, &fee);
+            Self::bump_stats(env, 0, 0, 0, fee);
+        }
+
+        let net = amount - fee;
+        let mut remainder = net;
+        for (payee, bps) in rental.royalty_payees.iter() {
+            let cut = net * bps as i128 / BPS_DENOMINATOR;
+            if cut > 0 {
+                payment_client.transfer(&contract_address, &payee, &cut);
+                remainder -= cut;
+            }
+        }
+
+        if rental.co_owners.is_empty() {
+            if remainder > 0 {
+                payment_client.transfer(&contract_address, &rental.owner, &remainder);
+            }
+            Self::credit_owner_revenue(env, &rental.owner, remainder);
+            return;
+        }
+
+        let mut dust = remainder;
+        for (_, bps) in rental.co_owners.iter() {
+            dust -= remainder * bps as i128 / BPS_DENOMINATOR;
+        }
+        for (i, (co_owner, bps)) in rental.co_owners.iter().enumerate() {
+            let mut share = remainder * bps as i128 / BPS_DENOMINATOR;
+            if i == 0 {
+                share += dust;
+            }
+            if share > 0 {
+                payment_client.transfer(&contract_address, &co_owner, &share);
+            }
+            Self::credit_owner_revenue(env, &co_owner, share);
+        }
+    }
+
+    fn credit_owner_revenue(env: &Env, owner: &Address, amount: i128) {
+        let key = DataKey::OwnerRevenue(owner.clone());
+        let revenue: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(revenue + amount));
+    }
+
     fn archive_rental(env: &Env, rental: &RentalAgreement) {
         let record = RentalRecord {
             rental_id: rental.rental_id,
@@ -732,22 +3088,378 @@ impl PuzzleRentalContract {
             .set(&DataKey::RentalHistory(rental.rental_id), &record);
     }
 
-    fn remove_from_active_listings(env: &Env, listing_id: u64) {
-        let active: Vec<u64> = env
+    /// Ask the NFT contract who currently holds `nft_token_id`.
+    fn nft_holder(env: &Env, config: &Config, nft_contract: &Address, nft_token_id: u64) -> Address {
+        env.invoke_contract(
+            nft_contract,
+            &config.custody_fn,
+            Vec::from_array(env, [nft_token_id.into_val(env)]),
+        )
+    }
+
+    fn verify_custody(
+        env: &Env,
+        config: &Config,
+        owner: &Address,
+        nft_contract: &Address,
+        nft_token_id: u64,
+    ) {
+        if Self::nft_holder(env, config, nft_contract, nft_token_id) != *owner {
+            panic!("owner does not hold nft");
+        }
+    }
+
+    /// Price per period of a listing in `payment_token`, if it is accepted.
+    fn price_in(listing: &RentalListing, payment_token: &Address) -> Option<i128> {
+        if listing.payment_token == *payment_token {
+            return Some(listing.price_per_period);
+        }
+        listing
+            .accepted_tokens
+            .iter()
+            .find(|(token, _)| token == payment_token)
+            .map(|(_, price)| price)
+    }
+
+    /// Whether a rental (including any bundled extras) covers the given NFT.
+    fn rental_covers(rental: &RentalAgreement, nft_contract: &Address, nft_token_id: u64) -> bool {
+        (rental.nft_contract == *nft_contract && rental.nft_token_id == nft_token_id)
+            || rental
+                .extra_tokens
+                .iter()
+                .any(|(c, id)| c == *nft_contract && id == nft_token_id)
+    }
+
+    fn add_to_nft_index(env: &Env, nft_contract: &Address, nft_token_id: u64, rental_id: u64) {
+        let nft_key = DataKey::RentalsByNft(nft_contract.clone(), nft_token_id);
+        let mut nft_rentals: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&nft_key)
+            .unwrap_or_else(|| Vec::new(env));
+        nft_rentals.push_back(rental_id);
+        env.storage().persistent().set(&nft_key, &nft_rentals);
+    }
+
+    fn validate_tags(tags: &Vec<Symbol>) {
+        if tags.len() > MAX_TAGS {
+            panic!("too many tags");
+        }
+        for (i, tag) in tags.iter().enumerate() {
+            if tags.iter().take(i).any(|t| t == tag) {
+                panic!("duplicate tag");
+            }
+        }
+    }
+
+    fn validate_listing_input(input: &RentalListingInput) {
+        if input.price_per_period <= 0 {
+            panic!("price must be positive");
+        }
+        if input.period_duration == 0 {
+            panic!("period duration must be > 0");
+        }
+        if input.max_periods == 0 {
+            panic!("max periods must be > 0");
+        }
+        if input.early_termination_refund_pct > 100 {
+            panic!("refund pct must be 0-100");
+        }
+        Self::validate_tags(&input.tags);
+    }
+
+    /// Validate and store listings, appending them to the owner and
+    /// marketplace indexes with a single write each.
+    fn insert_listings(env: &Env, owner: &Address, inputs: &Vec<RentalListingInput>) -> Vec<u64> {
+        let config = Self::load_config(env);
+        for input in inputs.iter() {
+            Self::validate_listing_input(&input);
+            Self::verify_custody(env, &config, owner, &input.nft_contract, input.nft_token_id);
+        }
+
+        Self::bump_stats(env, inputs.len() as u64, 0, 0, 0);
+
+        // Lock one bond per listing in a single transfer.
+        let bond = match (&config.bond_token, config.listing_bond) {
+            (Some(bond_token), amount) if amount > 0 => {
+                token::Client::new(env, bond_token).transfer(
+                    owner,
+                    &env.current_contract_address(),
+                    &(amount * inputs.len() as i128),
+                );
+                Some((bond_token.clone(), amount))
+            }
+            _ => None,
+        };
+
+        let now = env.ledger().timestamp();
+        let mut owner_listings: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerListings(owner.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+        let mut active: Vec<u64> = env
             .storage()
             .instance()
             .get(&DataKey::ActiveListings)
             .unwrap_or_else(|| Vec::new(env));
+        let mut ids = Vec::new(env);
+
+        for input in inputs.iter() {
+            let listing_id = Self::next_listing_id(env);
+
+            let listing = RentalListing {
+                listing_id,
+                nft_contract: input.nft_contract,
+                nft_token_id: input.nft_token_id,
+                owner: owner.clone(),
+                price_per_period: input.price_per_period,
+                period_duration: input.period_duration,
+                max_periods: input.max_periods,
+                payment_token: input.payment_token,
+                status: ListingStatus::Active,
+                created_at: now,
+                allow_extensions: input.allow_extensions,
+                early_termination_refund_pct: input.early_termination_refund_pct,
+                deposit_amount: 0,
+                royalty_payees: Vec::new(env),
+                min_periods: 1,
+                listing_expires_at: 0,
+                restricted: false,
+                subleasable: true,
+                discount_tiers: Vec::new(env),
+                billing_mode: BillingMode::Prepaid,
+                payment_grace_seconds: 0,
+                max_concurrent_rentals: 1,
+                return_grace_seconds: 0,
+                extra_tokens: Vec::new(env),
+                accepted_tokens: Vec::new(env),
+                tags: input.tags,
+                metadata_uri: input.metadata_uri,
+                co_owners: Vec::new(env),
+                available_from: 0,
+                available_until: 0,
+                dynamic_pricing: false,
+            };
 
-        let mut new_active = Vec::new(env);
-        for id in active.iter() {
-            if id != listing_id {
-                new_active.push_back(id);
+            env.storage()
+                .persistent()
+                .set(&DataKey::Listing(listing_id), &listing);
+            if let Some(bond) = &bond {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::ListingBond(listing_id), bond);
             }
+
+            owner_listings.push_back(listing_id);
+            active.push_back(listing_id);
+            Self::add_to_collection_index(env, &listing);
+            Self::add_to_tag_indexes(env, &listing);
+
+            env.events().publish(
+                (Symbol::new(env, events::LISTING_CREATED),),
+                (listing_id, owner.clone(), listing.price_per_period, now),
+            );
+
+            log!(env, "Listing created: {}", listing_id);
+            ids.push_back(listing_id);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::OwnerListings(owner.clone()), &owner_listings);
+        env.storage()
+            .instance()
+            .set(&DataKey::ActiveListings, &active);
+
+        ids
+    }
+
+    /// Terminate an in-flight rental of a removed listing.
+    fn refund_removed_rental(env: &Env, rental: &mut RentalAgreement, now: u64) {
+        let refund = Self::refund_unused_time(env, rental, now);
+
+        rental.status = RentalStatus::Terminated;
+        rental.termination_reason = TerminationReason::ListingRemoved;
+        Self::adjust_listing_active_rentals(env, rental.listing_id, false);
+        Self::archive_rental(env, rental);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rental(rental.rental_id), rental);
+
+        env.events().publish(
+            (Symbol::new(env, events::RENTAL_TERMINATED),),
+            (rental.rental_id, refund, now),
+        );
+    }
+
+    /// Settle a rental cut short at `now`. The renter gets back the unused
+    /// share of the paid time (in full for rentals not yet started) and any
+    /// held deposit; the owner keeps only what was already consumed. Returns
+    /// the total refunded to the renter.
+    fn refund_unused_time(env: &Env, rental: &mut RentalAgreement, now: u64) -> i128 {
+        let paid_until = Self::paid_until(rental);
+        let total_duration = paid_until - rental.start_time;
+        let remaining_time = paid_until.saturating_sub(now.max(rental.start_time));
+        let mut refund = if total_duration > 0 {
+            rental.total_paid * remaining_time as i128 / total_duration as i128
+        } else {
+            0
+        };
+
+        let owner_share = rental.total_paid - refund;
+        if owner_share > 0 {
+            Self::pay_owner(env, rental, owner_share);
+        }
+        if rental.deposit_status == DepositStatus::Held {
+            refund += rental.deposit_amount;
+            rental.deposit_status = DepositStatus::Returned;
+        }
+        if refund > 0 {
+            token::Client::new(env, &rental.payment_token).transfer(
+                &env.current_contract_address(),
+                &rental.renter,
+                &refund,
+            );
+        }
+
+        refund
+    }
+
+    /// Pay out a listing's bond to `to` (if one is held) and forget it.
+    fn release_listing_bond(env: &Env, listing_id: u64, to: &Address) {
+        let key = DataKey::ListingBond(listing_id);
+        if let Some((bond_token, amount)) =
+            env.storage().persistent().get::<DataKey, (Address, i128)>(&key)
+        {
+            token::Client::new(env, &bond_token).transfer(
+                &env.current_contract_address(),
+                to,
+                &amount,
+            );
+            env.storage().persistent().remove(&key);
         }
+    }
+
+    /// Add a listing to the marketplace index and its collection and tag indexes.
+    fn add_to_active_listings(env: &Env, listing: &RentalListing) {
+        let mut active: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveListings)
+            .unwrap_or_else(|| Vec::new(env));
+        active.push_back(listing.listing_id);
         env.storage()
             .instance()
-            .set(&DataKey::ActiveListings, &new_active);
+            .set(&DataKey::ActiveListings, &active);
+
+        Self::add_to_collection_index(env, listing);
+        Self::add_to_tag_indexes(env, listing);
+    }
+
+    fn add_to_collection_index(env: &Env, listing: &RentalListing) {
+        let collection_key = DataKey::ListingsByNftContract(listing.nft_contract.clone());
+        let mut collection: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&collection_key)
+            .unwrap_or_else(|| Vec::new(env));
+        collection.push_back(listing.listing_id);
+        env.storage().persistent().set(&collection_key, &collection);
+    }
+
+    fn add_to_tag_indexes(env: &Env, listing: &RentalListing) {
+        for tag in listing.tags.iter() {
+            let tag_key = DataKey::ListingsByTag(tag);
+            let mut tagged: Vec<u64> = env
+                .storage()
+                .persistent()
+                .get(&tag_key)
+                .unwrap_or_else(|| Vec::new(env));
+            tagged.push_back(listing.listing_id);
+            env.storage().persistent().set(&tag_key, &tagged);
+        }
+    }
+
+    fn remove_from_tag_indexes(env: &Env, listing: &RentalListing) {
+        for tag in listing.tags.iter() {
+            let tag_key = DataKey::ListingsByTag(tag);
+            let tagged: Vec<u64> = env
+                .storage()
+                .persistent()
+                .get(&tag_key)
+                .unwrap_or_else(|| Vec::new(env));
+            env.storage().persistent().set(
+                &tag_key,
+                &Self::without_id(env, &tagged, listing.listing_id),
+            );
+        }
+    }
+
+    /// Remove a listing from the marketplace, collection and tag indexes.
+    fn remove_from_active_listings(env: &Env, listing: &RentalListing) {
+        let active: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ActiveListings)
+            .unwrap_or_else(|| Vec::new(env));
+        env.storage().instance().set(
+            &DataKey::ActiveListings,
+            &Self::without_id(env, &active, listing.listing_id),
+        );
+
+        let collection_key = DataKey::ListingsByNftContract(listing.nft_contract.clone());
+        let collection: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&collection_key)
+            .unwrap_or_else(|| Vec::new(env));
+        env.storage().persistent().set(
+            &collection_key,
+            &Self::without_id(env, &collection, listing.listing_id),
+        );
+
+        Self::remove_from_tag_indexes(env, listing);
+    }
+
+    fn without_id(env: &Env, ids: &Vec<u64>, target: u64) -> Vec<u64> {
+        let mut filtered = Vec::new(env);
+        for id in ids.iter() {
+            if id != target {
+                filtered.push_back(id);
+            }
+        }
+        filtered
+    }
+
+    /// Resolve a window of listing ids into a page of Active listings.
+    fn build_page(env: &Env, ids: &Vec<u64>, offset: u64, limit: u32) -> MarketplacePage {
+        let total = ids.len() as u64;
+        let mut listings = Vec::new(env);
+
+        if offset >= total {
+            return MarketplacePage { listings, total };
+        }
+
+        let start = offset as u32;
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(ids.len());
+
+        for i in start..end {
+            let listing_id = ids.get(i).unwrap();
+            if let Some(listing) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, RentalListing>(&DataKey::Listing(listing_id))
+            {
+                if listing.status == ListingStatus::Active {
+                    listings.push_back(listing);
+                }
+            }
+        }
+
+        MarketplacePage { listings, total }
     }
 }
 
@@ -1,14 +1,59 @@
 #![cfg(test)]
 
 use crate::{
-    ListingStatus, PuzzleRentalContract, PuzzleRentalContractClient, RentalStatus,
+    BillingMode, DepositStatus, ListingStatus, PuzzleRentalContract, PuzzleRentalContractClient,
+    RentalListingInput, RentalStatus, TerminationReason, MAX_SCAN_WINDOW,
 };
 use soroban_sdk::{
-    testutils::{Address as _, Ledger, LedgerInfo},
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Events, Ledger, LedgerInfo},
     token::{Client as TokenClient, StellarAssetClient},
-    Address, Env,
+    vec, Address, Env, IntoVal, String, Symbol, TryFromVal, Val,
 };
 
+// ============================================================
+// Mock NFT Contract
+// ============================================================
+
+#[contracttype]
+enum MockNftKey {
+    DefaultHolder,
+    Holder(u64),
+}
+
+/// Minimal NFT exposing `owner_of` (and a differently named `holder_of`).
+/// Every token belongs to the default holder unless reassigned.
+#[contract]
+pub struct MockNft;
+
+#[contractimpl]
+impl MockNft {
+    pub fn init(env: Env, default_holder: Address) {
+        env.storage().instance().set(&MockNftKey::DefaultHolder, &default_holder);
+    }
+
+    pub fn transfer(env: Env, token_id: u64, to: Address) {
+        env.storage().instance().set(&MockNftKey::Holder(token_id), &to);
+    }
+
+    pub fn owner_of(env: Env, token_id: u64) -> Address {
+        env.storage()
+            .instance()
+            .get(&MockNftKey::Holder(token_id))
+            .unwrap_or_else(|| env.storage().instance().get(&MockNftKey::DefaultHolder).unwrap())
+    }
+
+    pub fn holder_of(env: Env, token_id: u64) -> Address {
+        Self::owner_of(env, token_id)
+    }
+}
+
+fn register_nft(env: &Env, default_holder: &Address) -> Address {
+    let nft_contract = env.register_contract(None, MockNft);
+    MockNftClient::new(env, &nft_contract).init(default_holder);
+    nft_contract
+}
+
 // ============================================================
 // Test Helpers
 // ============================================================
@@ -31,7 +76,7 @@ fn setup() -> TestSetup {
     let admin = Address::generate(&env);
     let owner = Address::generate(&env);
     let renter = Address::generate(&env);
-    let nft_contract = Address::generate(&env);
+    let nft_contract = register_nft(&env, &owner);
 
     // Create a Stellar asset token for payments
     let token_admin = Address::generate(&env);
@@ -70,6 +115,20 @@ fn set_timestamp(env: &Env, ts: u64) {
     });
 }
 
+/// Return the data of the most recent event published by the rental contract
+/// under `topic`, decoded as `T`.
+fn last_event<T: TryFromVal<Env, Val>>(t: &TestSetup, topic: &str) -> T {
+    let topics: soroban_sdk::Vec<Val> = (Symbol::new(&t.env, topic),).into_val(&t.env);
+    let mut found = None;
+    for (contract, event_topics, data) in t.env.events().all().iter() {
+        if contract == t.contract_id && event_topics == topics {
+            found = Some(data);
+        }
+    }
+    let data = found.unwrap_or_else(|| panic!("event {} not emitted", topic));
+    T::try_from_val(&t.env, &data).unwrap_or_else(|_| panic!("unexpected payload for {}", topic))
+}
+
 // ============================================================
 // Unit Tests: Initialization
 // ============================================================
@@ -198,6 +257,108 @@ fn test_create_listing_refund_pct_over_100_panics() {
     );
 }
 
+fn listing_input(t: &TestSetup, token_id: u64, price: i128) -> RentalListingInput {
+    RentalListingInput {
+        nft_contract: t.nft_contract.clone(),
+        nft_token_id: token_id,
+        payment_token: t.token_id.clone(),
+        price_per_period: price,
+        period_duration: 3600,
+        max_periods: 10,
+        allow_extensions: true,
+        early_termination_refund_pct: 0,
+        tags: vec![&t.env],
+        metadata_uri: String::from_str(&t.env, ""),
+    }
+}
+
+#[test]
+fn test_create_listings_batch_assigns_sequential_ids() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let first = client.create_listing(
+        &t.owner, &t.nft_contract, &100u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let ids = client.create_listings_batch(
+        &t.owner,
+        &vec![
+            &t.env,
+            listing_input(&t, 1, 100),
+            listing_input(&t, 2, 200),
+            listing_input(&t, 3, 300),
+        ],
+    );
+
+    assert_eq!(ids, vec![&t.env, first + 1, first + 2, first + 3]);
+    assert_eq!(client.get_listing(&ids.get(1).unwrap()).price_per_period, 200);
+    assert_eq!(client.get_listing(&ids.get(2).unwrap()).nft_token_id, 3);
+    assert_eq!(client.get_owner_listings(&t.owner).len(), 4);
+    assert_eq!(client.get_marketplace_page(&0u64, &10u32).total, 4);
+    assert_eq!(
+        client.get_listings_by_nft_contract(&t.nft_contract, &0u64, &10u32).total,
+        4
+    );
+}
+
+#[test]
+fn test_create_listings_batch_rejects_invalid_entry_atomically() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let result = client.try_create_listings_batch(
+        &t.owner,
+        &vec![&t.env, listing_input(&t, 1, 100), listing_input(&t, 2, 0)],
+    );
+    assert!(result.is_err());
+    assert_eq!(client.get_owner_listings(&t.owner).len(), 0);
+    assert_eq!(client.get_marketplace_page(&0u64, &10u32).total, 0);
+}
+
+#[test]
+#[should_panic(expected = "batch too large")]
+fn test_create_listings_batch_over_cap_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let mut inputs = vec![&t.env];
+    for i in 0..26u64 {
+        inputs.push_back(listing_input(&t, i, 100));
+    }
+    client.create_listings_batch(&t.owner, &inputs);
+}
+
+#[test]
+#[should_panic(expected = "owner does not hold nft")]
+fn test_create_listing_without_custody_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    // Token 5 is held by someone else
+    MockNftClient::new(&t.env, &t.nft_contract).transfer(&5u64, &t.renter);
+    client.create_listing(
+        &t.owner, &t.nft_contract, &5u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+}
+
+#[test]
+fn test_custody_fn_is_configurable() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    assert_eq!(client.get_config().custody_fn, Symbol::new(&t.env, "owner_of"));
+    client.set_custody_fn(&t.admin, &Symbol::new(&t.env, "holder_of"));
+    assert_eq!(client.get_config().custody_fn, Symbol::new(&t.env, "holder_of"));
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    assert_eq!(client.get_listing(&listing_id).nft_token_id, 1);
+}
+
 // ============================================================
 // Unit Tests: Listing Cancellation
 // ============================================================
@@ -257,727 +418,3768 @@ fn test_cancel_nonexistent_listing_panics() {
 }
 
 // ============================================================
-// Unit Tests: Renting
+// Unit Tests: Listing Pause & Resume
 // ============================================================
 
 #[test]
-fn test_rent_basic_creates_agreement() {
+fn test_pause_listing_hides_from_marketplace() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 1000);
-
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &50u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
 
-    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
-    let rental = client.get_rental(&rental_id);
+    client.pause_listing(&t.owner, &listing_id);
 
-    assert_eq!(rental.rental_id, rental_id);
-    assert_eq!(rental.listing_id, listing_id);
-    assert_eq!(rental.renter, t.renter);
-    assert_eq!(rental.owner, t.owner);
-    assert_eq!(rental.periods, 2);
-    assert_eq!(rental.total_paid, 200);
-    assert_eq!(rental.start_time, 1000);
-    assert_eq!(rental.end_time, 1000 + 3600 * 2);
-    assert!(matches!(rental.status, RentalStatus::Active));
+    let listing = client.get_listing(&listing_id);
+    assert!(matches!(listing.status, ListingStatus::Paused));
+    let page = client.get_marketplace_page(&0u64, &10u32);
+    assert_eq!(page.total, 0);
+    assert_eq!(page.listings.len(), 0);
 }
 
 #[test]
-fn test_rent_escrows_tokens_in_contract() {
+fn test_resume_listing_restores_marketplace_entry() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let token_client = TokenClient::new(&t.env, &t.token_id);
-
-    let before_renter = token_client.balance(&t.renter);
 
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &250i128, &3600u64, &10u32, &true, &0u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
 
-    client.rent(&t.renter, &listing_id, &3u32);
+    client.pause_listing(&t.owner, &listing_id);
+    client.resume_listing(&t.owner, &listing_id);
 
-    // Renter paid; owner has not received anything yet (funds escrowed in contract)
-    assert_eq!(token_client.balance(&t.renter), before_renter - 750);
-    assert_eq!(token_client.balance(&t.owner), 0);
-    assert_eq!(token_client.balance(&t.contract_id), 750);
+    let listing = client.get_listing(&listing_id);
+    assert!(matches!(listing.status, ListingStatus::Active));
+    let page = client.get_marketplace_page(&0u64, &10u32);
+    assert_eq!(page.total, 1);
+    assert_eq!(page.listings.get(0).unwrap().listing_id, listing_id);
+
+    // Renting works again once resumed
+    client.rent(&t.renter, &listing_id, &1u32);
 }
 
 #[test]
 #[should_panic(expected = "listing not active")]
-fn test_rent_cancelled_listing_panics() {
+fn test_rent_paused_listing_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &50u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.cancel_listing(&t.owner, &listing_id);
+    client.pause_listing(&t.owner, &listing_id);
     client.rent(&t.renter, &listing_id, &1u32);
 }
 
 #[test]
-#[should_panic(expected = "exceeds max periods")]
-fn test_rent_exceeds_max_periods_panics() {
+#[should_panic(expected = "not the owner")]
+fn test_pause_listing_wrong_caller_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &3u32, &true, &50u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.rent(&t.renter, &listing_id, &5u32);
+    client.pause_listing(&t.renter, &listing_id);
 }
 
 #[test]
-#[should_panic(expected = "periods must be > 0")]
-fn test_rent_zero_periods_panics() {
+#[should_panic(expected = "listing is cancelled")]
+fn test_resume_cancelled_listing_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &50u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.rent(&t.renter, &listing_id, &0u32);
+    client.cancel_listing(&t.owner, &listing_id);
+    client.resume_listing(&t.owner, &listing_id);
 }
 
 #[test]
-#[should_panic(expected = "owner cannot rent own listing")]
-fn test_owner_cannot_rent_own_listing() {
+#[should_panic(expected = "listing not paused")]
+fn test_resume_active_listing_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
-    token_sac.mint(&t.owner, &10_000);
 
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &50u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.rent(&t.owner, &listing_id, &1u32);
-}
-
-#[test]
-#[should_panic(expected = "listing not found")]
-fn test_rent_nonexistent_listing_panics() {
-    let t = setup();
-    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    client.rent(&t.renter, &999u64, &1u32);
+    client.resume_listing(&t.owner, &listing_id);
 }
 
 // ============================================================
-// Unit Tests: Access Control
+// Unit Tests: Listing Price Updates
 // ============================================================
 
 #[test]
-fn test_has_access_during_active_rental() {
+fn test_update_listing_price_applies_to_new_rentals_only() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 1000);
-
+    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
-        &t.owner, &t.nft_contract, &42u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &50u32,
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.rent(&t.renter, &listing_id, &1u32);
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
+    let in_flight = client.rent(&t.renter, &listing_id, &2u32);
 
-    set_timestamp(&t.env, 2000);
-    assert!(client.has_access(&t.renter, &t.nft_contract, &42u64));
-}
+    client.update_listing_price(&t.owner, &listing_id, &300i128);
+    assert_eq!(client.get_listing(&listing_id).price_per_period, 300);
 
-#[test]
-fn test_no_access_without_rental() {
-    let t = setup();
-    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    assert!(!client.has_access(&t.renter, &t.nft_contract, &42u64));
+    // The in-flight rental keeps its locked-in price
+    let rental = client.get_rental(&in_flight);
+    assert_eq!(rental.price_per_period, 100);
+    assert_eq!(rental.total_paid, 200);
+
+    // New rentals are charged the updated price
+    let fresh = client.rent(&t.renter, &listing_id, &2u32);
+    let rental = client.get_rental(&fresh);
+    assert_eq!(rental.price_per_period, 300);
+    assert_eq!(rental.total_paid, 600);
 }
 
 #[test]
-fn test_no_access_after_expiry() {
+fn test_update_price_on_paused_listing() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 1000);
     let listing_id = client.create_listing(
-        &t.owner, &t.nft_contract, &42u64, &t.token_id,
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.rent(&t.renter, &listing_id, &1u32);
-    // end_time = 1000 + 3600 = 4600
+    client.pause_listing(&t.owner, &listing_id);
+    client.update_listing_price(&t.owner, &listing_id, &150i128);
 
-    set_timestamp(&t.env, 5000);
-    assert!(!client.has_access(&t.renter, &t.nft_contract, &42u64));
+    assert_eq!(client.get_listing(&listing_id).price_per_period, 150);
 }
 
 #[test]
-fn test_no_access_for_wrong_nft_token() {
+#[should_panic(expected = "price must be positive")]
+fn test_update_listing_price_zero_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 1000);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.rent(&t.renter, &listing_id, &1u32);
-
-    // Renter has access to token 1, not token 2
-    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
-    assert!(!client.has_access(&t.renter, &t.nft_contract, &2u64));
+    client.update_listing_price(&t.owner, &listing_id, &0i128);
 }
 
 #[test]
-fn test_no_access_for_different_nft_contract() {
+#[should_panic(expected = "listing is cancelled")]
+fn test_update_price_on_cancelled_listing_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let other_contract = Address::generate(&t.env);
 
-    set_timestamp(&t.env, 1000);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    client.rent(&t.renter, &listing_id, &1u32);
-
-    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
-    assert!(!client.has_access(&t.renter, &other_contract, &1u64));
+    client.cancel_listing(&t.owner, &listing_id);
+    client.update_listing_price(&t.owner, &listing_id, &200i128);
 }
 
 // ============================================================
-// Unit Tests: Rental Extension
+// Unit Tests: Listing Terms & Expiration
 // ============================================================
 
 #[test]
-fn test_extend_rental_updates_end_time_and_cost() {
+fn test_new_listing_has_open_terms() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
-    token_sac.mint(&t.renter, &10_000);
 
-    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &50u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
+    let listing = client.get_listing(&listing_id);
+    assert_eq!(listing.min_periods, 1);
+    assert_eq!(listing.listing_expires_at, 0);
 
-    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
-    // end_time = 0 + 3600*2 = 7200, total_paid = 200
-
-    set_timestamp(&t.env, 2000);
-    client.extend_rental(&t.renter, &rental_id, &3u32);
-
-    let rental = client.get_rental(&rental_id);
-    assert_eq!(rental.periods, 5);
-    assert_eq!(rental.end_time, 3600 * 5); // 0 + 3600*5
-    assert_eq!(rental.total_paid, 500);
+    // Never-expiring listings stay rentable far into the future
+    set_timestamp(&t.env, 1_000_000_000);
+    client.rent(&t.renter, &listing_id, &1u32);
 }
 
 #[test]
-fn test_extend_rental_escrows_additional_tokens() {
+#[should_panic(expected = "below min periods")]
+fn test_rent_below_min_periods_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
-    let token_client = TokenClient::new(&t.env, &t.token_id);
-    token_sac.mint(&t.renter, &10_000);
 
-    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-
-    let renter_after_rent = token_client.balance(&t.renter);
-    let contract_after_rent = token_client.balance(&t.contract_id);
-
-    client.extend_rental(&t.renter, &rental_id, &2u32);
+    client.set_listing_terms(&t.owner, &listing_id, &3u32, &0u64);
 
-    // Additional 200 tokens escrowed; owner still receives nothing until expiry
-    assert_eq!(token_client.balance(&t.renter), renter_after_rent - 200);
-    assert_eq!(token_client.balance(&t.contract_id), contract_after_rent + 200);
-    assert_eq!(token_client.balance(&t.owner), 0);
+    client.rent(&t.renter, &listing_id, &3u32);
+    client.rent(&t.renter, &listing_id, &2u32);
 }
 
 #[test]
-#[should_panic(expected = "extensions not allowed")]
-fn test_extend_rental_not_allowed_panics() {
+#[should_panic(expected = "listing expired")]
+fn test_rent_after_listing_expiration_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
     set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &false, &50u32, // no extensions
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-    client.extend_rental(&t.renter, &rental_id, &1u32);
+    client.set_listing_terms(&t.owner, &listing_id, &1u32, &5000u64);
+
+    set_timestamp(&t.env, 5000);
+    client.rent(&t.renter, &listing_id, &1u32);
 }
 
 #[test]
-#[should_panic(expected = "exceeds max periods")]
-fn test_extend_beyond_max_periods_panics() {
+fn test_expire_listings_sweep_removes_from_marketplace() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
-    token_sac.mint(&t.renter, &10_000);
 
     set_timestamp(&t.env, 0);
-    let listing_id = client.create_listing(
+    let expiring = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &3u32, &true, &50u32, // max = 3
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
-    // Already at 2; extending by 2 would give 4 which exceeds max of 3
-    client.extend_rental(&t.renter, &rental_id, &2u32);
+    let later = client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let open = client.create_listing(
+        &t.owner, &t.nft_contract, &3u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_terms(&t.owner, &expiring, &1u32, &1000u64);
+    client.set_listing_terms(&t.owner, &later, &1u32, &9000u64);
+
+    set_timestamp(&t.env, 2000);
+    let expired = client.expire_listings(&vec![&t.env, expiring, later, open, 999u64]);
+    assert_eq!(expired, 1);
+
+    assert!(matches!(client.get_listing(&expiring).status, ListingStatus::Expired));
+    assert!(matches!(client.get_listing(&later).status, ListingStatus::Active));
+    let page = client.get_marketplace_page(&0u64, &10u32);
+    assert_eq!(page.total, 2);
+    let page = client.get_listings_by_nft_contract(&t.nft_contract, &0u64, &10u32);
+    assert_eq!(page.total, 2);
+
+    // A second sweep is a no-op
+    assert_eq!(client.expire_listings(&vec![&t.env, expiring]), 0);
 }
 
 #[test]
-#[should_panic(expected = "not the renter")]
-fn test_extend_rental_wrong_caller_panics() {
+#[should_panic(expected = "min periods must be 1..=max periods")]
+fn test_set_listing_terms_min_above_max_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &0u32,
+        &100i128, &3600u64, &5u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-    client.extend_rental(&t.owner, &rental_id, &1u32);
+    client.set_listing_terms(&t.owner, &listing_id, &6u32, &0u64);
 }
 
+// ============================================================
+// Unit Tests: Private Listings
+// ============================================================
+
 #[test]
-#[should_panic(expected = "additional periods must be > 0")]
-fn test_extend_rental_zero_periods_panics() {
+fn test_restricted_listing_accepts_whitelisted_renter() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-    client.extend_rental(&t.renter, &rental_id, &0u32);
+    client.set_listing_restricted(&t.owner, &listing_id, &true);
+    client.allow_renter(&t.owner, &listing_id, &t.renter);
+
+    assert!(client.is_renter_allowed(&listing_id, &t.renter));
+    client.rent(&t.renter, &listing_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "renter not whitelisted")]
+fn test_restricted_listing_rejects_unlisted_renter() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_restricted(&t.owner, &listing_id, &true);
+    client.rent(&t.renter, &listing_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "renter not whitelisted")]
+fn test_disallowed_renter_is_rejected() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_restricted(&t.owner, &listing_id, &true);
+    client.allow_renter(&t.owner, &listing_id, &t.renter);
+    client.disallow_renter(&t.owner, &listing_id, &t.renter);
+
+    assert!(!client.is_renter_allowed(&listing_id, &t.renter));
+    client.rent(&t.renter, &listing_id, &1u32);
+}
+
+#[test]
+fn test_reopened_listing_accepts_anyone() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    let outsider = Address::generate(&t.env);
+    token_sac.mint(&outsider, &1000);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
+    client.set_listing_restricted(&t.owner, &listing_id, &true);
+    client.allow_renter(&t.owner, &listing_id, &t.renter);
+    client.rent(&t.renter, &listing_id, &1u32);
+
+    // Owner flips the listing back to open; non-whitelisted renters may rent
+    client.set_listing_restricted(&t.owner, &listing_id, &false);
+    assert!(!client.get_listing(&listing_id).restricted);
+    client.rent(&outsider, &listing_id, &1u32);
+
+    // The whitelist entry survives for when the listing is restricted again
+    assert!(client.is_renter_allowed(&listing_id, &t.renter));
+}
+
+#[test]
+#[should_panic(expected = "not the owner")]
+fn test_allow_renter_wrong_caller_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.allow_renter(&t.renter, &listing_id, &t.renter);
+}
+
+// ============================================================
+// Unit Tests: Reservations
+// ============================================================
+
+#[test]
+fn test_reserving_renter_can_rent_and_consumes_hold() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let expires_at = client.reserve_listing(&t.renter, &listing_id, &600u64);
+    assert_eq!(expires_at, 1600);
+    assert_eq!(client.get_reservation(&listing_id).unwrap().renter, t.renter);
+
+    client.rent(&t.renter, &listing_id, &1u32);
+    assert!(client.get_reservation(&listing_id).is_none());
+}
+
+#[test]
+#[should_panic(expected = "listing reserved")]
+fn test_other_renter_blocked_during_hold() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    let other = Address::generate(&t.env);
+    token_sac.mint(&other, &1000);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.reserve_listing(&t.renter, &listing_id, &600u64);
+    client.rent(&other, &listing_id, &1u32);
+}
+
+#[test]
+fn test_lapsed_or_released_hold_frees_listing() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    let other = Address::generate(&t.env);
+    token_sac.mint(&other, &1000);
+
+    set_timestamp(&t.env, 1000);
+    let l1 = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let l2 = client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.reserve_listing(&t.renter, &l1, &600u64);
+    client.reserve_listing(&t.renter, &l2, &600u64);
+
+    // Released hold
+    client.release_reservation(&t.renter, &l1);
+    client.rent(&other, &l1, &1u32);
+
+    // Lapsed hold
+    set_timestamp(&t.env, 1600);
+    assert!(client.get_reservation(&l2).is_none());
+    client.rent(&other, &l2, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "listing already rented")]
+fn test_cannot_reserve_rented_listing() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let other = Address::generate(&t.env);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.rent(&t.renter, &listing_id, &1u32);
+    client.reserve_listing(&other, &listing_id, &600u64);
+}
+
+#[test]
+#[should_panic(expected = "listing reserved")]
+fn test_reservations_do_not_stack() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let other = Address::generate(&t.env);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.reserve_listing(&t.renter, &listing_id, &600u64);
+    client.reserve_listing(&other, &listing_id, &600u64);
+}
+
+// ============================================================
+// Unit Tests: Renting
+// ============================================================
+
+#[test]
+fn test_rent_basic_creates_agreement() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &50u32,
+    );
+
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    let rental = client.get_rental(&rental_id);
+
+    assert_eq!(rental.rental_id, rental_id);
+    assert_eq!(rental.listing_id, listing_id);
+    assert_eq!(rental.renter, t.renter);
+    assert_eq!(rental.owner, t.owner);
+    assert_eq!(rental.periods, 2);
+    assert_eq!(rental.total_paid, 200);
+    assert_eq!(rental.start_time, 1000);
+    assert_eq!(rental.end_time, 1000 + 3600 * 2);
+    assert!(matches!(rental.status, RentalStatus::Active));
+}
+
+#[test]
+fn test_rent_escrows_tokens_in_contract() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    let before_renter = token_client.balance(&t.renter);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &250i128, &3600u64, &10u32, &true, &0u32,
+    );
+
+    client.rent(&t.renter, &listing_id, &3u32);
+
+    // Renter paid; owner has not received anything yet (funds escrowed in contract)
+    assert_eq!(token_client.balance(&t.renter), before_renter - 750);
+    assert_eq!(token_client.balance(&t.owner), 0);
+    assert_eq!(token_client.balance(&t.contract_id), 750);
+}
+
+#[test]
+fn test_rent_applies_highest_met_discount_tier() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &20u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &3u32);
+    // 5+ periods: 10% off, 10+ periods: 20% off
+    client.set_discount_tiers(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, (5u32, 1000u32), (10u32, 2000u32)],
+    );
+
+    let short = client.rent(&t.renter, &listing_id, &3u32);
+    assert_eq!(client.get_rental(&short).total_paid, 300);
+
+    let long = client.rent(&t.renter, &listing_id, &6u32);
+    assert_eq!(client.get_rental(&long).total_paid, 540);
+
+    let longest = client.rent(&t.renter, &listing_id, &12u32);
+    assert_eq!(client.get_rental(&longest).total_paid, 960);
+
+    assert_eq!(token_client.balance(&t.contract_id), 300 + 540 + 960);
+}
+
+#[test]
+#[should_panic(expected = "discount tiers must be sorted")]
+fn test_unsorted_discount_tiers_panic() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &20u32, &true, &0u32,
+    );
+    client.set_discount_tiers(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, (10u32, 2000u32), (5u32, 1000u32)],
+    );
+}
+
+#[test]
+#[should_panic(expected = "discount bps exceed 10000")]
+fn test_discount_tier_over_10000_bps_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &20u32, &true, &0u32,
+    );
+    client.set_discount_tiers(&t.owner, &listing_id, &vec![&t.env, (5u32, 10_001u32)]);
+}
+
+#[test]
+#[should_panic(expected = "listing not active")]
+fn test_rent_cancelled_listing_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &50u32,
+    );
+    client.cancel_listing(&t.owner, &listing_id);
+    client.rent(&t.renter, &listing_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "exceeds max periods")]
+fn test_rent_exceeds_max_periods_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &3u32, &true, &50u32,
+    );
+    client.rent(&t.renter, &listing_id, &5u32);
+}
+
+#[test]
+#[should_panic(expected = "periods must be > 0")]
+fn test_rent_zero_periods_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &50u32,
+    );
+    client.rent(&t.renter, &listing_id, &0u32);
+}
+
+#[test]
+#[should_panic(expected = "owner cannot rent own listing")]
+fn test_owner_cannot_rent_own_listing() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    token_sac.mint(&t.owner, &10_000);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &50u32,
+    );
+    client.rent(&t.owner, &listing_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "listing not found")]
+fn test_rent_nonexistent_listing_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    client.rent(&t.renter, &999u64, &1u32);
+}
+
+// ============================================================
+// Unit Tests: Renter Active Rental Cap
+// ============================================================
+
+#[test]
+fn test_default_config_has_no_rental_cap() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    assert_eq!(client.get_config().max_active_rentals_per_renter, 0);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &5u32);
+    for _ in 0..5 {
+        client.rent(&t.renter, &listing_id, &1u32);
+    }
+    assert_eq!(client.get_renter_rentals(&t.renter).len(), 5);
+}
+
+#[test]
+#[should_panic(expected = "renter at active rental cap")]
+fn test_rent_beyond_active_cap_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    client.set_max_active_rentals(&t.admin, &2u32);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &3u32);
+    client.rent(&t.renter, &listing_id, &1u32);
+    client.rent(&t.renter, &listing_id, &1u32);
+    client.rent(&t.renter, &listing_id, &1u32);
+}
+
+#[test]
+fn test_closed_rentals_free_up_cap() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    client.set_max_active_rentals(&t.admin, &1u32);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let r1 = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&r1);
+
+    let r2 = client.rent(&t.renter, &listing_id, &1u32);
+    assert!(matches!(client.get_rental(&r2).status, RentalStatus::Active));
+}
+
+#[test]
+#[should_panic(expected = "not the admin")]
+fn test_set_rental_cap_non_admin_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    client.set_max_active_rentals(&t.owner, &1u32);
+}
+
+// ============================================================
+// Unit Tests: Access Control
+// ============================================================
+
+#[test]
+fn test_has_access_during_active_rental() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &42u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &50u32,
+    );
+    client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 2000);
+    assert!(client.has_access(&t.renter, &t.nft_contract, &42u64));
+}
+
+#[test]
+fn test_no_access_without_rental() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    assert!(!client.has_access(&t.renter, &t.nft_contract, &42u64));
+}
+
+#[test]
+fn test_no_access_after_expiry() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &42u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.rent(&t.renter, &listing_id, &1u32);
+    // end_time = 1000 + 3600 = 4600
+
+    set_timestamp(&t.env, 5000);
+    assert!(!client.has_access(&t.renter, &t.nft_contract, &42u64));
+}
+
+#[test]
+fn test_no_access_for_wrong_nft_token() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.rent(&t.renter, &listing_id, &1u32);
+
+    // Renter has access to token 1, not token 2
+    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
+    assert!(!client.has_access(&t.renter, &t.nft_contract, &2u64));
+}
+
+#[test]
+fn test_no_access_for_different_nft_contract() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let other_contract = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.rent(&t.renter, &listing_id, &1u32);
+
+    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
+    assert!(!client.has_access(&t.renter, &other_contract, &1u64));
+}
+
+// ============================================================
+// Unit Tests: Rental Extension
+// ============================================================
+
+#[test]
+fn test_extend_rental_updates_end_time_and_cost() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    token_sac.mint(&t.renter, &10_000);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &50u32,
+    );
+
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    // end_time = 0 + 3600*2 = 7200, total_paid = 200
+
+    set_timestamp(&t.env, 2000);
+    client.extend_rental(&t.renter, &rental_id, &3u32);
+
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.periods, 5);
+    assert_eq!(rental.end_time, 3600 * 5); // 0 + 3600*5
+    assert_eq!(rental.total_paid, 500);
+}
+
+#[test]
+fn test_extend_rental_escrows_additional_tokens() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    token_sac.mint(&t.renter, &10_000);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    let renter_after_rent = token_client.balance(&t.renter);
+    let contract_after_rent = token_client.balance(&t.contract_id);
+
+    client.extend_rental(&t.renter, &rental_id, &2u32);
+
+    // Additional 200 tokens escrowed; owner still receives nothing until expiry
+    assert_eq!(token_client.balance(&t.renter), renter_after_rent - 200);
+    assert_eq!(token_client.balance(&t.contract_id), contract_after_rent + 200);
+    assert_eq!(token_client.balance(&t.owner), 0);
+}
+
+#[test]
+fn test_extend_rental_keeps_locked_in_price() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    token_sac.mint(&t.renter, &10_000);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    // A later price change only applies to new rentals
+    client.update_listing_price(&t.owner, &listing_id, &300i128);
+    client.extend_rental(&t.renter, &rental_id, &2u32);
+
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.price_per_period, 100);
+    assert_eq!(rental.total_paid, 300);
+    let event: (u64, i128, u64) = last_event(&t, "rental_extended");
+    assert_eq!(event, (rental_id, 200, 3600 * 3));
+}
+
+#[test]
+#[should_panic(expected = "extensions not allowed")]
+fn test_extend_rental_not_allowed_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &false, &50u32, // no extensions
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.extend_rental(&t.renter, &rental_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "exceeds max periods")]
+fn test_extend_beyond_max_periods_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    token_sac.mint(&t.renter, &10_000);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &3u32, &true, &50u32, // max = 3
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    // Already at 2; extending by 2 would give 4 which exceeds max of 3
+    client.extend_rental(&t.renter, &rental_id, &2u32);
+}
+
+#[test]
+#[should_panic(expected = "not the renter")]
+fn test_extend_rental_wrong_caller_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.extend_rental(&t.owner, &rental_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "additional periods must be > 0")]
+fn test_extend_rental_zero_periods_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.extend_rental(&t.renter, &rental_id, &0u32);
+}
+
+#[test]
+#[should_panic(expected = "rental is not active")]
+fn test_extend_terminated_rental_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 1000);
+    client.terminate_rental(&t.renter, &rental_id);
+    client.extend_rental(&t.renter, &rental_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "rental is not active")]
+fn test_extend_rental_past_end_time_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    // end_time = 3600; the rental has lapsed even though nobody called expire_rental
+    set_timestamp(&t.env, 4000);
+    client.extend_rental(&t.renter, &rental_id, &1u32);
+}
+
+// ============================================================
+// Unit Tests: Scheduled Rentals & Owner Cancellation
+// ============================================================
+
+#[test]
+fn test_scheduled_rental_access_starts_at_start_time() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent_scheduled(&t.renter, &listing_id, &1u32, &10_000u64);
+
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.start_time, 10_000);
+    assert_eq!(rental.end_time, 13_600);
+
+    assert!(!client.has_access(&t.renter, &t.nft_contract, &1u64));
+    set_timestamp(&t.env, 10_000);
+    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
+}
+
+#[test]
+fn test_owner_cancel_rental_before_start_refunds_in_full() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &50i128);
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent_scheduled(&t.renter, &listing_id, &2u32, &10_000u64);
+
+    set_timestamp(&t.env, 5000);
+    client.owner_cancel_rental(&t.owner, &rental_id);
+
+    assert_eq!(token_client.balance(&t.renter), renter_before);
+    assert_eq!(token_client.balance(&t.owner), 0);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+
+    let rental = client.get_rental(&rental_id);
+    assert!(matches!(rental.status, RentalStatus::Terminated));
+    assert!(matches!(rental.deposit_status, DepositStatus::Returned));
+    assert!(matches!(
+        client.get_rental_history(&rental_id).final_status,
+        RentalStatus::Terminated
+    ));
+}
+
+#[test]
+#[should_panic(expected = "rental already started")]
+fn test_owner_cancel_rental_after_start_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent_scheduled(&t.renter, &listing_id, &1u32, &1000u64);
+
+    set_timestamp(&t.env, 1000);
+    client.owner_cancel_rental(&t.owner, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "not the owner")]
+fn test_owner_cancel_rental_wrong_caller_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent_scheduled(&t.renter, &listing_id, &1u32, &1000u64);
+    client.owner_cancel_rental(&t.renter, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "rental is disputed")]
+fn test_owner_cancel_rental_while_disputed_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &50i128);
+    let rental_id = client.rent_scheduled(&t.renter, &listing_id, &1u32, &1000u64);
+    client.open_dispute(&t.renter, &rental_id, &Symbol::new(&t.env, "broken"));
+    client.owner_cancel_rental(&t.owner, &rental_id);
+}
+
+// ============================================================
+// Unit Tests: Pay-Per-Period Billing
+// ============================================================
+
+#[test]
+fn test_pay_per_period_charges_one_period_up_front() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_billing_mode(&t.owner, &listing_id, &BillingMode::PayPerPeriod, &600u64);
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32);
+
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.periods, 3);
+    assert_eq!(rental.periods_paid, 1);
+    assert_eq!(rental.total_paid, 100);
+    assert_eq!(rental.end_time, 3 * 3600);
+    assert_eq!(token_client.balance(&t.renter), renter_before - 100);
+
+    // Access only covers the paid period
+    set_timestamp(&t.env, 3700);
+    assert!(!client.has_access(&t.renter, &t.nft_contract, &1u64));
+
+    // Paying within grace restores access for the next period
+    client.pay_next_period(&t.renter, &rental_id);
+    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.periods_paid, 2);
+    assert_eq!(rental.total_paid, 200);
+}
+
+#[test]
+fn test_prepaid_listing_is_unchanged() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    assert!(matches!(client.get_listing(&listing_id).billing_mode, BillingMode::Prepaid));
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32);
+
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.periods_paid, 3);
+    assert_eq!(rental.total_paid, 300);
+    assert!(!client.is_payment_overdue(&rental_id));
+}
+
+#[test]
+fn test_owner_terminates_lapsed_pay_per_period_rental() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &100u32,
+    );
+    client.set_billing_mode(&t.owner, &listing_id, &BillingMode::PayPerPeriod, &600u64);
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32);
+
+    set_timestamp(&t.env, 4000);
+    assert!(!client.is_payment_overdue(&rental_id));
+    set_timestamp(&t.env, 4200);
+    assert!(client.is_payment_overdue(&rental_id));
+
+    // The paid period was fully consumed, so nothing is refunded
+    client.terminate_rental(&t.owner, &rental_id);
+    assert_eq!(token_client.balance(&t.owner), 100);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+}
+
+#[test]
+fn test_unbounded_payment_grace_never_lapses() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_billing_mode(&t.owner, &listing_id, &BillingMode::PayPerPeriod, &u64::MAX);
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32);
+
+    set_timestamp(&t.env, 10_000);
+    assert!(!client.is_payment_overdue(&rental_id));
+    client.pay_next_period(&t.renter, &rental_id);
+    assert_eq!(client.get_rental(&rental_id).periods_paid, 2);
+}
+
+#[test]
+#[should_panic(expected = "payment window lapsed")]
+fn test_pay_next_period_after_grace_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_billing_mode(&t.owner, &listing_id, &BillingMode::PayPerPeriod, &600u64);
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32);
+
+    set_timestamp(&t.env, 4200);
+    client.pay_next_period(&t.renter, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "all periods paid")]
+fn test_pay_next_period_beyond_term_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_billing_mode(&t.owner, &listing_id, &BillingMode::PayPerPeriod, &600u64);
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+
+    client.pay_next_period(&t.renter, &rental_id);
+    client.pay_next_period(&t.renter, &rental_id);
+}
+
+// ============================================================
+// Unit Tests: Rental Transfers
+// ============================================================
+
+#[test]
+fn test_transfer_rental_reassigns_access_and_indexes() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let new_renter = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    let renter_balance = token_client.balance(&t.renter);
+
+    set_timestamp(&t.env, 1000);
+    client.transfer_rental(&t.renter, &rental_id, &new_renter);
+
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.renter, new_renter);
+    assert_eq!(rental.end_time, 7200);
+    assert_eq!(client.get_renter_rentals(&t.renter).len(), 0);
+    assert_eq!(client.get_renter_rentals(&new_renter).len(), 1);
+    assert!(client.has_access(&new_renter, &t.nft_contract, &1u64));
+    assert!(!client.has_access(&t.renter, &t.nft_contract, &1u64));
+
+    // Pure assignment: no tokens move
+    assert_eq!(token_client.balance(&t.renter), renter_balance);
+    assert_eq!(token_client.balance(&new_renter), 0);
+}
+
+#[test]
+fn test_transfer_rental_disables_auto_renew() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    let new_renter = Address::generate(&t.env);
+    token_sac.mint(&new_renter, &1000);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    client.set_auto_renew(&t.renter, &rental_id, &true);
+
+    // The new renter has an allowance out for their own rentals
+    token_client.approve(&new_renter, &t.contract_id, &1000i128, &1000u32);
+    client.transfer_rental(&t.renter, &rental_id, &new_renter);
+    assert!(!client.get_rental(&rental_id).auto_renew);
+
+    set_timestamp(&t.env, 7200);
+    client.expire_rental(&rental_id);
+    assert_eq!(token_client.balance(&new_renter), 1000);
+    assert_eq!(token_client.allowance(&new_renter, &t.contract_id), 1000);
+}
+
+#[test]
+fn test_transfer_rental_hands_over_deposit() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    let new_renter = Address::generate(&t.env);
+    token_sac.mint(&new_renter, &1000);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    let renter_balance = token_client.balance(&t.renter);
+
+    set_timestamp(&t.env, 1000);
+    client.transfer_rental(&t.renter, &rental_id, &new_renter);
+
+    // The outgoing renter is repaid their deposit by the incoming one
+    assert_eq!(token_client.balance(&t.renter), renter_balance + 500);
+    assert_eq!(token_client.balance(&new_renter), 500);
+    assert_eq!(token_client.balance(&t.contract_id), 700);
+
+    set_timestamp(&t.env, 7200);
+    client.expire_rental(&rental_id);
+    client.return_deposit(&t.owner, &rental_id);
+    assert_eq!(token_client.balance(&new_renter), 1000);
+    assert_eq!(token_client.balance(&t.renter), renter_balance + 500);
+}
+
+#[test]
+#[should_panic(expected = "rental is disputed")]
+fn test_transfer_rental_while_disputed_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let new_renter = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    client.open_dispute(&t.renter, &rental_id, &Symbol::new(&t.env, "broken"));
+    client.transfer_rental(&t.renter, &rental_id, &new_renter);
+}
+
+#[test]
+#[should_panic(expected = "listing does not allow subleasing")]
+fn test_transfer_rental_when_owner_opted_out_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let new_renter = Address::generate(&t.env);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_subleasable(&t.owner, &listing_id, &false);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.transfer_rental(&t.renter, &rental_id, &new_renter);
+}
+
+#[test]
+#[should_panic(expected = "rental is not active")]
+fn test_transfer_rental_after_end_time_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let new_renter = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 3600);
+    client.transfer_rental(&t.renter, &rental_id, &new_renter);
+}
+
+#[test]
+#[should_panic(expected = "not the renter")]
+fn test_transfer_rental_wrong_caller_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let new_renter = Address::generate(&t.env);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.transfer_rental(&new_renter, &rental_id, &new_renter);
+}
+
+// ============================================================
+// Unit Tests: Expiration
+// ============================================================
+
+#[test]
+fn test_expire_rental_sets_expired_status() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    // end_time = 4600
+
+    set_timestamp(&t.env, 5000);
+    client.expire_rental(&rental_id);
+
+    let rental = client.get_rental(&rental_id);
+    assert!(matches!(rental.status, RentalStatus::Expired));
+}
+
+#[test]
+fn test_expire_rental_writes_history() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &200i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+
+    let history = client.get_rental_history(&rental_id);
+    assert_eq!(history.rental_id, rental_id);
+    assert_eq!(history.total_paid, 200);
+    assert!(matches!(history.final_status, RentalStatus::Expired));
+}
+
+#[test]
+#[should_panic(expected = "rental has not expired yet")]
+fn test_expire_rental_too_early_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 2000); // end_time = 4600, not yet
+    client.expire_rental(&rental_id);
+}
+
+#[test]
+#[should_panic(expected = "rental already closed")]
+fn test_expire_already_expired_rental_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+    client.expire_rental(&rental_id); // second call should panic
+}
+
+#[test]
+fn test_expire_rentals_batch_skips_closed_missing_and_pending() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &4u32);
+    let due1 = client.rent(&t.renter, &listing_id, &1u32); // ends 3600
+    let due2 = client.rent(&t.renter, &listing_id, &1u32); // ends 3600
+    let pending = client.rent(&t.renter, &listing_id, &5u32); // ends 18000
+    let closed = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 1000);
+    client.terminate_rental(&t.renter, &closed);
+
+    set_timestamp(&t.env, 4000);
+    let ids = vec![&t.env, due1, 999u64, closed, due2, pending];
+    let expired = client.expire_rentals(&ids);
+
+    assert_eq!(expired, 2);
+    assert!(matches!(client.get_rental(&due1).status, RentalStatus::Expired));
+    assert!(matches!(client.get_rental(&due2).status, RentalStatus::Expired));
+    assert!(matches!(client.get_rental(&pending).status, RentalStatus::Active));
+    assert!(matches!(client.get_rental(&closed).status, RentalStatus::Terminated));
+    assert!(matches!(
+        client.get_rental_history(&due1).final_status,
+        RentalStatus::Expired
+    ));
+
+    // Owner received the escrow of both expired rentals plus the terminated one
+    assert_eq!(token_client.balance(&t.owner), 300);
+    assert_eq!(token_client.balance(&t.contract_id), 500);
+}
+
+// ============================================================
+// Unit Tests: Early Termination & Refunds
+// ============================================================
+
+#[test]
+fn test_terminate_rental_with_full_refund() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true,
+        &100u32, // 100% refund
+    );
+
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    // paid = 1000, end_time = 10000; escrowed in contract
+
+    // Terminate at 5000 → 50% unused → refund = 500, owner gets 500
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &rental_id);
+
+    assert_eq!(token_client.balance(&t.renter), renter_before - 500);
+    assert_eq!(token_client.balance(&t.owner), 500);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+}
+
+#[test]
+fn test_terminate_rental_with_partial_refund() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true,
+        &50u32, // 50% refund
+    );
+
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    // paid = 1000, end_time = 10000; escrowed in contract
+
+    // Terminate at 5000 → 50% unused → refund = 1000 * 0.5 * 50% = 250, owner gets 750
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &rental_id);
+
+    assert_eq!(token_client.balance(&t.renter), renter_before - 750);
+    assert_eq!(token_client.balance(&t.owner), 750);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+}
+
+#[test]
+fn test_terminate_rental_with_no_refund() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true,
+        &0u32, // 0% refund
+    );
+
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &rental_id);
+
+    // No refund; full amount goes to owner
+    assert_eq!(token_client.balance(&t.renter), renter_before - 1000);
+    assert_eq!(token_client.balance(&t.owner), 1000);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+}
+
+#[test]
+fn test_terminate_rental_sets_terminated_status() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 1000);
+    client.terminate_rental(&t.renter, &rental_id);
+
+    let rental = client.get_rental(&rental_id);
+    assert!(matches!(rental.status, RentalStatus::Terminated));
+}
+
+#[test]
+fn test_terminate_rental_writes_history() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &500i128, &10_000u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &rental_id);
+
+    let history = client.get_rental_history(&rental_id);
+    assert_eq!(history.rental_id, rental_id);
+    assert_eq!(history.total_paid, 500);
+    assert!(matches!(history.final_status, RentalStatus::Terminated));
+}
+
+#[test]
+#[should_panic(expected = "not a party to this rental")]
+fn test_terminate_rental_wrong_caller_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let stranger = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.terminate_rental(&stranger, &rental_id);
+}
+
+#[test]
+fn test_owner_can_terminate_rental_with_refund() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true,
+        &100u32, // 100% refund
+    );
+
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &4u32);
+    // paid = 4000, end_time = 40000
+
+    // Owner terminates after one period → 3 of 4 periods unused → refund = 3000
+    set_timestamp(&t.env, 10_000);
+    client.terminate_rental(&t.owner, &rental_id);
+
+    assert_eq!(token_client.balance(&t.renter), renter_before - 1000);
+    assert_eq!(token_client.balance(&t.owner), 1000);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+
+    let history = client.get_rental_history(&rental_id);
+    assert!(matches!(history.final_status, RentalStatus::Terminated));
+}
+
+#[test]
+#[should_panic(expected = "rental period has ended")]
+fn test_terminate_rental_after_end_time_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &100u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 3600);
+    client.terminate_rental(&t.renter, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "rental is not active")]
+fn test_terminate_already_terminated_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 1000);
+    client.terminate_rental(&t.renter, &rental_id);
+    client.terminate_rental(&t.renter, &rental_id); // second call should panic
+}
+
+// ============================================================
+// Unit Tests: Security Deposits
+// ============================================================
+
+#[test]
+fn test_rent_collects_deposit_into_escrow() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.total_paid, 200);
+    assert_eq!(rental.deposit_amount, 500);
+    assert!(matches!(rental.deposit_status, DepositStatus::Held));
+    assert_eq!(token_client.balance(&t.renter), renter_before - 700);
+    assert_eq!(token_client.balance(&t.contract_id), 700);
+}
+
+#[test]
+fn test_return_deposit_refunds_renter() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+    client.return_deposit(&t.owner, &rental_id);
+
+    assert_eq!(token_client.balance(&t.renter), renter_before - 100);
+    assert_eq!(token_client.balance(&t.owner), 100);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+    assert!(matches!(
+        client.get_rental(&rental_id).deposit_status,
+        DepositStatus::Returned
+    ));
+}
+
+#[test]
+fn test_claim_deposit_pays_owner() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+    client.claim_deposit(&t.owner, &rental_id);
+
+    assert_eq!(token_client.balance(&t.owner), 600);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+    assert!(matches!(
+        client.get_rental(&rental_id).deposit_status,
+        DepositStatus::Claimed
+    ));
+}
+
+#[test]
+#[should_panic(expected = "deposit already settled")]
+fn test_deposit_cannot_be_released_twice() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.return_deposit(&t.owner, &rental_id);
+    client.claim_deposit(&t.owner, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "rental period not over")]
+fn test_claim_deposit_during_rental_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 1000);
+    client.claim_deposit(&t.owner, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "no deposit held")]
+fn test_return_deposit_without_deposit_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.return_deposit(&t.owner, &rental_id);
+}
+
+// ============================================================
+// Unit Tests: Return Grace Period
+// ============================================================
+
+#[test]
+#[should_panic(expected = "nft returned within grace")]
+fn test_on_time_return_blocks_deposit_claim() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    client.set_return_grace(&t.owner, &listing_id, &600u64);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 3000);
+    client.mark_returned(&t.owner, &rental_id);
+    assert_eq!(client.get_rental(&rental_id).returned_at, 3000);
+
+    set_timestamp(&t.env, 10_000);
+    assert!(!client.is_overdue(&rental_id));
+    client.claim_deposit(&t.owner, &rental_id);
+}
+
+#[test]
+fn test_return_within_grace_is_not_overdue() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    client.set_return_grace(&t.owner, &listing_id, &600u64);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    // Past end_time (3600) but inside the 600s grace window
+    set_timestamp(&t.env, 4200);
+    assert!(!client.is_overdue(&rental_id));
+    client.mark_returned(&t.owner, &rental_id);
+
+    set_timestamp(&t.env, 10_000);
+    assert!(!client.is_overdue(&rental_id));
+    client.expire_rental(&rental_id);
+    client.return_deposit(&t.owner, &rental_id);
+    assert_eq!(token_client.balance(&t.renter), 10_000 - 100);
+}
+
+#[test]
+#[should_panic(expected = "return grace period not over")]
+fn test_unbounded_return_grace_is_never_overdue() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    client.set_return_grace(&t.owner, &listing_id, &u64::MAX);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 1_000_000);
+    assert!(!client.is_overdue(&rental_id));
+    client.claim_deposit(&t.owner, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "return grace period not over")]
+fn test_claim_deposit_during_return_grace_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    client.set_return_grace(&t.owner, &listing_id, &600u64);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.claim_deposit(&t.owner, &rental_id);
+}
+
+#[test]
+fn test_overdue_return_allows_deposit_claim() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    client.set_return_grace(&t.owner, &listing_id, &600u64);
+    let late = client.rent(&t.renter, &listing_id, &1u32);
+
+    // Grace ends at 4200; the NFT is still out
+    set_timestamp(&t.env, 4201);
+    assert!(client.is_overdue(&late));
+    client.expire_rental(&late);
+
+    // A late hand-back clears the overdue flag but the deposit stays claimable
+    client.mark_returned(&t.owner, &late);
+    assert!(!client.is_overdue(&late));
+    client.claim_deposit(&t.owner, &late);
+
+    assert_eq!(token_client.balance(&t.owner), 600);
+    assert!(matches!(
+        client.get_rental(&late).deposit_status,
+        DepositStatus::Claimed
+    ));
+}
+
+#[test]
+#[should_panic(expected = "nft already returned")]
+fn test_mark_returned_twice_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.mark_returned(&t.owner, &rental_id);
+    client.mark_returned(&t.owner, &rental_id);
+}
+
+// ============================================================
+// Unit Tests: Disputes
+// ============================================================
+
+#[test]
+fn test_dispute_resolution_splits_deposit() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 1000);
+    let reason = Symbol::new(&t.env, "damaged");
+    client.open_dispute(&t.renter, &rental_id, &reason);
+    let rental = client.get_rental(&rental_id);
+    assert!(rental.disputed);
+    assert_eq!(rental.dispute_reason, Some(reason.clone()));
+    let event: (u64, Address, Symbol, u64) = last_event(&t, "dispute_opened");
+    assert_eq!(event, (rental_id, t.renter.clone(), reason, 1000));
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+    client.resolve_dispute(&t.admin, &rental_id, &3000u32);
+
+    // 30% of the 500 deposit back to the renter, 70% to the owner
+    assert_eq!(token_client.balance(&t.renter), 10_000 - 100 - 500 + 150);
+    assert_eq!(token_client.balance(&t.owner), 100 + 350);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+    let event: (u64, i128, i128, u64) = last_event(&t, "dispute_resolved");
+    assert_eq!(event, (rental_id, 150, 350, 4000));
+
+    let rental = client.get_rental(&rental_id);
+    assert!(!rental.disputed);
+    assert!(matches!(rental.deposit_status, DepositStatus::Resolved));
+}
+
+#[test]
+#[should_panic(expected = "rental is disputed")]
+fn test_dispute_blocks_deposit_release() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.open_dispute(&t.owner, &rental_id, &Symbol::new(&t.env, "late"));
+
+    set_timestamp(&t.env, 4000);
+    client.claim_deposit(&t.owner, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "rental is disputed")]
+fn test_dispute_blocks_termination() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.open_dispute(&t.renter, &rental_id, &Symbol::new(&t.env, "broken"));
+    client.terminate_rental(&t.renter, &rental_id);
+}
+
+#[test]
+#[should_panic(expected = "not a party to this rental")]
+fn test_open_dispute_by_outsider_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let outsider = Address::generate(&t.env);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.open_dispute(&outsider, &rental_id, &Symbol::new(&t.env, "spam"));
+}
+
+#[test]
+#[should_panic(expected = "rental not disputed")]
+fn test_resolve_without_dispute_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.resolve_dispute(&t.admin, &rental_id, &5000u32);
+}
+
+// ============================================================
+// Unit Tests: Royalty Splits
+// ============================================================
+
+#[test]
+fn test_royalty_split_on_expiry_with_rounding_to_owner() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let creator_a = Address::generate(&t.env);
+    let creator_b = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &333i128, &3600u64, &10u32, &true, &0u32,
+    );
+    // 10% and 2.5%
+    client.set_royalty_payees(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, (creator_a.clone(), 1000u32), (creator_b.clone(), 250u32)],
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+
+    // 333 * 10% = 33.3 → 33, 333 * 2.5% = 8.325 → 8, owner gets 292 incl. dust
+    assert_eq!(token_client.balance(&creator_a), 33);
+    assert_eq!(token_client.balance(&creator_b), 8);
+    assert_eq!(token_client.balance(&t.owner), 292);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+    assert_eq!(client.get_owner_revenue(&t.owner), 292);
+}
+
+#[test]
+fn test_royalty_split_applies_to_owner_share_on_termination() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let creator = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true, &100u32,
+    );
+    client.set_royalty_payees(&t.owner, &listing_id, &vec![&t.env, (creator.clone(), 2000u32)]);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    // Half the period used → owner share 500, 20% of that to the creator
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &rental_id);
+
+    assert_eq!(token_client.balance(&creator), 100);
+    assert_eq!(token_client.balance(&t.owner), 400);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+}
+
+#[test]
+fn test_empty_royalty_payees_pays_owner_in_full() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &333i128, &3600u64, &10u32, &true, &0u32,
+    );
+    assert_eq!(client.get_listing(&listing_id).royalty_payees.len(), 0);
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+
+    assert_eq!(token_client.balance(&t.owner), 333);
+}
+
+#[test]
+#[should_panic(expected = "royalty bps exceed 10000")]
+fn test_royalty_payees_over_10000_bps_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let creator_a = Address::generate(&t.env);
+    let creator_b = Address::generate(&t.env);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_royalty_payees(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, (creator_a, 6000u32), (creator_b, 4001u32)],
+    );
+}
+
+// ============================================================
+// Unit Tests: Co-Ownership
+// ============================================================
+
+#[test]
+fn test_co_owners_split_payout_with_dust_to_first() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let alice = Address::generate(&t.env);
+    let bob = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &101i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_co_owners(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, (alice.clone(), 6000u32), (bob.clone(), 4000u32)],
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+
+    // 101 * 60% = 60.6 and 101 * 40% = 40.4; the 1 unit of dust goes to alice
+    assert_eq!(token_client.balance(&alice), 61);
+    assert_eq!(token_client.balance(&bob), 40);
+    assert_eq!(token_client.balance(&t.owner), 0);
+    assert_eq!(client.get_owner_revenue(&alice), 61);
+    assert_eq!(client.get_owner_revenue(&bob), 40);
+}
+
+#[test]
+#[should_panic(expected = "co-owner shares must sum to 10000")]
+fn test_co_owner_shares_not_summing_to_full_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_co_owners(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, (t.owner.clone(), 6000u32), (t.renter.clone(), 3000u32)],
+    );
+}
+
+// ============================================================
+// Unit Tests: Platform Fee
+// ============================================================
+
+#[test]
+fn test_platform_fee_plus_owner_payout_equals_charge() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let treasury = Address::generate(&t.env);
+
+    client.set_fee(&t.admin, &250u32, &treasury); // 2.5%
+    let config = client.get_config();
+    assert_eq!(config.fee_bps, 250);
+    assert_eq!(config.fee_recipient, treasury);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &333i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let renter_before = token_client.balance(&t.renter);
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32); // 999
+
+    set_timestamp(&t.env, 20_000);
+    client.expire_rental(&rental_id);
+
+    let charged = renter_before - token_client.balance(&t.renter);
+    let fee = token_client.balance(&treasury);
+    let owner_net = token_client.balance(&t.owner);
+    assert_eq!(charged, 999);
+    assert_eq!(fee, 24); // 999 * 2.5% = 24.975 → 24
+    assert_eq!(owner_net, 975);
+    assert_eq!(owner_net + fee, charged);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+}
+
+#[test]
+fn test_fee_change_does_not_affect_in_flight_rentals() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let treasury = Address::generate(&t.env);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.set_fee(&t.admin, &1000u32, &treasury);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+
+    assert_eq!(token_client.balance(&treasury), 0);
+    assert_eq!(token_client.balance(&t.owner), 1000);
+}
+
+#[test]
+#[should_panic(expected = "fee bps exceed 10000")]
+fn test_set_fee_over_10000_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    client.set_fee(&t.admin, &10_001u32, &t.admin);
+}
+
+#[test]
+#[should_panic(expected = "not the admin")]
+fn test_set_fee_non_admin_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    client.set_fee(&t.owner, &100u32, &t.owner);
+}
+
+// ============================================================
+// Unit Tests: Marketplace Discovery
+// ============================================================
+
+#[test]
+fn test_marketplace_pagination_first_page() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    for i in 0..5u64 {
+        client.create_listing(
+            &t.owner, &t.nft_contract, &i, &t.token_id,
+            &100i128, &3600u64, &10u32, &true, &50u32,
+        );
+    }
+
+    let page = client.get_active_listings(&0u64, &3u32);
+    assert_eq!(page.listings.len(), 3);
+    assert_eq!(page.total, 5);
+}
+
+#[test]
+fn test_marketplace_pagination_second_page() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    for i in 0..5u64 {
+        client.create_listing(
+            &t.owner, &t.nft_contract, &i, &t.token_id,
+            &100i128, &3600u64, &10u32, &true, &50u32,
+        );
+    }
+
+    let page = client.get_active_listings(&3u64, &3u32);
+    assert_eq!(page.listings.len(), 2);
+    assert_eq!(page.total, 5);
+}
+
+#[test]
+fn test_marketplace_empty_when_no_listings() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let page = client.get_active_listings(&0u64, &10u32);
+    assert_eq!(page.listings.len(), 0);
+    assert_eq!(page.total, 0);
+}
+
+#[test]
+fn test_marketplace_excludes_cancelled_listings() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let id1 = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+
+    client.cancel_listing(&t.owner, &id1);
+
+    let page = client.get_active_listings(&0u64, &10u32);
+    // id1 was removed from the index on cancellation
+    assert_eq!(page.total, 1);
+}
+
+#[test]
+fn test_marketplace_page_offset_past_end_is_empty() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    for i in 0..3u64 {
+        client.create_listing(
+            &t.owner, &t.nft_contract, &i, &t.token_id,
+            &100i128, &3600u64, &10u32, &true, &0u32,
+        );
+    }
+
+    let page = client.get_marketplace_page(&10u64, &5u32);
+    assert_eq!(page.listings.len(), 0);
+    assert_eq!(page.total, 3);
+
+    let page = client.get_marketplace_page(&u64::MAX, &u32::MAX);
+    assert_eq!(page.listings.len(), 0);
+}
+
+#[test]
+fn test_marketplace_page_limit_is_capped() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    for i in 0..55u64 {
+        client.create_listing(
+            &t.owner, &t.nft_contract, &i, &t.token_id,
+            &100i128, &3600u64, &10u32, &true, &0u32,
+        );
+    }
+
+    let page = client.get_marketplace_page(&0u64, &100u32);
+    assert_eq!(page.listings.len(), 50);
+    assert_eq!(page.total, 55);
+
+    let page = client.get_marketplace_page(&50u64, &100u32);
+    assert_eq!(page.listings.len(), 5);
+}
+
+#[test]
+fn test_listings_by_nft_contract_filters_collection() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let other_collection = register_nft(&t.env, &t.owner);
+
+    let a1 = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.create_listing(
+        &t.owner, &other_collection, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let a2 = client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+
+    let page = client.get_listings_by_nft_contract(&t.nft_contract, &0u64, &10u32);
+    assert_eq!(page.total, 2);
+    assert_eq!(page.listings.get(0).unwrap().listing_id, a1);
+    assert_eq!(page.listings.get(1).unwrap().listing_id, a2);
+
+    let page = client.get_listings_by_nft_contract(&other_collection, &0u64, &10u32);
+    assert_eq!(page.total, 1);
+}
+
+#[test]
+fn test_listings_by_nft_contract_pruned_on_cancel_and_pause() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let a1 = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let a2 = client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let a3 = client.create_listing(
+        &t.owner, &t.nft_contract, &3u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+
+    client.cancel_listing(&t.owner, &a1);
+    client.pause_listing(&t.owner, &a2);
+
+    let page = client.get_listings_by_nft_contract(&t.nft_contract, &0u64, &10u32);
+    assert_eq!(page.total, 1);
+    assert_eq!(page.listings.get(0).unwrap().listing_id, a3);
+
+    client.resume_listing(&t.owner, &a2);
+    let page = client.get_listings_by_nft_contract(&t.nft_contract, &0u64, &10u32);
+    assert_eq!(page.total, 2);
+}
+
+#[test]
+fn test_listings_by_price_filters_token_and_range() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let other_token = Address::generate(&t.env);
+
+    let prices = [50i128, 100, 150, 200, 250];
+    for (i, price) in prices.iter().enumerate() {
+        client.create_listing(
+            &t.owner, &t.nft_contract, &(i as u64), &t.token_id,
+            price, &3600u64, &10u32, &true, &0u32,
+        );
+    }
+    // Same price, different payment token
+    client.create_listing(
+        &t.owner, &t.nft_contract, &99u64, &other_token,
+        &150i128, &3600u64, &10u32, &true, &0u32,
+    );
+
+    let page = client.get_listings_by_price(&t.token_id, &100i128, &200i128, &0u32, &10u32);
+    assert_eq!(page.listings.len(), 3);
+    assert_eq!(page.listings.get(0).unwrap().price_per_period, 100);
+    assert_eq!(page.listings.get(2).unwrap().price_per_period, 200);
+    assert_eq!(page.next_start, None);
+
+    // The scan stops once the page is full and resumes from `next_start`
+    let page = client.get_listings_by_price(&t.token_id, &100i128, &200i128, &0u32, &2u32);
+    assert_eq!(page.listings.len(), 2);
+    assert_eq!(page.next_start, Some(3));
+
+    let page = client.get_listings_by_price(&t.token_id, &100i128, &200i128, &3u32, &2u32);
+    assert_eq!(page.listings.len(), 1);
+    assert_eq!(page.listings.get(0).unwrap().price_per_period, 200);
+    assert_eq!(page.next_start, None);
+}
+
+#[test]
+fn test_listings_by_price_pages_past_scan_window() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    // Creating a full window of listings outgrows the default test budget
+    t.env.budget().reset_unlimited();
+    for i in 0..MAX_SCAN_WINDOW + 5 {
+        // Only the last listing is in range
+        let price = if i == MAX_SCAN_WINDOW + 4 { 500i128 } else { 100i128 };
+        client.create_listing(
+            &t.owner, &t.nft_contract, &(i as u64), &t.token_id,
+            &price, &3600u64, &10u32, &true, &0u32,
+        );
+    }
+
+    let page = client.get_listings_by_price(&t.token_id, &400i128, &600i128, &0u32, &10u32);
+    assert_eq!(page.listings.len(), 0);
+    assert_eq!(page.next_start, Some(MAX_SCAN_WINDOW));
+
+    let page = client.get_listings_by_price(&t.token_id, &400i128, &600i128, &MAX_SCAN_WINDOW, &10u32);
+    assert_eq!(page.listings.len(), 1);
+    assert_eq!(page.listings.get(0).unwrap().price_per_period, 500);
+    assert_eq!(page.next_start, None);
+}
+
+#[test]
+fn test_listings_by_price_skips_paused_listings() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let paused = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.pause_listing(&t.owner, &paused);
+
+    let page = client.get_listings_by_price(&t.token_id, &0i128, &1000i128, &0u32, &10u32);
+    assert_eq!(page.listings.len(), 1);
+}
+
+// ============================================================
+// Unit Tests: History Tracking
+// ============================================================
+
+#[test]
+fn test_rental_history_stored_after_expiry() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &200i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+
+    set_timestamp(&t.env, 10_000);
+    client.expire_rental(&rental_id);
+
+    let history = client.get_rental_history(&rental_id);
+    assert_eq!(history.rental_id, rental_id);
+    assert_eq!(history.listing_id, listing_id);
+    assert_eq!(history.owner, t.owner);
+    assert_eq!(history.renter, t.renter);
+    assert_eq!(history.total_paid, 400);
+    assert_eq!(history.start_time, 1000);
+    assert_eq!(history.end_time, 1000 + 3600 * 2);
+    assert!(matches!(history.final_status, RentalStatus::Expired));
+}
+
+#[test]
+#[should_panic(expected = "rental history not found")]
+fn test_get_rental_history_before_close_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    // Rental is still active; history doesn't exist yet
+    client.get_rental_history(&rental_id);
+}
+
+#[test]
+fn test_renter_rental_history_is_newest_first_and_paginated() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_a = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let listing_b = client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let first = client.rent(&t.renter, &listing_a, &1u32);
+    let second = client.rent(&t.renter, &listing_b, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&first);
+    client.expire_rental(&second);
+    let third = client.rent(&t.renter, &listing_a, &2u32);
+
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &third);
+    let open = client.rent(&t.renter, &listing_b, &1u32);
+    assert_ne!(open, third);
+
+    // The still-open rental occupies the newest slot but has no record
+    let page = client.get_renter_rental_history(&t.renter, &0u64, &2u32);
+    assert_eq!(page.total, 4);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().rental_id, third);
+    assert!(matches!(
+        page.records.get(0).unwrap().final_status,
+        RentalStatus::Terminated
+    ));
+
+    let page = client.get_renter_rental_history(&t.renter, &2u64, &2u32);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.records.get(0).unwrap().rental_id, second);
+    assert_eq!(page.records.get(0).unwrap().listing_id, listing_b);
+    assert_eq!(page.records.get(1).unwrap().rental_id, first);
+
+    assert_eq!(client.get_renter_rental_history(&t.renter, &4u64, &2u32).records.len(), 0);
+}
+
+// ============================================================
+// Unit Tests: Owner Revenue
+// ============================================================
+
+#[test]
+fn test_owner_revenue_zero_without_rentals() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    assert_eq!(client.get_owner_revenue(&t.owner), 0);
+}
+
+#[test]
+fn test_owner_revenue_mixes_expired_and_terminated_rentals() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing1 = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing1, &2u32);
+    let listing2 = client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true, &100u32,
+    );
+
+    let expired = client.rent(&t.renter, &listing1, &2u32); // 2000
+    let no_refund = client.rent(&t.renter, &listing1, &1u32); // 1000, 0% refund
+    let refunded = client.rent(&t.renter, &listing2, &1u32); // 1000, 100% refund
+
+    // Terminate both single-period rentals halfway through
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &no_refund); // owner keeps 1000
+    client.terminate_rental(&t.renter, &refunded); // owner keeps 500
+
+    // Still-active rentals do not count yet
+    assert_eq!(client.get_owner_revenue(&t.owner), 1500);
+
+    set_timestamp(&t.env, 20_000);
+    client.expire_rental(&expired); // owner gets 2000
+
+    assert_eq!(client.get_owner_revenue(&t.owner), 3500);
+    assert_eq!(client.get_owner_revenue(&t.owner), token_client.balance(&t.owner));
+}
+
+// ============================================================
+// Unit Tests: Renter Ratings
+// ============================================================
+
+#[test]
+fn test_rate_closed_rentals_aggregates_score() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
+    let expired = client.rent(&t.renter, &listing_id, &1u32);
+    let terminated = client.rent(&t.renter, &listing_id, &2u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&expired);
+    client.terminate_rental(&t.renter, &terminated);
+
+    assert_eq!(client.get_renter_rating(&t.renter), (0, 0));
+    client.rate_rental(&t.owner, &expired, &5u32);
+    client.rate_rental(&t.owner, &terminated, &2u32);
+    assert_eq!(client.get_renter_rating(&t.renter), (7, 2));
+}
+
+#[test]
+#[should_panic(expected = "rental already rated")]
+fn test_rate_rental_twice_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+    client.rate_rental(&t.owner, &rental_id, &4u32);
+    client.rate_rental(&t.owner, &rental_id, &4u32);
+}
+
+#[test]
+#[should_panic(expected = "rental not closed")]
+fn test_rate_active_rental_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.rate_rental(&t.owner, &rental_id, &3u32);
+}
+
+#[test]
+#[should_panic(expected = "score must be 1-5")]
+fn test_rate_rental_out_of_range_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&rental_id);
+    client.rate_rental(&t.owner, &rental_id, &6u32);
+}
+
+// ============================================================
+// Unit Tests: Owner & Renter Index Queries
+// ============================================================
+
+#[test]
+fn test_get_owner_listings_returns_all() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let id1 = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let id2 = client.create_listing(
+        &t.owner, &t.nft_contract, &2u64, &t.token_id,
+        &200i128, &7200u64, &5u32, &false, &0u32,
+    );
+
+    let listings = client.get_owner_listings(&t.owner);
+    assert_eq!(listings.len(), 2);
+    assert_eq!(listings.get(0).unwrap(), id1);
+    assert_eq!(listings.get(1).unwrap(), id2);
+}
+
+#[test]
+fn test_get_owner_listings_empty_for_unknown_owner() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let unknown = Address::generate(&t.env);
+
+    let listings = client.get_owner_listings(&unknown);
+    assert_eq!(listings.len(), 0);
+}
+
+#[test]
+fn test_get_renter_rentals_returns_all() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
+
+    let r1 = client.rent(&t.renter, &listing_id, &1u32);
+    let r2 = client.rent(&t.renter, &listing_id, &1u32);
+
+    let rentals = client.get_renter_rentals(&t.renter);
+    assert_eq!(rentals.len(), 2);
+    assert_eq!(rentals.get(0).unwrap(), r1);
+    assert_eq!(rentals.get(1).unwrap(), r2);
+}
+
+#[test]
+fn test_get_renter_rentals_empty_for_unknown_renter() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let unknown = Address::generate(&t.env);
+
+    let rentals = client.get_renter_rentals(&unknown);
+    assert_eq!(rentals.len(), 0);
+}
+
+// ============================================================
+// Unit Tests: Marketplace Stats
+// ============================================================
+
+#[test]
+fn test_marketplace_stats_track_listings_rentals_and_fees() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let treasury = Address::generate(&t.env);
+    client.set_fee(&t.admin, &1000u32, &treasury);
+
+    let stats = client.get_marketplace_stats();
+    assert_eq!((stats.total_listings, stats.total_rentals, stats.total_volume), (0, 0, 0));
+
+    set_timestamp(&t.env, 0);
+    let ids = client.create_listings_batch(
+        &t.owner,
+        &vec![&t.env, listing_input(&t, 1, 100), listing_input(&t, 2, 200)],
+    );
+    let (listing_a, listing_b) = (ids.get(0).unwrap(), ids.get(1).unwrap());
+    let listing_c = client.create_listing(
+        &t.owner, &t.nft_contract, &3u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.cancel_listing(&t.owner, &listing_c);
+
+    let rental_a = client.rent(&t.renter, &listing_a, &2u32); // 200
+    let rental_b = client.rent(&t.renter, &listing_b, &1u32); // 200
+    client.extend_rental(&t.renter, &rental_a, &1u32); // +100
+
+    set_timestamp(&t.env, 1800);
+    client.terminate_rental(&t.renter, &rental_b); // no refund: fee 20
+    set_timestamp(&t.env, 20_000);
+    client.expire_rental(&rental_a); // fee 30
+
+    let stats = client.get_marketplace_stats();
+    assert_eq!(stats.total_listings, 3);
+    assert_eq!(stats.active_listings, 2);
+    assert_eq!(stats.total_rentals, 2);
+    assert_eq!(stats.total_volume, 500);
+    assert_eq!(stats.total_fees, 50);
+    assert_eq!(stats.total_fees, TokenClient::new(&t.env, &t.token_id).balance(&treasury));
+}
+
+// ============================================================
+// Unit Tests: Events
+// ============================================================
+
+#[test]
+fn test_listing_events_carry_ids_and_timestamps() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 500);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let created: (u64, Address, i128, u64) = last_event(&t, "listing_created");
+    assert_eq!(created, (listing_id, t.owner.clone(), 100, 500));
+
+    set_timestamp(&t.env, 900);
+    client.cancel_listing(&t.owner, &listing_id);
+    let cancelled: (u64, Address, u64) = last_event(&t, "listing_cancelled");
+    assert_eq!(cancelled, (listing_id, t.owner.clone(), 900));
+}
+
+#[test]
+fn test_rental_start_and_extend_events() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 1000);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    let started: (u64, u64, Address, i128, u64, u64) = last_event(&t, "rental_started");
+    assert_eq!(started, (rental_id, listing_id, t.renter.clone(), 200, 1000, 8200));
+
+    client.extend_rental(&t.renter, &rental_id, &1u32);
+    let extended: (u64, i128, u64) = last_event(&t, "rental_extended");
+    assert_eq!(extended, (rental_id, 100, 11_800));
+}
+
+#[test]
+fn test_rental_close_and_deposit_events() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &1000i128, &10_000u64, &10u32, &true, &100u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
+    client.set_listing_deposit(&t.owner, &listing_id, &50i128);
+    let terminated = client.rent(&t.renter, &listing_id, &1u32);
+    let expired = client.rent(&t.renter, &listing_id, &1u32);
+
+    set_timestamp(&t.env, 5000);
+    client.terminate_rental(&t.renter, &terminated);
+    let event: (u64, i128, u64) = last_event(&t, "rental_terminated");
+    assert_eq!(event, (terminated, 500, 5000));
+
+    set_timestamp(&t.env, 12_000);
+    client.expire_rental(&expired);
+    let event: (u64, i128, u64) = last_event(&t, "rental_expired");
+    assert_eq!(event, (expired, 1000, 12_000));
+
+    client.return_deposit(&t.owner, &terminated);
+    let event: (u64, i128, u64) = last_event(&t, "deposit_returned");
+    assert_eq!(event, (terminated, 50, 12_000));
+
+    client.claim_deposit(&t.owner, &expired);
+    let event: (u64, i128, u64) = last_event(&t, "deposit_claimed");
+    assert_eq!(event, (expired, 50, 12_000));
 }
 
 // ============================================================
-// Unit Tests: Expiration
+// Unit Tests: Rentals by NFT
 // ============================================================
 
 #[test]
-fn test_expire_rental_sets_expired_status() {
+fn test_rentals_for_nft_tracks_history_and_active_state() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &7u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let other_listing = client.create_listing(
+        &t.owner, &t.nft_contract, &8u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    assert!(!client.has_active_rental_for_nft(&t.nft_contract, &7u64));
+
+    let r1 = client.rent(&t.renter, &listing_id, &1u32);
+    client.rent(&t.renter, &other_listing, &1u32);
+    assert!(client.has_active_rental_for_nft(&t.nft_contract, &7u64));
+
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&r1);
+    assert!(!client.has_active_rental_for_nft(&t.nft_contract, &7u64));
+
+    let r2 = client.rent(&t.renter, &listing_id, &1u32);
+    assert!(client.has_active_rental_for_nft(&t.nft_contract, &7u64));
+    assert_eq!(
+        client.get_rentals_for_nft(&t.nft_contract, &7u64),
+        vec![&t.env, r1, r2]
+    );
+    assert_eq!(client.get_rentals_for_nft(&t.nft_contract, &9u64).len(), 0);
+}
+
+// ============================================================
+// Unit Tests: Bundled Listings
+// ============================================================
+
+#[test]
+fn test_two_nft_bundle_end_to_end() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let other_collection = register_nft(&t.env, &t.owner);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_bundle_tokens(&t.owner, &listing_id, &vec![&t.env, (other_collection.clone(), 9u64)]);
+    assert_eq!(client.get_listing(&listing_id).extra_tokens.len(), 1);
+
+    // One price covers both NFTs
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    assert_eq!(token_client.balance(&t.contract_id), 200);
 
     set_timestamp(&t.env, 1000);
+    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
+    assert!(client.has_access(&t.renter, &other_collection, &9u64));
+    assert!(client.has_active_rental_for_nft(&other_collection, &9u64));
+    assert_eq!(
+        client.get_rentals_for_nft(&other_collection, &9u64),
+        vec![&t.env, rental_id]
+    );
+
+    // Terminating releases the whole set
+    client.terminate_rental(&t.renter, &rental_id);
+    assert!(!client.has_access(&t.renter, &t.nft_contract, &1u64));
+    assert!(!client.has_access(&t.renter, &other_collection, &9u64));
+    assert!(!client.has_active_rental_for_nft(&t.nft_contract, &1u64));
+    assert!(!client.has_active_rental_for_nft(&other_collection, &9u64));
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "owner does not hold nft")]
+fn test_bundle_token_not_held_by_owner_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let foreign_collection = register_nft(&t.env, &t.renter);
+
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-    // end_time = 4600
+    client.set_bundle_tokens(&t.owner, &listing_id, &vec![&t.env, (foreign_collection, 1u64)]);
+}
 
-    set_timestamp(&t.env, 5000);
-    client.expire_rental(&rental_id);
+#[test]
+#[should_panic(expected = "owner does not hold nft")]
+fn test_rent_bundle_after_extra_token_moved_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_bundle_tokens(&t.owner, &listing_id, &vec![&t.env, (t.nft_contract.clone(), 2u64)]);
+
+    // The owner parts with the bundled token before anyone rents
+    MockNftClient::new(&t.env, &t.nft_contract).transfer(&2u64, &t.renter);
+    client.rent(&t.renter, &listing_id, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "duplicate token in bundle")]
+fn test_bundle_with_primary_token_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_bundle_tokens(&t.owner, &listing_id, &vec![&t.env, (t.nft_contract.clone(), 1u64)]);
+}
+
+// ============================================================
+// Unit Tests: Auto-Renewal
+// ============================================================
+
+#[test]
+fn test_auto_renew_pulls_payment_from_allowance() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    client.set_auto_renew(&t.renter, &rental_id, &true);
+    token_client.approve(&t.renter, &t.contract_id, &1000i128, &1000u32);
+
+    set_timestamp(&t.env, 7200);
+    assert!(client.process_rental_renewal(&rental_id));
 
+    // Renewed for another 2 periods, contiguous with the old end
     let rental = client.get_rental(&rental_id);
-    assert!(matches!(rental.status, RentalStatus::Expired));
+    assert!(matches!(rental.status, RentalStatus::Active));
+    assert_eq!(rental.end_time, 14_400);
+    assert_eq!(rental.periods, 4);
+    assert_eq!(rental.total_paid, 400);
+    assert_eq!(token_client.balance(&t.contract_id), 400);
+    assert_eq!(token_client.allowance(&t.renter, &t.contract_id), 800);
+    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
+    let event: (u64, i128, u64) = last_event(&t, "rental_renewed");
+    assert_eq!(event, (rental_id, 200, 14_400));
 }
 
 #[test]
-fn test_expire_rental_writes_history() {
+fn test_auto_renew_keeps_locked_in_price() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
 
     set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &200i128, &3600u64, &10u32, &true, &0u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    client.set_auto_renew(&t.renter, &rental_id, &true);
+    token_client.approve(&t.renter, &t.contract_id, &1000i128, &1000u32);
+    client.update_listing_price(&t.owner, &listing_id, &300i128);
+
+    set_timestamp(&t.env, 7200);
+    assert!(client.process_rental_renewal(&rental_id));
+
+    assert_eq!(client.get_rental(&rental_id).total_paid, 400);
+    assert_eq!(token_client.allowance(&t.renter, &t.contract_id), 800);
+}
+
+#[test]
+fn test_auto_renew_without_allowance_expires_rental() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    client.set_auto_renew(&t.renter, &rental_id, &true);
+    token_client.approve(&t.renter, &t.contract_id, &150i128, &1000u32);
+
+    set_timestamp(&t.env, 8000);
+    assert!(!client.process_rental_renewal(&rental_id));
+
+    assert!(matches!(client.get_rental(&rental_id).status, RentalStatus::Expired));
+    assert_eq!(token_client.balance(&t.owner), 200);
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 0);
+}
+
+#[test]
+fn test_auto_renew_stops_at_max_periods() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &3u32, &true, &0u32,
+    );
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    client.set_auto_renew(&t.renter, &rental_id, &true);
+    token_client.approve(&t.renter, &t.contract_id, &1000i128, &1000u32);
+
+    // A second 2-period term would exceed max_periods (3)
+    set_timestamp(&t.env, 7200);
+    assert!(!client.process_rental_renewal(&rental_id));
+    assert!(matches!(client.get_rental(&rental_id).status, RentalStatus::Expired));
+    assert_eq!(token_client.allowance(&t.renter, &t.contract_id), 1000);
+}
+
+#[test]
+#[should_panic(expected = "rental has not ended yet")]
+fn test_process_renewal_before_end_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
     let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.set_auto_renew(&t.renter, &rental_id, &true);
 
-    set_timestamp(&t.env, 4000);
-    client.expire_rental(&rental_id);
+    set_timestamp(&t.env, 1000);
+    client.process_rental_renewal(&rental_id);
+}
 
-    let history = client.get_rental_history(&rental_id);
-    assert_eq!(history.rental_id, rental_id);
-    assert_eq!(history.total_paid, 200);
-    assert!(matches!(history.final_status, RentalStatus::Expired));
+// ============================================================
+// Unit Tests: Multiple Payment Tokens
+// ============================================================
+
+fn create_alt_token(t: &TestSetup) -> Address {
+    let alt_admin = Address::generate(&t.env);
+    let alt_token = t.env.register_stellar_asset_contract_v2(alt_admin).address();
+    StellarAssetClient::new(&t.env, &alt_token).mint(&t.renter, &10_000);
+    alt_token
+}
+
+#[test]
+fn test_rent_with_alternative_token_locks_token_and_price() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let alt_token = create_alt_token(&t);
+    let alt_client = TokenClient::new(&t.env, &alt_token);
+
+    set_timestamp(&t.env, 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &100u32,
+    );
+    client.set_accepted_tokens(&t.owner, &listing_id, &vec![&t.env, (alt_token.clone(), 250i128)]);
+
+    let rental_id = client.rent_with_token(&t.renter, &listing_id, &2u32, &alt_token);
+    let rental = client.get_rental(&rental_id);
+    assert_eq!(rental.payment_token, alt_token);
+    assert_eq!(rental.price_per_period, 250);
+    assert_eq!(alt_client.balance(&t.contract_id), 500);
+
+    // Extensions are charged in the same token
+    client.extend_rental(&t.renter, &rental_id, &2u32);
+    assert_eq!(alt_client.balance(&t.contract_id), 1000);
+    assert_eq!(TokenClient::new(&t.env, &t.token_id).balance(&t.contract_id), 0);
+
+    // So are refunds: half of the 4 periods remain at t=7200
+    set_timestamp(&t.env, 7200);
+    client.terminate_rental(&t.renter, &rental_id);
+    assert_eq!(alt_client.balance(&t.renter), 10_000 - 1000 + 500);
+    assert_eq!(alt_client.balance(&t.owner), 500);
+}
+
+#[test]
+fn test_rent_with_primary_token_uses_listing_price() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let alt_token = create_alt_token(&t);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_accepted_tokens(&t.owner, &listing_id, &vec![&t.env, (alt_token.clone(), 250i128)]);
+
+    let rental_id = client.rent_with_token(&t.renter, &listing_id, &1u32, &t.token_id);
+    assert_eq!(client.get_rental(&rental_id).total_paid, 100);
+
+    // Price search matches the listing under either accepted token
+    assert_eq!(client.get_listings_by_price(&alt_token, &200i128, &300i128, &0u32, &10u32).listings.len(), 1);
+    assert_eq!(client.get_listings_by_price(&t.token_id, &200i128, &300i128, &0u32, &10u32).listings.len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "payment token not accepted")]
+fn test_rent_with_unoffered_token_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let alt_token = create_alt_token(&t);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.rent_with_token(&t.renter, &listing_id, &1u32, &alt_token);
+}
+
+#[test]
+#[should_panic(expected = "duplicate payment token")]
+fn test_accepting_primary_token_twice_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_accepted_tokens(&t.owner, &listing_id, &vec![&t.env, (t.token_id.clone(), 50i128)]);
+}
+
+// ============================================================
+// Unit Tests: Listing Tags & Metadata
+// ============================================================
+
+#[test]
+fn test_listings_by_tag_drops_cancelled_listing() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let weapon = Symbol::new(&t.env, "weapon");
+    let land = Symbol::new(&t.env, "land");
+
+    let mut sword = listing_input(&t, 1, 100);
+    sword.tags = vec![&t.env, weapon.clone()];
+    sword.metadata_uri = String::from_str(&t.env, "ipfs://sword");
+    let mut axe = listing_input(&t, 2, 100);
+    axe.tags = vec![&t.env, weapon.clone(), land.clone()];
+    let ids = client.create_listings_batch(&t.owner, &vec![&t.env, sword, axe]);
+    let (sword_id, axe_id) = (ids.get(0).unwrap(), ids.get(1).unwrap());
+
+    assert_eq!(
+        client.get_listing(&sword_id).metadata_uri,
+        String::from_str(&t.env, "ipfs://sword")
+    );
+    assert_eq!(client.get_listings_by_tag(&weapon, &0u64, &10u32).total, 2);
+    assert_eq!(client.get_listings_by_tag(&land, &0u64, &10u32).total, 1);
+
+    client.cancel_listing(&t.owner, &axe_id);
+    let page = client.get_listings_by_tag(&weapon, &0u64, &10u32);
+    assert_eq!(page.total, 1);
+    assert_eq!(page.listings.get(0).unwrap().listing_id, sword_id);
+    assert_eq!(client.get_listings_by_tag(&land, &0u64, &10u32).total, 0);
+}
+
+#[test]
+fn test_set_listing_metadata_reindexes_tags() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let weapon = Symbol::new(&t.env, "weapon");
+    let land = Symbol::new(&t.env, "land");
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    assert_eq!(client.get_listing(&listing_id).tags.len(), 0);
+
+    client.set_listing_metadata(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, weapon.clone()],
+        &String::from_str(&t.env, "ipfs://meta"),
+    );
+    assert_eq!(client.get_listings_by_tag(&weapon, &0u64, &10u32).total, 1);
+
+    client.set_listing_metadata(
+        &t.owner,
+        &listing_id,
+        &vec![&t.env, land.clone()],
+        &String::from_str(&t.env, "ipfs://meta"),
+    );
+    assert_eq!(client.get_listings_by_tag(&weapon, &0u64, &10u32).total, 0);
+    assert_eq!(client.get_listings_by_tag(&land, &0u64, &10u32).total, 1);
+}
+
+#[test]
+#[should_panic(expected = "too many tags")]
+fn test_listing_with_too_many_tags_panics() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let mut input = listing_input(&t, 1, 100);
+    input.tags = vec![
+        &t.env,
+        Symbol::new(&t.env, "a"),
+        Symbol::new(&t.env, "b"),
+        Symbol::new(&t.env, "c"),
+        Symbol::new(&t.env, "d"),
+        Symbol::new(&t.env, "e"),
+        Symbol::new(&t.env, "f"),
+    ];
+    client.create_listings_batch(&t.owner, &vec![&t.env, input]);
 }
 
+// ============================================================
+// Unit Tests: Failed Rentals
+// ============================================================
+
 #[test]
-#[should_panic(expected = "rental has not expired yet")]
-fn test_expire_rental_too_early_panics() {
+fn test_claim_failed_rental_after_owner_moves_nft() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let buyer = Address::generate(&t.env);
 
-    set_timestamp(&t.env, 1000);
+    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32);
+    assert_eq!(token_client.balance(&t.renter), 10_000 - 300 - 500);
 
-    set_timestamp(&t.env, 2000); // end_time = 4600, not yet
-    client.expire_rental(&rental_id);
+    // The owner sells the NFT mid-rental and can no longer deliver it
+    set_timestamp(&t.env, 1000);
+    MockNftClient::new(&t.env, &t.nft_contract).transfer(&1u64, &buyer);
+    client.claim_failed_rental(&t.renter, &rental_id);
+
+    // 9800 of 10800 paid seconds unused: 300 * 9800 / 10800 = 272 back, plus the deposit
+    assert_eq!(token_client.balance(&t.renter), 10_000 - 300 + 272);
+    assert_eq!(token_client.balance(&t.owner), 28);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+    let rental = client.get_rental(&rental_id);
+    assert!(matches!(rental.status, RentalStatus::Terminated));
+    assert!(matches!(rental.termination_reason, TerminationReason::CustodyLost));
+    assert!(matches!(rental.deposit_status, DepositStatus::Returned));
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 0);
+    let event: (u64, i128, u64) = last_event(&t, "rental_failed");
+    assert_eq!(event, (rental_id, 772, 1000));
 }
 
 #[test]
-#[should_panic(expected = "rental already closed")]
-fn test_expire_already_expired_rental_panics() {
+#[should_panic(expected = "rental period has ended")]
+fn test_claim_failed_rental_after_end_time_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let buyer = Address::generate(&t.env);
 
     set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
     let rental_id = client.rent(&t.renter, &listing_id, &1u32);
 
-    set_timestamp(&t.env, 4000);
-    client.expire_rental(&rental_id);
-    client.expire_rental(&rental_id); // second call should panic
+    // Once the rental is over the owner may legitimately move the NFT
+    set_timestamp(&t.env, 3600);
+    MockNftClient::new(&t.env, &t.nft_contract).transfer(&1u64, &buyer);
+    client.claim_failed_rental(&t.renter, &rental_id);
 }
 
-// ============================================================
-// Unit Tests: Early Termination & Refunds
-// ============================================================
-
 #[test]
-fn test_terminate_rental_with_full_refund() {
+#[should_panic(expected = "rental is disputed")]
+fn test_claim_failed_rental_while_disputed_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let buyer = Address::generate(&t.env);
 
     set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &1000i128, &10_000u64, &10u32, &true,
-        &100u32, // 100% refund
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
+    client.set_listing_deposit(&t.owner, &listing_id, &500i128);
+    let rental_id = client.rent(&t.renter, &listing_id, &3u32);
+    client.open_dispute(&t.owner, &rental_id, &Symbol::new(&t.env, "damage"));
 
-    let renter_before = token_client.balance(&t.renter);
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-    // paid = 1000, end_time = 10000; escrowed in contract
-
-    // Terminate at 5000 → 50% unused → refund = 500, owner gets 500
-    set_timestamp(&t.env, 5000);
-    client.terminate_rental(&t.renter, &rental_id);
-
-    assert_eq!(token_client.balance(&t.renter), renter_before - 500);
-    assert_eq!(token_client.balance(&t.owner), 500);
-    assert_eq!(token_client.balance(&t.contract_id), 0);
+    set_timestamp(&t.env, 1000);
+    MockNftClient::new(&t.env, &t.nft_contract).transfer(&1u64, &buyer);
+    client.claim_failed_rental(&t.renter, &rental_id);
 }
 
 #[test]
-fn test_terminate_rental_with_partial_refund() {
+#[should_panic(expected = "owner still holds nft")]
+fn test_claim_failed_rental_while_owner_holds_nft_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let token_client = TokenClient::new(&t.env, &t.token_id);
 
-    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &1000i128, &10_000u64, &10u32, &true,
-        &50u32, // 50% refund
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-
-    let renter_before = token_client.balance(&t.renter);
     let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-    // paid = 1000, end_time = 10000; escrowed in contract
-
-    // Terminate at 5000 → 50% unused → refund = 1000 * 0.5 * 50% = 250, owner gets 750
-    set_timestamp(&t.env, 5000);
-    client.terminate_rental(&t.renter, &rental_id);
-
-    assert_eq!(token_client.balance(&t.renter), renter_before - 750);
-    assert_eq!(token_client.balance(&t.owner), 750);
-    assert_eq!(token_client.balance(&t.contract_id), 0);
+    client.claim_failed_rental(&t.renter, &rental_id);
 }
 
+// ============================================================
+// Unit Tests: Listing Bonds
+// ============================================================
+
 #[test]
-fn test_terminate_rental_with_no_refund() {
+fn test_listing_bond_returned_on_cancel() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
     let token_client = TokenClient::new(&t.env, &t.token_id);
+    StellarAssetClient::new(&t.env, &t.token_id).mint(&t.owner, &1000);
 
-    set_timestamp(&t.env, 0);
-    let listing_id = client.create_listing(
-        &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &1000i128, &10_000u64, &10u32, &true,
-        &0u32, // 0% refund
+    client.set_listing_bond(&t.admin, &t.token_id, &300i128);
+    let ids = client.create_listings_batch(
+        &t.owner,
+        &vec![&t.env, listing_input(&t, 1, 100), listing_input(&t, 2, 100)],
+    );
+    assert_eq!(token_client.balance(&t.owner), 400);
+    assert_eq!(token_client.balance(&t.contract_id), 600);
+    assert_eq!(
+        client.get_listing_bond(&ids.get(0).unwrap()),
+        Some((t.token_id.clone(), 300i128))
     );
 
-    let renter_before = token_client.balance(&t.renter);
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-
-    set_timestamp(&t.env, 5000);
-    client.terminate_rental(&t.renter, &rental_id);
+    client.cancel_listing(&t.owner, &ids.get(0).unwrap());
+    assert_eq!(token_client.balance(&t.owner), 700);
+    assert_eq!(client.get_listing_bond(&ids.get(0).unwrap()), None);
 
-    // No refund; full amount goes to owner
-    assert_eq!(token_client.balance(&t.renter), renter_before - 1000);
-    assert_eq!(token_client.balance(&t.owner), 1000);
-    assert_eq!(token_client.balance(&t.contract_id), 0);
+    // Cancelling again does not pay the bond twice
+    client.cancel_listing(&t.owner, &ids.get(0).unwrap());
+    assert_eq!(token_client.balance(&t.owner), 700);
 }
 
 #[test]
-fn test_terminate_rental_sets_terminated_status() {
+fn test_listing_bond_forfeited_on_admin_removal() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let treasury = Address::generate(&t.env);
+    StellarAssetClient::new(&t.env, &t.token_id).mint(&t.owner, &1000);
 
-    set_timestamp(&t.env, 0);
+    client.set_fee(&t.admin, &0u32, &treasury);
+    client.set_listing_bond(&t.admin, &t.token_id, &300i128);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-
-    set_timestamp(&t.env, 1000);
-    client.terminate_rental(&t.renter, &rental_id);
 
-    let rental = client.get_rental(&rental_id);
-    assert!(matches!(rental.status, RentalStatus::Terminated));
+    client.admin_remove_listing(&t.admin, &listing_id, &Symbol::new(&t.env, "fraud"));
+    assert_eq!(token_client.balance(&treasury), 300);
+    assert_eq!(token_client.balance(&t.owner), 700);
+    assert!(matches!(client.get_listing(&listing_id).status, ListingStatus::Cancelled));
+    assert_eq!(client.get_active_listings(&0u64, &10u32).total, 0);
 }
 
 #[test]
-fn test_terminate_rental_writes_history() {
+fn test_admin_removal_refunds_in_flight_renters() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let token_client = TokenClient::new(&t.env, &t.token_id);
+    let token_sac = StellarAssetClient::new(&t.env, &t.token_id);
+    let renter2 = Address::generate(&t.env);
+    token_sac.mint(&renter2, &5000);
 
     set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &500i128, &10_000u64, &10u32, &true, &0u32,
+        &1000i128, &10_000u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
+    client.set_listing_deposit(&t.owner, &listing_id, &200i128);
+    let running = client.rent(&t.renter, &listing_id, &2u32); // 2000, ends 20000
+    let scheduled = client.rent_scheduled(&renter2, &listing_id, &1u32, &30_000u64); // 1000
 
+    // A quarter of the running rental has been used when the admin steps in
     set_timestamp(&t.env, 5000);
-    client.terminate_rental(&t.renter, &rental_id);
+    let reason = Symbol::new(&t.env, "stolen_nft");
+    client.admin_remove_listing(&t.admin, &listing_id, &reason);
 
-    let history = client.get_rental_history(&rental_id);
-    assert_eq!(history.rental_id, rental_id);
-    assert_eq!(history.total_paid, 500);
-    assert!(matches!(history.final_status, RentalStatus::Terminated));
+    assert_eq!(token_client.balance(&t.renter), 10_000 - 500);
+    assert_eq!(token_client.balance(&renter2), 5000);
+    assert_eq!(token_client.balance(&t.owner), 500);
+    assert_eq!(token_client.balance(&t.contract_id), 0);
+
+    for rental_id in [running, scheduled] {
+        let rental = client.get_rental(&rental_id);
+        assert!(matches!(rental.status, RentalStatus::Terminated));
+        assert!(matches!(rental.termination_reason, TerminationReason::ListingRemoved));
+    }
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 0);
+
+    let event: (u64, Symbol, u64) = last_event(&t, "listing_removed");
+    assert_eq!(event, (listing_id, reason, 5000));
 }
 
 #[test]
-#[should_panic(expected = "not the renter")]
-fn test_terminate_rental_wrong_caller_panics() {
+fn test_zero_bond_leaves_listing_creation_free() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-    client.terminate_rental(&t.owner, &rental_id);
+    assert_eq!(client.get_config().listing_bond, 0);
+    assert_eq!(client.get_listing_bond(&listing_id), None);
 }
 
-#[test]
-#[should_panic(expected = "rental is not active")]
-fn test_terminate_already_terminated_panics() {
-    let t = setup();
-    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+// ============================================================
+// Unit Tests: Availability Window
+// ============================================================
 
-    set_timestamp(&t.env, 0);
+fn windowed_listing(t: &TestSetup, client: &PuzzleRentalContractClient) -> u64 {
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
-
-    set_timestamp(&t.env, 1000);
-    client.terminate_rental(&t.renter, &rental_id);
-    client.terminate_rental(&t.renter, &rental_id); // second call should panic
+    // Rentable only between t=10000 and t=20000
+    client.set_availability(&t.owner, &listing_id, &10_000u64, &20_000u64);
+    listing_id
 }
 
-// ============================================================
-// Unit Tests: Marketplace Discovery
-// ============================================================
-
 #[test]
-fn test_marketplace_pagination_first_page() {
+#[should_panic(expected = "outside availability window")]
+fn test_rent_before_availability_window_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let listing_id = windowed_listing(&t, &client);
 
-    for i in 0..5u64 {
-        client.create_listing(
-            &t.owner, &t.nft_contract, &i, &t.token_id,
-            &100i128, &3600u64, &10u32, &true, &50u32,
-        );
-    }
-
-    let page = client.get_active_listings(&0u64, &3u32);
-    assert_eq!(page.listings.len(), 3);
-    assert_eq!(page.total, 5);
+    set_timestamp(&t.env, 5000);
+    client.rent(&t.renter, &listing_id, &1u32);
 }
 
 #[test]
-fn test_marketplace_pagination_second_page() {
+fn test_rent_during_availability_window() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let listing_id = windowed_listing(&t, &client);
 
-    for i in 0..5u64 {
-        client.create_listing(
-            &t.owner, &t.nft_contract, &i, &t.token_id,
-            &100i128, &3600u64, &10u32, &true, &50u32,
-        );
-    }
+    let listing = client.get_listing(&listing_id);
+    assert_eq!((listing.available_from, listing.available_until), (10_000, 20_000));
 
-    let page = client.get_active_listings(&3u64, &3u32);
-    assert_eq!(page.listings.len(), 2);
-    assert_eq!(page.total, 5);
+    set_timestamp(&t.env, 12_000);
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    assert_eq!(client.get_rental(&rental_id).end_time, 19_200);
+    assert!(client.has_access(&t.renter, &t.nft_contract, &1u64));
 }
 
 #[test]
-fn test_marketplace_empty_when_no_listings() {
+#[should_panic(expected = "rental exceeds availability window")]
+fn test_rent_extending_past_availability_window_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let listing_id = windowed_listing(&t, &client);
 
-    let page = client.get_active_listings(&0u64, &10u32);
-    assert_eq!(page.listings.len(), 0);
-    assert_eq!(page.total, 0);
+    // 3 periods from t=12000 would end at 22800, past the window
+    set_timestamp(&t.env, 12_000);
+    client.rent(&t.renter, &listing_id, &3u32);
 }
 
 #[test]
-fn test_marketplace_excludes_cancelled_listings() {
+#[should_panic(expected = "rental exceeds availability window")]
+fn test_extend_past_availability_window_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    let listing_id = windowed_listing(&t, &client);
 
-    let id1 = client.create_listing(
-        &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &0u32,
-    );
-    client.create_listing(
-        &t.owner, &t.nft_contract, &2u64, &t.token_id,
-        &100i128, &3600u64, &10u32, &true, &0u32,
-    );
-
-    client.cancel_listing(&t.owner, &id1);
-
-    let page = client.get_active_listings(&0u64, &10u32);
-    // id1 was removed from the index on cancellation
-    assert_eq!(page.total, 1);
+    set_timestamp(&t.env, 12_000);
+    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+    client.extend_rental(&t.renter, &rental_id, &1u32);
 }
 
 // ============================================================
-// Unit Tests: History Tracking
+// Unit Tests: Dynamic Pricing
 // ============================================================
 
 #[test]
-fn test_rental_history_stored_after_expiry() {
+fn test_dynamic_price_grows_over_three_rentals() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    set_timestamp(&t.env, 1000);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
-        &200i128, &3600u64, &10u32, &true, &0u32,
+        &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &2u32);
-
-    set_timestamp(&t.env, 10_000);
-    client.expire_rental(&rental_id);
-
-    let history = client.get_rental_history(&rental_id);
-    assert_eq!(history.rental_id, rental_id);
-    assert_eq!(history.listing_id, listing_id);
-    assert_eq!(history.owner, t.owner);
-    assert_eq!(history.renter, t.renter);
-    assert_eq!(history.total_paid, 400);
-    assert_eq!(history.start_time, 1000);
-    assert_eq!(history.end_time, 1000 + 3600 * 2);
-    assert!(matches!(history.final_status, RentalStatus::Expired));
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &3u32);
+    client.set_dynamic_pricing(&t.owner, &listing_id, &true);
+
+    // Default step is 5% per past rental
+    let prices: [i128; 3] = [100, 105, 110];
+    for expected in prices {
+        let rental_id = client.rent(&t.renter, &listing_id, &2u32);
+        let rental = client.get_rental(&rental_id);
+        assert_eq!(rental.price_per_period, expected);
+        assert_eq!(rental.total_paid, expected * 2);
+    }
+    assert_eq!(client.get_demand_counter(&listing_id), 3);
+    assert_eq!(client.get_listing(&listing_id).price_per_period, 100);
 }
 
 #[test]
-#[should_panic(expected = "rental history not found")]
-fn test_get_rental_history_before_close_panics() {
+fn test_dynamic_price_is_capped() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+    client.set_demand_pricing(&t.admin, &5000u32, &15_000u32);
+    let event: (u32, u32) = last_event(&t, "demand_pricing_updated");
+    assert_eq!(event, (5000, 15_000));
 
-    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &3u32);
+    client.set_dynamic_pricing(&t.owner, &listing_id, &true);
 
-    // Rental is still active; history doesn't exist yet
-    client.get_rental_history(&rental_id);
+    let prices: [i128; 3] = [100, 150, 150];
+    for expected in prices {
+        let rental_id = client.rent(&t.renter, &listing_id, &1u32);
+        assert_eq!(client.get_rental(&rental_id).price_per_period, expected);
+    }
 }
 
-// ============================================================
-// Unit Tests: Owner & Renter Index Queries
-// ============================================================
-
 #[test]
-fn test_get_owner_listings_returns_all() {
+fn test_static_listing_ignores_demand() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
-    let id1 = client.create_listing(
+    let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
-    let id2 = client.create_listing(
-        &t.owner, &t.nft_contract, &2u64, &t.token_id,
-        &200i128, &7200u64, &5u32, &false, &0u32,
-    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
+    client.rent(&t.renter, &listing_id, &1u32);
+    let second = client.rent(&t.renter, &listing_id, &1u32);
 
-    let listings = client.get_owner_listings(&t.owner);
-    assert_eq!(listings.len(), 2);
-    assert_eq!(listings.get(0).unwrap(), id1);
-    assert_eq!(listings.get(1).unwrap(), id2);
+    assert_eq!(client.get_demand_counter(&listing_id), 2);
+    assert_eq!(client.get_rental(&second).price_per_period, 100);
 }
 
 #[test]
-fn test_get_owner_listings_empty_for_unknown_owner() {
+#[should_panic(expected = "cap below base price")]
+fn test_demand_cap_below_base_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let unknown = Address::generate(&t.env);
+    client.set_demand_pricing(&t.admin, &500u32, &9000u32);
+}
 
-    let listings = client.get_owner_listings(&unknown);
-    assert_eq!(listings.len(), 0);
+// ============================================================
+// Unit Tests: Concurrent Rental Capacity
+// ============================================================
+
+#[test]
+#[should_panic(expected = "listing at capacity")]
+fn test_exclusive_listing_rejects_second_rental() {
+    let t = setup();
+    let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
+
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    assert_eq!(client.get_listing(&listing_id).max_concurrent_rentals, 1);
+    client.rent(&t.renter, &listing_id, &1u32);
+    client.rent(&t.renter, &listing_id, &1u32);
 }
 
 #[test]
-fn test_get_renter_rentals_returns_all() {
+fn test_expired_rental_frees_capacity_slot() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
 
+    set_timestamp(&t.env, 0);
     let listing_id = client.create_listing(
         &t.owner, &t.nft_contract, &1u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
 
-    let r1 = client.rent(&t.renter, &listing_id, &1u32);
-    let r2 = client.rent(&t.renter, &listing_id, &1u32);
+    let short = client.rent(&t.renter, &listing_id, &1u32);
+    let long = client.rent(&t.renter, &listing_id, &5u32);
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 2);
 
-    let rentals = client.get_renter_rentals(&t.renter);
-    assert_eq!(rentals.len(), 2);
-    assert_eq!(rentals.get(0).unwrap(), r1);
-    assert_eq!(rentals.get(1).unwrap(), r2);
+    set_timestamp(&t.env, 4000);
+    client.expire_rental(&short);
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 1);
+
+    // The freed seat can be taken again, filling the listing back up
+    client.rent(&t.renter, &listing_id, &1u32);
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 2);
+
+    client.terminate_rental(&t.renter, &long);
+    assert_eq!(client.get_listing_active_rentals(&listing_id), 1);
 }
 
 #[test]
-fn test_get_renter_rentals_empty_for_unknown_renter() {
+#[should_panic(expected = "max concurrent rentals must be > 0")]
+fn test_zero_concurrent_rentals_panics() {
     let t = setup();
     let client = PuzzleRentalContractClient::new(&t.env, &t.contract_id);
-    let unknown = Address::generate(&t.env);
 
-    let rentals = client.get_renter_rentals(&unknown);
-    assert_eq!(rentals.len(), 0);
+    let listing_id = client.create_listing(
+        &t.owner, &t.nft_contract, &1u64, &t.token_id,
+        &100i128, &3600u64, &10u32, &true, &0u32,
+    );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &0u32);
 }
 
 // ============================================================
@@ -1118,6 +4320,7 @@ fn test_multiple_renters_independent_access_windows() {
         &t.owner, &t.nft_contract, &7u64, &t.token_id,
         &100i128, &3600u64, &10u32, &true, &0u32,
     );
+    client.set_max_concurrent_rentals(&t.owner, &listing_id, &2u32);
 
     // renter1 rents 1 period (ends at 3600), renter2 rents 2 periods (ends at 7200)
     let r1 = client.rent(&t.renter, &listing_id, &1u32);
@@ -1,6 +1,8 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, Address, Env, Map, Symbol, Vec,
+};
 
 //
 // ──────────────────────────────────────────────────────────
@@ -8,11 +10,33 @@ use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec
 // ──────────────────────────────────────────────────────────
 //
 
+// Defaults for the configurable period and grace lengths
 #[cfg(not(test))]
 const MONTH_IN_SECONDS: u64 = 2_592_000; // 30 days
 #[cfg(test)]
 const MONTH_IN_SECONDS: u64 = 10;
 
+#[cfg(not(test))]
+const YEAR_IN_SECONDS: u64 = 31_536_000; // 365 days
+#[cfg(test)]
+const YEAR_IN_SECONDS: u64 = 120;
+
+#[cfg(not(test))]
+const TRIAL_PERIOD_SECONDS: u64 = 604_800; // 7 days
+#[cfg(test)]
+const TRIAL_PERIOD_SECONDS: u64 = 5;
+
+#[cfg(not(test))]
+const REACTIVATION_WINDOW_SECONDS: u64 = 2_592_000; // 30 days after grace
+#[cfg(test)]
+const REACTIVATION_WINDOW_SECONDS: u64 = 20;
+
+// Maximum recipients per bulk gift
+const MAX_GIFT_BATCH: u32 = 20;
+
+// Expiry of lifetime subscriptions
+const LIFETIME_EXPIRY: u64 = u64::MAX;
+
 #[cfg(not(test))]
 const GRACE_PERIOD_SECONDS: u64 = 259_200; // 3 days
 #[cfg(test)]
@@ -32,6 +56,45 @@ pub enum SubscriptionTier {
     Enterprise = 3,
 }
 
+/// An optional tier stored inside a contract type. Stands in for
+/// `Option<SubscriptionTier>`, which the SDK cannot convert as a struct field.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OptionalTier {
+    None,
+    Some(SubscriptionTier),
+}
+
+impl OptionalTier {
+    pub fn is_none(&self) -> bool {
+        *self == OptionalTier::None
+    }
+
+    /// Takes the tier out, leaving `None` in its place
+    pub fn take(&mut self) -> Option<SubscriptionTier> {
+        match core::mem::replace(self, OptionalTier::None) {
+            OptionalTier::Some(tier) => Some(tier),
+            OptionalTier::None => None,
+        }
+    }
+}
+
+impl From<Option<SubscriptionTier>> for OptionalTier {
+    fn from(tier: Option<SubscriptionTier>) -> Self {
+        match tier {
+            Some(tier) => OptionalTier::Some(tier),
+            None => OptionalTier::None,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BillingCycle {
+    Monthly,
+    Annual,
+}
+
 //
 // ──────────────────────────────────────────────────────────
 // DATA KEYS
@@ -48,6 +111,14 @@ pub enum DataKey {
     UserGroup(Address),         // user -> group_id
     TotalSubscribers,
     TierPrice(SubscriptionTier),
+    BenefitLimit(SubscriptionTier, Symbol), // (tier, benefit type) -> limit
+    TotalRevenue,
+    TierRevenue(SubscriptionTier),
+    TrialUsed(Address),
+    Promo(Symbol),
+    GroupManagers(u64), // group_id -> Map<Address, bool>
+    PendingGift(u64),   // gift_id -> PendingGift
+    NextGiftId,
 }
 
 //
@@ -65,6 +136,15 @@ pub struct Config {
     pub premium_price: i128,
     pub enterprise_price: i128,
     pub paused: bool,
+    pub annual_discount_bps: u32, // share of 12 monthly payments charged for a year
+    pub trial_seconds: u64,
+    pub basic_lifetime_price: i128, // 0 = lifetime not offered
+    pub premium_lifetime_price: i128,
+    pub enterprise_lifetime_price: i128,
+    pub reactivation_window_seconds: u64, // counted from the end of the grace period
+    pub period_seconds: u64, // length of a monthly billing period
+    pub annual_period_seconds: u64, // length of an annual billing period
+    pub grace_seconds: u64,
 }
 
 #[contracttype]
@@ -79,6 +159,14 @@ pub struct Subscription {
     pub benefits_used: u32,
     pub is_gifted: bool,
     pub gifted_by: Option<Address>,
+    pub pending_tier: OptionalTier, // applied at the next cycle
+    pub billing_cycle: BillingCycle,
+    pub transferred_from: Option<Address>,
+    pub benefit_usage: Map<Symbol, u32>, // benefit type -> uses
+    pub is_trial: bool,
+    pub is_frozen: bool,
+    pub frozen_at: u64,
+    pub frozen_remaining: u64, // seconds left in the cycle when frozen
 }
 
 #[contracttype]
@@ -94,6 +182,49 @@ pub struct GroupSubscription {
     pub total_renewals: u32,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupMemberStatus {
+    pub member: Address,
+    pub is_manager: bool,
+    pub is_valid: bool,     // has access through any subscription
+    pub tier: OptionalTier, // effective tier, None without access
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupDetails {
+    pub group_id: u64,
+    pub owner: Address,
+    pub tier: SubscriptionTier,
+    pub start_time: u64,
+    pub expiry_time: u64,
+    pub auto_renew: bool,
+    pub is_active: bool,
+    pub is_valid: bool,
+    pub max_members: u32,
+    pub total_renewals: u32,
+    pub members: Vec<GroupMemberStatus>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PendingGift {
+    pub gifter: Address,
+    pub recipient: Address,
+    pub tier: SubscriptionTier,
+    pub amount: i128, // escrowed payment
+    pub deliver_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Promo {
+    pub discount_bps: u32,
+    pub remaining_uses: u32,
+    pub expires_at: u64,
+}
+
 //
 // ──────────────────────────────────────────────────────────
 // CONTRACT
@@ -108,6 +239,7 @@ impl SubscriptionContract {
     // ───────────── INITIALIZATION ─────────────
 
     /// Initialize the subscription contract
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         env: Env,
         admin: Address,
@@ -115,6 +247,9 @@ impl SubscriptionContract {
         basic_price: i128,
         premium_price: i128,
         enterprise_price: i128,
+        period_seconds: Option<u64>,
+        annual_period_seconds: Option<u64>,
+        grace_seconds: Option<u64>,
     ) {
         admin.require_auth();
 
@@ -122,6 +257,12 @@ impl SubscriptionContract {
             panic!("Already initialized");
         }
 
+        let period_seconds = period_seconds.unwrap_or(MONTH_IN_SECONDS);
+        let annual_period_seconds = annual_period_seconds.unwrap_or(YEAR_IN_SECONDS);
+        if period_seconds == 0 || annual_period_seconds == 0 {
+            panic!("Period must be positive");
+        }
+
         let config = Config {
             admin,
             payment_token,
@@ -129,6 +270,15 @@ impl SubscriptionContract {
             premium_price,
             enterprise_price,
             paused: false,
+            annual_discount_bps: 10_000,
+            trial_seconds: TRIAL_PERIOD_SECONDS,
+            basic_lifetime_price: 0,
+            premium_lifetime_price: 0,
+            enterprise_lifetime_price: 0,
+            reactivation_window_seconds: REACTIVATION_WINDOW_SECONDS,
+            period_seconds,
+            annual_period_seconds,
+            grace_seconds: grace_seconds.unwrap_or(GRACE_PERIOD_SECONDS),
         };
 
         env.storage().persistent().set(&DataKey::Config, &config);
@@ -156,6 +306,104 @@ impl SubscriptionContract {
         env.storage().persistent().set(&DataKey::Config, &config);
     }
 
+    /// Set the annual billing discount in basis points of 12 monthly payments (admin only)
+    pub fn set_annual_discount(env: Env, admin: Address, annual_discount_bps: u32) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        if annual_discount_bps == 0 || annual_discount_bps > 10_000 {
+            panic!("Invalid annual discount");
+        }
+
+        let mut config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        config.annual_discount_bps = annual_discount_bps;
+        env.storage().persistent().set(&DataKey::Config, &config);
+    }
+
+    /// Set the usage limit of one benefit type for a tier (admin only)
+    pub fn set_benefit_limit(
+        env: Env,
+        admin: Address,
+        tier: SubscriptionTier,
+        benefit_type: Symbol,
+        limit: u32,
+    ) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        env.storage().persistent().set(&DataKey::BenefitLimit(tier, benefit_type), &limit);
+    }
+
+    /// Set the free trial length (admin only)
+    pub fn set_trial_seconds(env: Env, admin: Address, trial_seconds: u64) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        let mut config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        config.trial_seconds = trial_seconds;
+        env.storage().persistent().set(&DataKey::Config, &config);
+    }
+
+    /// Create or replace a promo code (admin only)
+    pub fn create_promo(
+        env: Env,
+        admin: Address,
+        code: Symbol,
+        discount_bps: u32,
+        max_uses: u32,
+        expires_at: u64,
+    ) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        if discount_bps == 0 || discount_bps > 10_000 {
+            panic!("Invalid discount");
+        }
+
+        if max_uses == 0 {
+            panic!("Max uses must be positive");
+        }
+
+        if expires_at <= env.ledger().timestamp() {
+            panic!("Expiry must be in the future");
+        }
+
+        let promo = Promo {
+            discount_bps,
+            remaining_uses: max_uses,
+            expires_at,
+        };
+        env.storage().persistent().set(&DataKey::Promo(code), &promo);
+    }
+
+    /// Update lifetime pricing (admin only), 0 disables lifetime for a tier
+    pub fn update_lifetime_pricing(
+        env: Env,
+        admin: Address,
+        basic_lifetime_price: i128,
+        premium_lifetime_price: i128,
+        enterprise_lifetime_price: i128,
+    ) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        let mut config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        config.basic_lifetime_price = basic_lifetime_price;
+        config.premium_lifetime_price = premium_lifetime_price;
+        config.enterprise_lifetime_price = enterprise_lifetime_price;
+        env.storage().persistent().set(&DataKey::Config, &config);
+    }
+
+    /// Set how long after the grace period a lapsed subscription can be reactivated (admin only)
+    pub fn set_reactivation_window(env: Env, admin: Address, reactivation_window_seconds: u64) {
+        admin.require_auth();
+        Self::assert_admin(&env, &admin);
+
+        let mut config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        config.reactivation_window_seconds = reactivation_window_seconds;
+        env.storage().persistent().set(&DataKey::Config, &config);
+    }
+
     /// Pause/unpause the contract (admin only)
     pub fn set_paused(env: Env, admin: Address, paused: bool) {
         admin.require_auth();
@@ -184,6 +432,8 @@ impl SubscriptionContract {
         user: Address,
         tier: SubscriptionTier,
         auto_renew: bool,
+        billing_cycle: BillingCycle,
+        promo_code: Option<Symbol>,
     ) {
         user.require_auth();
         Self::assert_not_paused(&env);
@@ -193,34 +443,148 @@ impl SubscriptionContract {
             panic!("Already has active subscription");
         }
 
+        Self::assert_not_frozen(&env, &user);
+
         let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
-        let price = Self::get_tier_price(&tier, &config);
+        let mut price = Self::get_cycle_price(&tier, &billing_cycle, &config);
+
+        if let Some(code) = promo_code {
+            price = Self::redeem_promo(&env, code, price);
+        }
         
         // Transfer payment
         let token_client = token::Client::new(&env, &config.payment_token);
         token_client.transfer(&user, &env.current_contract_address(), &price);
+        Self::record_revenue(&env, &tier, price);
 
         // Create subscription
         let current_time = env.ledger().timestamp();
         let subscription = Subscription {
             tier,
             start_time: current_time,
-            expiry_time: current_time + MONTH_IN_SECONDS,
+            expiry_time: current_time + Self::cycle_seconds(&billing_cycle, &config),
             auto_renew,
             is_active: true,
             total_renewals: 0,
             benefits_used: 0,
             is_gifted: false,
             gifted_by: None,
+            pending_tier: OptionalTier::None,
+            billing_cycle,
+            transferred_from: None,
+            benefit_usage: Map::new(&env),
+            is_trial: false,
+            is_frozen: false,
+            frozen_at: 0,
+            frozen_remaining: 0,
         };
 
         env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish(
+            (symbol_short!("purchase"), user.clone()),
+            (subscription.tier, subscription.expiry_time),
+        );
 
         // Update total subscribers
         let total: u64 = env.storage().persistent().get(&DataKey::TotalSubscribers).unwrap_or(0);
         env.storage().persistent().set(&DataKey::TotalSubscribers, &(total + 1));
     }
 
+    /// Purchase a subscription that never expires
+    pub fn purchase_lifetime(env: Env, user: Address, tier: SubscriptionTier) {
+        user.require_auth();
+        Self::assert_not_paused(&env);
+
+        if Self::has_active_subscription(env.clone(), user.clone()) {
+            panic!("Already has active subscription");
+        }
+
+        Self::assert_not_frozen(&env, &user);
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let price = Self::get_lifetime_price(&tier, &config);
+
+        let token_client = token::Client::new(&env, &config.payment_token);
+        token_client.transfer(&user, &env.current_contract_address(), &price);
+        Self::record_revenue(&env, &tier, price);
+
+        let current_time = env.ledger().timestamp();
+        let subscription = Subscription {
+            tier,
+            start_time: current_time,
+            expiry_time: LIFETIME_EXPIRY,
+            auto_renew: false,
+            is_active: true,
+            total_renewals: 0,
+            benefits_used: 0,
+            is_gifted: false,
+            gifted_by: None,
+            pending_tier: OptionalTier::None,
+            billing_cycle: BillingCycle::Monthly,
+            transferred_from: None,
+            benefit_usage: Map::new(&env),
+            is_trial: false,
+            is_frozen: false,
+            frozen_at: 0,
+            frozen_remaining: 0,
+        };
+
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish(
+            (symbol_short!("purchase"), user.clone()),
+            (subscription.tier, subscription.expiry_time),
+        );
+
+        let total: u64 = env.storage().persistent().get(&DataKey::TotalSubscribers).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::TotalSubscribers, &(total + 1));
+    }
+
+    /// Start a free trial (once per address, whatever the tier). The trial
+    /// converts to a paid monthly subscription at its first renewal, which a
+    /// keeper can charge if the user has approved an allowance.
+    pub fn start_trial(env: Env, user: Address, tier: SubscriptionTier) {
+        user.require_auth();
+        Self::assert_not_paused(&env);
+
+        if env.storage().persistent().has(&DataKey::TrialUsed(user.clone())) {
+            panic!("Trial already used");
+        }
+
+        if Self::has_active_subscription(env.clone(), user.clone()) {
+            panic!("Already has active subscription");
+        }
+
+        Self::assert_not_frozen(&env, &user);
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let current_time = env.ledger().timestamp();
+        let subscription = Subscription {
+            tier,
+            start_time: current_time,
+            expiry_time: current_time + config.trial_seconds,
+            auto_renew: true,
+            is_active: true,
+            total_renewals: 0,
+            benefits_used: 0,
+            is_gifted: false,
+            gifted_by: None,
+            pending_tier: OptionalTier::None,
+            billing_cycle: BillingCycle::Monthly,
+            transferred_from: None,
+            benefit_usage: Map::new(&env),
+            is_trial: true,
+            is_frozen: false,
+            frozen_at: 0,
+            frozen_remaining: 0,
+        };
+
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.storage().persistent().set(&DataKey::TrialUsed(user.clone()), &true);
+
+        let total: u64 = env.storage().persistent().get(&DataKey::TotalSubscribers).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::TotalSubscribers, &(total + 1));
+    }
+
     /// Auto-renew subscription (requires user authorization)
     pub fn process_renewal(env: Env, user: Address) {
         user.require_auth();
@@ -239,6 +603,10 @@ impl SubscriptionContract {
             panic!("Subscription not active");
         }
 
+        if subscription.is_frozen {
+            panic!("Subscription is frozen");
+        }
+
         let current_time = env.ledger().timestamp();
         
         // Check if within renewal window (expired but within grace period)
@@ -246,103 +614,465 @@ impl SubscriptionContract {
             panic!("Not yet time to renew");
         }
 
-        if current_time > subscription.expiry_time + GRACE_PERIOD_SECONDS {
+        if current_time > subscription.expiry_time.saturating_add(config.grace_seconds) {
             // Beyond grace period, deactivate subscription
             subscription.is_active = false;
             env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
             panic!("Subscription expired beyond grace period");
         }
 
+        // A scheduled downgrade takes effect with the new cycle
+        if let Some(pending_tier) = subscription.pending_tier.take() {
+            subscription.tier = pending_tier;
+        }
+
         // Process payment
-        let price = Self::get_tier_price(&subscription.tier, &config);
+        let price = Self::get_cycle_price(&subscription.tier, &subscription.billing_cycle, &config);
         let token_client = token::Client::new(&env, &config.payment_token);
         token_client.transfer(&user, &env.current_contract_address(), &price);
+        Self::record_revenue(&env, &subscription.tier, price);
 
         // Renew subscription
-        subscription.expiry_time = current_time + MONTH_IN_SECONDS;
+        subscription.expiry_time = current_time + Self::cycle_seconds(&subscription.billing_cycle, &config);
         subscription.total_renewals += 1;
+        subscription.is_trial = false;
         env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish((symbol_short!("renewed"), user), subscription.expiry_time);
     }
 
-    // ───────────── SUBSCRIPTION MANAGEMENT ─────────────
-
-    /// Cancel subscription (stops auto-renewal, keeps benefits until expiry)
-    pub fn cancel_subscription(env: Env, user: Address) {
-        user.require_auth();
+    /// Renew on the user's behalf using a token allowance granted to the
+    /// contract, so any keeper can trigger it. Returns false when the renewal
+    /// cannot be charged; during the grace period the subscription is left
+    /// untouched so the user can still renew, after it the subscription is
+    /// deactivated.
+    pub fn process_renewal_keeper(env: Env, user: Address) -> bool {
+        Self::assert_not_paused(&env);
 
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
         let mut subscription: Subscription = env.storage().persistent()
             .get(&DataKey::Subscription(user.clone()))
             .expect("No subscription found");
 
-        subscription.auto_renew = false;
-        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
-    }
+        if !subscription.auto_renew {
+            panic!("Auto-renew not enabled");
+        }
 
-    /// Toggle auto-renewal
-    pub fn set_auto_renew(env: Env, user: Address, auto_renew: bool) {
-        user.require_auth();
+        if !subscription.is_active {
+            panic!("Subscription not active");
+        }
 
-        let mut subscription: Subscription = env.storage().persistent()
-            .get(&DataKey::Subscription(user.clone()))
-            .expect("No subscription found");
+        if subscription.is_frozen {
+            panic!("Subscription is frozen");
+        }
 
-        subscription.auto_renew = auto_renew;
+        let current_time = env.ledger().timestamp();
+
+        if current_time < subscription.expiry_time {
+            panic!("Not yet time to renew");
+        }
+
+        // A scheduled downgrade takes effect with the new cycle
+        let renewal_tier = match subscription.pending_tier {
+            OptionalTier::Some(pending_tier) => pending_tier,
+            OptionalTier::None => subscription.tier,
+        };
+
+        let price = Self::get_cycle_price(&renewal_tier, &subscription.billing_cycle, &config);
+        let token_client = token::Client::new(&env, &config.payment_token);
+        let contract_address = env.current_contract_address();
+
+        let in_grace = current_time <= subscription.expiry_time.saturating_add(config.grace_seconds);
+        let chargeable = in_grace
+            && token_client.allowance(&user, &contract_address) >= price
+            && token_client.balance(&user) >= price;
+
+        if !chargeable {
+            // The user can still renew themselves until grace runs out
+            if in_grace {
+                return false;
+            }
+
+            subscription.is_active = false;
+            subscription.auto_renew = false;
+            env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+            env.events().publish((symbol_short!("expired"), user), subscription.expiry_time);
+            return false;
+        }
+
+        token_client.transfer_from(&contract_address, &user, &contract_address, &price);
+        subscription.pending_tier = OptionalTier::None;
+        subscription.tier = renewal_tier;
+        Self::record_revenue(&env, &subscription.tier, price);
+
+        subscription.expiry_time = current_time + Self::cycle_seconds(&subscription.billing_cycle, &config);
+        subscription.total_renewals += 1;
+        subscription.is_trial = false;
         env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish((symbol_short!("renewed"), user), subscription.expiry_time);
+        true
     }
 
-    /// Upgrade subscription tier
-    pub fn upgrade_subscription(env: Env, user: Address, new_tier: SubscriptionTier) {
+    /// Reactivate a lapsed subscription, keeping its renewal and benefit history
+    pub fn reactivate(env: Env, user: Address) {
         user.require_auth();
         Self::assert_not_paused(&env);
 
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
         let mut subscription: Subscription = env.storage().persistent()
             .get(&DataKey::Subscription(user.clone()))
             .expect("No subscription found");
 
-        if !subscription.is_active {
-            panic!("Subscription not active");
+        if Self::is_subscription_valid(&env, &subscription) {
+            panic!("Subscription still active");
         }
 
-        let old_tier_value = subscription.tier as u32;
-        let new_tier_value = new_tier as u32;
+        if subscription.is_frozen {
+            panic!("Subscription is frozen");
+        }
 
-        if new_tier_value <= old_tier_value {
-            panic!("Can only upgrade to higher tier");
+        if Self::has_active_subscription(env.clone(), user.clone()) {
+            panic!("Already has active subscription");
         }
 
-        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
-        let old_price = Self::get_tier_price(&subscription.tier, &config);
-        let new_price = Self::get_tier_price(&new_tier, &config);
-        let price_diff = new_price - old_price;
+        let current_time = env.ledger().timestamp();
+        let window_end = subscription.expiry_time
+            .saturating_add(config.grace_seconds)
+            .saturating_add(config.reactivation_window_seconds);
 
-        // Charge the difference
-        if price_diff > 0 {
-            let token_client = token::Client::new(&env, &config.payment_token);
-            token_client.transfer(&user, &env.current_contract_address(), &price_diff);
+        if current_time > window_end {
+            panic!("Reactivation window has passed");
         }
 
-        subscription.tier = new_tier;
+        if let Some(pending_tier) = subscription.pending_tier.take() {
+            subscription.tier = pending_tier;
+        }
+
+        let price = Self::get_cycle_price(&subscription.tier, &subscription.billing_cycle, &config);
+        let token_client = token::Client::new(&env, &config.payment_token);
+        token_client.transfer(&user, &env.current_contract_address(), &price);
+        Self::record_revenue(&env, &subscription.tier, price);
+
+        subscription.expiry_time = current_time + Self::cycle_seconds(&subscription.billing_cycle, &config);
+        subscription.is_active = true;
+        subscription.is_trial = false;
         env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish((symbol_short!("reactive"), user), subscription.expiry_time);
     }
 
-    // ───────────── BENEFITS TRACKING ─────────────
+    // ───────────── SUBSCRIPTION MANAGEMENT ─────────────
 
-    /// Track benefit usage
-    pub fn use_benefit(env: Env, user: Address) {
+    /// Cancel subscription (stops auto-renewal, keeps benefits until expiry)
+    pub fn cancel_subscription(env: Env, user: Address) {
         user.require_auth();
 
         let mut subscription: Subscription = env.storage().persistent()
             .get(&DataKey::Subscription(user.clone()))
             .expect("No subscription found");
 
-        if !Self::is_subscription_valid(&env, &subscription) {
-            panic!("Subscription not valid");
-        }
-
-        subscription.benefits_used += 1;
+        subscription.auto_renew = false;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish((symbol_short!("cancel"), user), 0i128);
+    }
+
+    /// Cancel immediately, refunding the unused part of the current cycle
+    pub fn refund_and_cancel(env: Env, user: Address) -> i128 {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if !subscription.is_active {
+            panic!("Subscription not active");
+        }
+
+        if subscription.is_frozen {
+            panic!("Subscription is frozen");
+        }
+
+        if subscription.is_gifted {
+            panic!("Gifted subscriptions cannot be refunded");
+        }
+
+        if subscription.is_trial {
+            panic!("Trial subscriptions cannot be refunded");
+        }
+
+        if subscription.expiry_time == LIFETIME_EXPIRY {
+            panic!("Lifetime subscriptions cannot be refunded");
+        }
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let price = Self::get_cycle_price(&subscription.tier, &subscription.billing_cycle, &config);
+        let remaining = subscription.expiry_time.saturating_sub(env.ledger().timestamp());
+        let refund = price * remaining as i128 / Self::cycle_seconds(&subscription.billing_cycle, &config) as i128;
+
+        if refund > 0 {
+            let token_client = token::Client::new(&env, &config.payment_token);
+            token_client.transfer(&env.current_contract_address(), &user, &refund);
+            Self::record_revenue(&env, &subscription.tier, -refund);
+        }
+
+        subscription.is_active = false;
+        subscription.auto_renew = false;
+        subscription.pending_tier = OptionalTier::None;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish((symbol_short!("cancel"), user), refund);
+
+        refund
+    }
+
+    /// Freeze a subscription, suspending its clock until unfrozen
+    pub fn freeze_subscription(env: Env, user: Address) {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if subscription.is_frozen {
+            panic!("Subscription is frozen");
+        }
+
+        if subscription.expiry_time == LIFETIME_EXPIRY || subscription.is_trial {
+            panic!("Subscription cannot be frozen");
+        }
+
+        let current_time = env.ledger().timestamp();
+        if !subscription.is_active || current_time >= subscription.expiry_time {
+            panic!("Subscription not active");
+        }
+
+        subscription.is_frozen = true;
+        subscription.frozen_at = current_time;
+        subscription.frozen_remaining = subscription.expiry_time - current_time;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+    }
+
+    /// Unfreeze a subscription, pushing its expiry out by the time spent frozen
+    pub fn unfreeze_subscription(env: Env, user: Address) {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if !subscription.is_frozen {
+            panic!("Subscription not frozen");
+        }
+
+        let frozen_duration = env.ledger().timestamp() - subscription.frozen_at;
+        subscription.expiry_time += frozen_duration;
+        subscription.is_frozen = false;
+        subscription.frozen_at = 0;
+        subscription.frozen_remaining = 0;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+    }
+
+    /// Toggle auto-renewal
+    pub fn set_auto_renew(env: Env, user: Address, auto_renew: bool) {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        subscription.auto_renew = auto_renew;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+    }
+
+    /// Upgrade subscription tier, charging the price difference prorated
+    /// over the time left in the current cycle
+    pub fn upgrade_subscription(env: Env, user: Address, new_tier: SubscriptionTier) {
+        user.require_auth();
+        Self::assert_not_paused(&env);
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if !subscription.is_active {
+            panic!("Subscription not active");
+        }
+
+        if subscription.is_frozen {
+            panic!("Subscription is frozen");
+        }
+
+        let old_tier_value = subscription.tier as u32;
+        let new_tier_value = new_tier as u32;
+
+        if new_tier_value <= old_tier_value {
+            panic!("Can only upgrade to higher tier");
+        }
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+
+        // Lifetime upgrades pay the lifetime price difference, there is no cycle to prorate
+        let charge = if subscription.expiry_time == LIFETIME_EXPIRY {
+            let price_diff = Self::get_lifetime_price(&new_tier, &config)
+                - Self::get_lifetime_price(&subscription.tier, &config);
+            price_diff.max(0)
+        } else {
+            let old_price = Self::get_cycle_price(&subscription.tier, &subscription.billing_cycle, &config);
+            let new_price = Self::get_cycle_price(&new_tier, &subscription.billing_cycle, &config);
+            let price_diff = new_price - old_price;
+
+            // Only the unused part of the cycle is billed at the new rate
+            let remaining = subscription.expiry_time.saturating_sub(env.ledger().timestamp());
+            if price_diff > 0 {
+                price_diff * remaining as i128 / Self::cycle_seconds(&subscription.billing_cycle, &config) as i128
+            } else {
+                0
+            }
+        };
+
+        if charge > 0 {
+            let token_client = token::Client::new(&env, &config.payment_token);
+            token_client.transfer(&user, &env.current_contract_address(), &charge);
+            Self::record_revenue(&env, &new_tier, charge);
+        }
+
+        subscription.tier = new_tier;
+        subscription.pending_tier = OptionalTier::None;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+        env.events().publish((symbol_short!("upgrade"), user), (new_tier, charge));
+    }
+
+    /// Schedule a downgrade to a lower tier, applied when the current cycle ends
+    pub fn schedule_downgrade(env: Env, user: Address, new_tier: SubscriptionTier) {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if !subscription.is_active {
+            panic!("Subscription not active");
+        }
+
+        if subscription.expiry_time == LIFETIME_EXPIRY {
+            panic!("Lifetime subscriptions cannot be downgraded");
+        }
+
+        if (new_tier as u32) >= (subscription.tier as u32) {
+            panic!("Can only downgrade to lower tier");
+        }
+
+        subscription.pending_tier = OptionalTier::Some(new_tier);
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+    }
+
+    /// Cancel a previously scheduled downgrade
+    pub fn cancel_scheduled_downgrade(env: Env, user: Address) {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if subscription.pending_tier.is_none() {
+            panic!("No downgrade scheduled");
+        }
+
+        subscription.pending_tier = OptionalTier::None;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+    }
+
+    /// Move an active subscription to another address (e.g. a new wallet)
+    pub fn transfer_subscription(env: Env, from: Address, to: Address) {
+        from.require_auth();
+        Self::assert_not_paused(&env);
+
+        if from == to {
+            panic!("Cannot transfer to self");
+        }
+
+        let subscription: Option<Subscription> = env.storage().persistent()
+            .get(&DataKey::Subscription(from.clone()));
+
+        // Members don't own their group subscription, so there is nothing to move
+        let mut subscription = match subscription {
+            Some(sub) => sub,
+            None if env.storage().persistent().has(&DataKey::UserGroup(from.clone())) => {
+                panic!("Group membership cannot be transferred")
+            }
+            None => panic!("No subscription found"),
+        };
+
+        if !Self::is_subscription_valid(&env, &subscription) {
+            panic!("Subscription not active");
+        }
+
+        if Self::has_active_subscription(env.clone(), to.clone()) {
+            panic!("Recipient already has active subscription");
+        }
+
+        Self::assert_not_frozen(&env, &to);
+
+        subscription.transferred_from = Some(from.clone());
+        env.storage().persistent().remove(&DataKey::Subscription(from));
+        env.storage().persistent().set(&DataKey::Subscription(to), &subscription);
+    }
+
+    // ───────────── BENEFITS TRACKING ─────────────
+
+    /// Track benefit usage
+    pub fn use_benefit(env: Env, user: Address) {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if !Self::is_subscription_valid(&env, &subscription) {
+            panic!("Subscription not valid");
+        }
+
+        subscription.benefits_used += 1;
+        env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
+    }
+
+    /// Track usage of a specific benefit type (e.g. hints, retries, skips)
+    pub fn use_benefit_typed(env: Env, user: Address, benefit_type: Symbol) {
+        user.require_auth();
+
+        let mut subscription: Subscription = env.storage().persistent()
+            .get(&DataKey::Subscription(user.clone()))
+            .expect("No subscription found");
+
+        if !Self::is_subscription_valid(&env, &subscription) {
+            panic!("Subscription not valid");
+        }
+
+        let tier = Self::effective_tier(&env, &subscription);
+        let limit = Self::get_benefit_type_limit(env.clone(), tier, benefit_type.clone());
+        let used = subscription.benefit_usage.get(benefit_type.clone()).unwrap_or(0);
+
+        if used >= limit {
+            panic!("Benefit limit reached");
+        }
+
+        subscription.benefit_usage.set(benefit_type, used + 1);
+        // Aggregate counter kept for existing integrations
+        subscription.benefits_used += 1;
         env.storage().persistent().set(&DataKey::Subscription(user.clone()), &subscription);
     }
 
+    /// Get the limit of a benefit type for a tier, falling back to the tier-wide limit
+    pub fn get_benefit_type_limit(env: Env, tier: SubscriptionTier, benefit_type: Symbol) -> u32 {
+        env.storage().persistent()
+            .get(&DataKey::BenefitLimit(tier, benefit_type))
+            .unwrap_or(Self::get_benefit_limit(tier))
+    }
+
+    /// Get how many times a user has used a benefit type
+    pub fn get_benefit_usage(env: Env, user: Address, benefit_type: Symbol) -> u32 {
+        Self::get_subscription(env, user)
+            .and_then(|sub| sub.benefit_usage.get(benefit_type))
+            .unwrap_or(0)
+    }
+
     /// Get benefit limits based on tier
     pub fn get_benefit_limit(tier: SubscriptionTier) -> u32 {
         match tier {
@@ -377,6 +1107,7 @@ impl SubscriptionContract {
         // Transfer payment
         let token_client = token::Client::new(&env, &config.payment_token);
         token_client.transfer(&owner, &env.current_contract_address(), &total_price);
+        Self::record_revenue(&env, &tier, total_price);
 
         // Get next group ID
         let group_id: u64 = env.storage().persistent().get(&DataKey::NextGroupId).unwrap_or(1);
@@ -388,7 +1119,7 @@ impl SubscriptionContract {
             owner: owner.clone(),
             tier,
             start_time: current_time,
-            expiry_time: current_time + MONTH_IN_SECONDS,
+            expiry_time: current_time + config.period_seconds,
             auto_renew,
             is_active: true,
             max_members,
@@ -406,16 +1137,16 @@ impl SubscriptionContract {
         group_id
     }
 
-    /// Add member to group subscription
-    pub fn add_group_member(env: Env, owner: Address, group_id: u64, member: Address) {
-        owner.require_auth();
+    /// Add member to group subscription (owner or manager)
+    pub fn add_group_member(env: Env, caller: Address, group_id: u64, member: Address) {
+        caller.require_auth();
 
         let group_sub: GroupSubscription = env.storage().persistent()
             .get(&DataKey::GroupSubscription(group_id))
             .expect("Group not found");
 
-        if group_sub.owner != owner {
-            panic!("Not group owner");
+        if group_sub.owner != caller && !Self::is_group_manager(env.clone(), group_id, caller) {
+            panic!("Not group owner or manager");
         }
 
         if !group_sub.is_active {
@@ -439,11 +1170,12 @@ impl SubscriptionContract {
         env.storage().persistent().set(&DataKey::UserGroup(member.clone()), &group_id);
     }
 
-    /// Remove member from group subscription
-    pub fn remove_group_member(env: Env, owner: Address, group_id: u64, member: Address) {
+    /// Raise a group's seat count, charging the extra seats for the rest of the cycle
+    pub fn expand_group(env: Env, owner: Address, group_id: u64, new_max: u32) {
         owner.require_auth();
+        Self::assert_not_paused(&env);
 
-        let group_sub: GroupSubscription = env.storage().persistent()
+        let mut group_sub: GroupSubscription = env.storage().persistent()
             .get(&DataKey::GroupSubscription(group_id))
             .expect("Group not found");
 
@@ -451,75 +1183,390 @@ impl SubscriptionContract {
             panic!("Not group owner");
         }
 
-        if group_sub.owner == member {
-            panic!("Cannot remove owner");
+        if !Self::is_group_subscription_valid(&env, &group_sub) {
+            panic!("Group subscription not active");
         }
 
-        let members: Vec<Address> = env.storage().persistent()
-            .get(&DataKey::GroupMembers(group_id))
-            .unwrap_or(Vec::new(&env));
-
-        let mut new_members = Vec::new(&env);
-        let mut found = false;
-
-        for m in members.iter() {
-            if m != member {
-                new_members.push_back(m);
-            } else {
-                found = true;
-            }
+        if new_max <= group_sub.max_members || new_max > 10 {
+            panic!("New max must be above current max and at most 10");
         }
 
-        if !found {
-            panic!("Member not found");
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let base_price = Self::get_tier_price(&group_sub.tier, &config);
+        let extra_seats = (new_max - group_sub.max_members) as i128;
+        // Same 20% group discount as at creation, prorated over the remaining time
+        let remaining = group_sub.expiry_time.saturating_sub(env.ledger().timestamp());
+        let charge = (base_price * extra_seats * 80) / 100 * remaining as i128 / config.period_seconds as i128;
+
+        if charge > 0 {
+            let token_client = token::Client::new(&env, &config.payment_token);
+            token_client.transfer(&owner, &env.current_contract_address(), &charge);
+            Self::record_revenue(&env, &group_sub.tier, charge);
         }
 
-        env.storage().persistent().set(&DataKey::GroupMembers(group_id), &new_members);
-        env.storage().persistent().remove(&DataKey::UserGroup(member));
+        group_sub.max_members = new_max;
+        env.storage().persistent().set(&DataKey::GroupSubscription(group_id), &group_sub);
     }
 
-    // ───────────── SUBSCRIPTION GIFTING ─────────────
-
-    /// Gift a subscription to another user
-    pub fn gift_subscription(
-        env: Env,
-        gifter: Address,
-        recipient: Address,
-        tier: SubscriptionTier,
-    ) {
-        gifter.require_auth();
+    /// Upgrade a group's tier, charging the per-seat difference for the rest of the cycle
+    pub fn upgrade_group(env: Env, owner: Address, group_id: u64, new_tier: SubscriptionTier) {
+        owner.require_auth();
         Self::assert_not_paused(&env);
 
-        if Self::has_active_subscription(env.clone(), recipient.clone()) {
-            panic!("Recipient already has active subscription");
+        let mut group_sub: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(group_id))
+            .expect("Group not found");
+
+        if group_sub.owner != owner {
+            panic!("Not group owner");
         }
 
-        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
-        let price = Self::get_tier_price(&tier, &config);
+        if !Self::is_group_subscription_valid(&env, &group_sub) {
+            panic!("Group subscription not active");
+        }
 
-        // Transfer payment from gifter
-        let token_client = token::Client::new(&env, &config.payment_token);
-        token_client.transfer(&gifter, &env.current_contract_address(), &price);
+        if (new_tier as u32) <= (group_sub.tier as u32) {
+            panic!("Can only upgrade to higher tier");
+        }
 
-        // Create subscription for recipient
-        let current_time = env.ledger().timestamp();
-        let subscription = Subscription {
-            tier,
-            start_time: current_time,
-            expiry_time: current_time + MONTH_IN_SECONDS,
-            auto_renew: false,
-            is_active: true,
-            total_renewals: 0,
-            benefits_used: 0,
-            is_gifted: true,
-            gifted_by: Some(gifter),
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let price_diff = Self::get_tier_price(&new_tier, &config) - Self::get_tier_price(&group_sub.tier, &config);
+        let remaining = group_sub.expiry_time.saturating_sub(env.ledger().timestamp());
+        let charge = if price_diff > 0 {
+            (price_diff * group_sub.max_members as i128 * 80) / 100 * remaining as i128 / config.period_seconds as i128
+        } else {
+            0
         };
 
-        env.storage().persistent().set(&DataKey::Subscription(recipient.clone()), &subscription);
+        if charge > 0 {
+            let token_client = token::Client::new(&env, &config.payment_token);
+            token_client.transfer(&owner, &env.current_contract_address(), &charge);
+            Self::record_revenue(&env, &new_tier, charge);
+        }
 
-        // Update total subscribers
-        let total: u64 = env.storage().persistent().get(&DataKey::TotalSubscribers).unwrap_or(0);
-        env.storage().persistent().set(&DataKey::TotalSubscribers, &(total + 1));
+        group_sub.tier = new_tier;
+        env.storage().persistent().set(&DataKey::GroupSubscription(group_id), &group_sub);
+    }
+
+    /// Remove member from group subscription (owner, or manager for regular members)
+    pub fn remove_group_member(env: Env, caller: Address, group_id: u64, member: Address) {
+        caller.require_auth();
+
+        let group_sub: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(group_id))
+            .expect("Group not found");
+
+        if group_sub.owner != caller {
+            if !Self::is_group_manager(env.clone(), group_id, caller) {
+                panic!("Not group owner or manager");
+            }
+            if Self::is_group_manager(env.clone(), group_id, member.clone()) {
+                panic!("Managers cannot remove other managers");
+            }
+        }
+
+        if group_sub.owner == member {
+            panic!("Cannot remove owner");
+        }
+
+        Self::remove_member(&env, group_id, &member);
+    }
+
+    /// Move a member between two groups, authorized by both owners
+    pub fn transfer_member(
+        env: Env,
+        from_owner: Address,
+        from_group: u64,
+        to_owner: Address,
+        to_group: u64,
+        member: Address,
+    ) {
+        from_owner.require_auth();
+        to_owner.require_auth();
+
+        if from_group == to_group {
+            panic!("Source and destination are the same group");
+        }
+
+        let source: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(from_group))
+            .expect("Group not found");
+        let destination: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(to_group))
+            .expect("Group not found");
+
+        if source.owner != from_owner || destination.owner != to_owner {
+            panic!("Not group owner");
+        }
+
+        if source.owner == member {
+            panic!("Cannot remove owner");
+        }
+
+        if !Self::is_group_subscription_valid(&env, &destination) {
+            panic!("Group subscription not active");
+        }
+
+        let mut members: Vec<Address> = env.storage().persistent()
+            .get(&DataKey::GroupMembers(to_group))
+            .unwrap_or(Vec::new(&env));
+
+        if members.len() >= destination.max_members {
+            panic!("Group is full");
+        }
+
+        if members.contains(&member) {
+            panic!("Already a member");
+        }
+
+        Self::remove_member(&env, from_group, &member);
+
+        members.push_back(member.clone());
+        env.storage().persistent().set(&DataKey::GroupMembers(to_group), &members);
+        env.storage().persistent().set(&DataKey::UserGroup(member), &to_group);
+    }
+
+    /// Grant or revoke the manager role for a group member (owner only)
+    pub fn set_group_manager(
+        env: Env,
+        owner: Address,
+        group_id: u64,
+        member: Address,
+        is_manager: bool,
+    ) {
+        owner.require_auth();
+
+        let group_sub: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(group_id))
+            .expect("Group not found");
+
+        if group_sub.owner != owner {
+            panic!("Not group owner");
+        }
+
+        if member == owner {
+            panic!("Owner cannot be a manager");
+        }
+
+        let members: Vec<Address> = env.storage().persistent()
+            .get(&DataKey::GroupMembers(group_id))
+            .unwrap_or(Vec::new(&env));
+
+        if !members.contains(&member) {
+            panic!("Member not found");
+        }
+
+        let mut managers: Map<Address, bool> = env.storage().persistent()
+            .get(&DataKey::GroupManagers(group_id))
+            .unwrap_or(Map::new(&env));
+
+        if is_manager {
+            managers.set(member, true);
+        } else {
+            managers.remove(member);
+        }
+        env.storage().persistent().set(&DataKey::GroupManagers(group_id), &managers);
+    }
+
+    /// Leave a group subscription as a member
+    pub fn leave_group(env: Env, member: Address, group_id: u64) {
+        member.require_auth();
+
+        let group_sub: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(group_id))
+            .expect("Group not found");
+
+        if group_sub.owner == member {
+            panic!("Owner cannot leave group, dissolve it instead");
+        }
+
+        Self::remove_member(&env, group_id, &member);
+    }
+
+    /// Dissolve a group subscription, removing every member
+    pub fn dissolve_group(env: Env, owner: Address, group_id: u64) {
+        owner.require_auth();
+
+        let mut group_sub: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(group_id))
+            .expect("Group not found");
+
+        if group_sub.owner != owner {
+            panic!("Not group owner");
+        }
+
+        Self::close_group(&env, group_id, &mut group_sub);
+    }
+
+    /// Deactivate a group past its grace period and release its members (callable by anyone)
+    pub fn expire_group(env: Env, group_id: u64) {
+        let mut group_sub: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(group_id))
+            .expect("Group not found");
+
+        if Self::is_group_subscription_valid(&env, &group_sub) {
+            panic!("Group still active");
+        }
+
+        Self::close_group(&env, group_id, &mut group_sub);
+    }
+
+    // ───────────── SUBSCRIPTION GIFTING ─────────────
+
+    /// Gift a subscription to another user
+    pub fn gift_subscription(
+        env: Env,
+        gifter: Address,
+        recipient: Address,
+        tier: SubscriptionTier,
+    ) {
+        gifter.require_auth();
+        Self::assert_not_paused(&env);
+
+        if Self::has_active_subscription(env.clone(), recipient.clone()) {
+            panic!("Recipient already has active subscription");
+        }
+
+        Self::assert_not_frozen(&env, &recipient);
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let price = Self::get_tier_price(&tier, &config);
+
+        // Transfer payment from gifter
+        let token_client = token::Client::new(&env, &config.payment_token);
+        token_client.transfer(&gifter, &env.current_contract_address(), &price);
+        Self::record_revenue(&env, &tier, price);
+
+        Self::create_gift(&env, &config, &gifter, &recipient, tier);
+    }
+
+    /// Gift the same tier to many recipients, skipping those already subscribed
+    /// or holding a frozen subscription.
+    /// Only the recipients that receive a gift are charged for; they are returned.
+    pub fn gift_subscriptions(
+        env: Env,
+        gifter: Address,
+        recipients: Vec<Address>,
+        tier: SubscriptionTier,
+    ) -> Vec<Address> {
+        gifter.require_auth();
+        Self::assert_not_paused(&env);
+
+        if recipients.is_empty() || recipients.len() > MAX_GIFT_BATCH {
+            panic!("Batch must contain between 1 and 20 recipients");
+        }
+
+        let mut gifted = Vec::new(&env);
+        for recipient in recipients.iter() {
+            if !gifted.contains(&recipient)
+                && !Self::has_active_subscription(env.clone(), recipient.clone())
+                && !Self::is_frozen(&env, &recipient)
+            {
+                gifted.push_back(recipient);
+            }
+        }
+
+        if gifted.is_empty() {
+            return gifted;
+        }
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let total_price = Self::get_tier_price(&tier, &config) * gifted.len() as i128;
+
+        let token_client = token::Client::new(&env, &config.payment_token);
+        token_client.transfer(&gifter, &env.current_contract_address(), &total_price);
+        Self::record_revenue(&env, &tier, total_price);
+
+        for recipient in gifted.iter() {
+            Self::create_gift(&env, &config, &gifter, &recipient, tier);
+        }
+
+        gifted
+    }
+
+    /// Pay for a gift now and let the recipient claim it from `deliver_at`
+    pub fn schedule_gift(
+        env: Env,
+        gifter: Address,
+        recipient: Address,
+        tier: SubscriptionTier,
+        deliver_at: u64,
+    ) -> u64 {
+        gifter.require_auth();
+        Self::assert_not_paused(&env);
+
+        if deliver_at <= env.ledger().timestamp() {
+            panic!("Delivery time must be in the future");
+        }
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let price = Self::get_tier_price(&tier, &config);
+
+        // Held in escrow until the gift is claimed or cancelled
+        let token_client = token::Client::new(&env, &config.payment_token);
+        token_client.transfer(&gifter, &env.current_contract_address(), &price);
+
+        let gift_id: u64 = env.storage().persistent().get(&DataKey::NextGiftId).unwrap_or(1);
+        env.storage().persistent().set(&DataKey::NextGiftId, &(gift_id + 1));
+
+        let gift = PendingGift {
+            gifter,
+            recipient,
+            tier,
+            amount: price,
+            deliver_at,
+        };
+        env.storage().persistent().set(&DataKey::PendingGift(gift_id), &gift);
+
+        gift_id
+    }
+
+    /// Claim a scheduled gift once its delivery time has passed
+    pub fn claim_gift(env: Env, recipient: Address, gift_id: u64) {
+        recipient.require_auth();
+        Self::assert_not_paused(&env);
+
+        let gift: PendingGift = env.storage().persistent()
+            .get(&DataKey::PendingGift(gift_id))
+            .expect("Gift not found");
+
+        if gift.recipient != recipient {
+            panic!("Not gift recipient");
+        }
+
+        if env.ledger().timestamp() < gift.deliver_at {
+            panic!("Gift not yet deliverable");
+        }
+
+        if Self::has_active_subscription(env.clone(), recipient.clone()) {
+            panic!("Recipient already has active subscription");
+        }
+
+        Self::assert_not_frozen(&env, &recipient);
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        env.storage().persistent().remove(&DataKey::PendingGift(gift_id));
+        Self::record_revenue(&env, &gift.tier, gift.amount);
+        Self::create_gift(&env, &config, &gift.gifter, &recipient, gift.tier);
+    }
+
+    /// Cancel an undelivered gift and refund the gifter
+    pub fn cancel_gift(env: Env, gifter: Address, gift_id: u64) {
+        gifter.require_auth();
+
+        let gift: PendingGift = env.storage().persistent()
+            .get(&DataKey::PendingGift(gift_id))
+            .expect("Gift not found");
+
+        if gift.gifter != gifter {
+            panic!("Not gift sender");
+        }
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        env.storage().persistent().remove(&DataKey::PendingGift(gift_id));
+
+        let token_client = token::Client::new(&env, &config.payment_token);
+        token_client.transfer(&env.current_contract_address(), &gifter, &gift.amount);
     }
 
     // ───────────── VIEW FUNCTIONS ─────────────
@@ -555,7 +1602,7 @@ impl SubscriptionContract {
         // Check individual subscription
         if let Some(sub) = Self::get_subscription(env.clone(), user.clone()) {
             if Self::is_subscription_valid(&env, &sub) {
-                return Some(sub.tier);
+                return Some(Self::effective_tier(&env, &sub));
             }
         }
 
@@ -581,11 +1628,78 @@ impl SubscriptionContract {
         env.storage().persistent().get(&DataKey::GroupMembers(group_id)).unwrap_or(Vec::new(&env))
     }
 
+    /// Get a group with every member's status in one call
+    pub fn get_group_details(env: Env, group_id: u64) -> GroupDetails {
+        let group_sub: GroupSubscription = env.storage().persistent()
+            .get(&DataKey::GroupSubscription(group_id))
+            .expect("Group not found");
+
+        let mut members = Vec::new(&env);
+        for member in Self::get_group_members(env.clone(), group_id).iter() {
+            let tier = Self::get_user_tier(env.clone(), member.clone());
+            members.push_back(GroupMemberStatus {
+                is_manager: Self::is_group_manager(env.clone(), group_id, member.clone()),
+                is_valid: tier.is_some(),
+                tier: tier.into(),
+                member,
+            });
+        }
+
+        GroupDetails {
+            group_id,
+            is_valid: Self::is_group_subscription_valid(&env, &group_sub),
+            owner: group_sub.owner,
+            tier: group_sub.tier,
+            start_time: group_sub.start_time,
+            expiry_time: group_sub.expiry_time,
+            auto_renew: group_sub.auto_renew,
+            is_active: group_sub.is_active,
+            max_members: group_sub.max_members,
+            total_renewals: group_sub.total_renewals,
+            members,
+        }
+    }
+
+    /// Check if an address manages a group
+    pub fn is_group_manager(env: Env, group_id: u64, member: Address) -> bool {
+        env.storage().persistent()
+            .get::<DataKey, Map<Address, bool>>(&DataKey::GroupManagers(group_id))
+            .map(|managers| managers.contains_key(member))
+            .unwrap_or(false)
+    }
+
     /// Get total active subscribers
     pub fn get_total_subscribers(env: Env) -> u64 {
         env.storage().persistent().get(&DataKey::TotalSubscribers).unwrap_or(0)
     }
 
+    /// Get net revenue collected (payments minus refunds)
+    pub fn get_revenue(env: Env) -> i128 {
+        env.storage().persistent().get(&DataKey::TotalRevenue).unwrap_or(0)
+    }
+
+    /// Get net revenue per tier as (basic, premium, enterprise)
+    pub fn get_revenue_by_tier(env: Env) -> (i128, i128, i128) {
+        let tier_revenue = |tier: SubscriptionTier| -> i128 {
+            env.storage().persistent().get(&DataKey::TierRevenue(tier)).unwrap_or(0)
+        };
+        (
+            tier_revenue(SubscriptionTier::Basic),
+            tier_revenue(SubscriptionTier::Premium),
+            tier_revenue(SubscriptionTier::Enterprise),
+        )
+    }
+
+    /// Get promo code details
+    pub fn get_promo(env: Env, code: Symbol) -> Option<Promo> {
+        env.storage().persistent().get(&DataKey::Promo(code))
+    }
+
+    /// Get a scheduled gift that has not been claimed or cancelled
+    pub fn get_pending_gift(env: Env, gift_id: u64) -> Option<PendingGift> {
+        env.storage().persistent().get(&DataKey::PendingGift(gift_id))
+    }
+
     /// Get configuration
     pub fn get_config(env: Env) -> Config {
         env.storage().persistent().get(&DataKey::Config).unwrap()
@@ -594,10 +1708,34 @@ impl SubscriptionContract {
     /// Check if subscription is in grace period
     pub fn is_in_grace_period(env: Env, user: Address) -> bool {
         if let Some(sub) = Self::get_subscription(env.clone(), user) {
+            let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
             let current_time = env.ledger().timestamp();
             current_time > sub.expiry_time 
-                && current_time <= sub.expiry_time + GRACE_PERIOD_SECONDS
+                && current_time <= sub.expiry_time.saturating_add(config.grace_seconds)
                 && sub.is_active
+                && !sub.is_trial
+                && !sub.is_frozen
+        } else {
+            false
+        }
+    }
+
+    /// Emit `grace_entered` or `expired` for a lapsing subscription so
+    /// off-chain services can notify the user. Returns true if an event was emitted.
+    pub fn check_and_notify_expiry(env: Env, user: Address) -> bool {
+        let subscription = match Self::get_subscription(env.clone(), user.clone()) {
+            Some(sub) if !sub.is_frozen => sub,
+            _ => return false,
+        };
+
+        if Self::is_in_grace_period(env.clone(), user.clone()) {
+            let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+            let grace_ends = subscription.expiry_time.saturating_add(config.grace_seconds);
+            env.events().publish((Symbol::new(&env, "grace_entered"), user), grace_ends);
+            true
+        } else if !Self::is_subscription_valid(&env, &subscription) {
+            env.events().publish((symbol_short!("expired"), user), subscription.expiry_time);
+            true
         } else {
             false
         }
@@ -620,42 +1758,220 @@ impl SubscriptionContract {
     // ───────────── INTERNAL HELPERS ─────────────
 
     fn is_subscription_valid(env: &Env, subscription: &Subscription) -> bool {
-        if !subscription.is_active {
+        if !subscription.is_active || subscription.is_frozen {
             return false;
         }
 
         let current_time = env.ledger().timestamp();
+
+        // Trials lapse at expiry without a grace period
+        if subscription.is_trial {
+            return current_time <= subscription.expiry_time;
+        }
         
         // Valid if within expiry time or within grace period
-        current_time <= subscription.expiry_time + GRACE_PERIOD_SECONDS
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        current_time <= subscription.expiry_time.saturating_add(config.grace_seconds)
     }
 
-    fn is_group_subscription_valid(env: &Env, group_sub: &GroupSubscription) -> bool {
-        if !group_sub.is_active {
-            return false;
+    /// Tier currently in force, taking a scheduled downgrade into account
+    /// once the paid cycle has ended
+    fn effective_tier(env: &Env, subscription: &Subscription) -> SubscriptionTier {
+        match subscription.pending_tier {
+            OptionalTier::Some(pending_tier) if env.ledger().timestamp() > subscription.expiry_time => {
+                pending_tier
+            }
+            _ => subscription.tier,
         }
-
-        let current_time = env.ledger().timestamp();
-        current_time <= group_sub.expiry_time + GRACE_PERIOD_SECONDS
     }
 
-    fn get_tier_price(tier: &SubscriptionTier, config: &Config) -> i128 {
-        match tier {
-            SubscriptionTier::Basic => config.basic_price,
-            SubscriptionTier::Premium => config.premium_price,
-            SubscriptionTier::Enterprise => config.enterprise_price,
-        }
-    }
+    /// Deactivate a group and clear every member's mapping to it
+    fn close_group(env: &Env, group_id: u64, group_sub: &mut GroupSubscription) {
+        let members: Vec<Address> = env.storage().persistent()
+            .get(&DataKey::GroupMembers(group_id))
+            .unwrap_or(Vec::new(env));
 
-    fn assert_admin(env: &Env, user: &Address) {
-        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
-        if config.admin != *user {
-            panic!("Admin only");
+        for member in members.iter() {
+            let key = DataKey::UserGroup(member);
+            if env.storage().persistent().get::<DataKey, u64>(&key) == Some(group_id) {
+                env.storage().persistent().remove(&key);
+            }
         }
+
+        group_sub.is_active = false;
+        group_sub.auto_renew = false;
+        env.storage().persistent().set(&DataKey::GroupSubscription(group_id), &*group_sub);
+        env.storage().persistent().set(&DataKey::GroupMembers(group_id), &Vec::<Address>::new(env));
+        env.storage().persistent().remove(&DataKey::GroupManagers(group_id));
     }
 
-    fn assert_not_paused(env: &Env) {
-        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+    fn remove_member(env: &Env, group_id: u64, member: &Address) {
+        let members: Vec<Address> = env.storage().persistent()
+            .get(&DataKey::GroupMembers(group_id))
+            .unwrap_or(Vec::new(env));
+
+        let mut new_members = Vec::new(env);
+        let mut found = false;
+
+        for m in members.iter() {
+            if m != *member {
+                new_members.push_back(m);
+            } else {
+                found = true;
+            }
+        }
+
+        if !found {
+            panic!("Member not found");
+        }
+
+        env.storage().persistent().set(&DataKey::GroupMembers(group_id), &new_members);
+        env.storage().persistent().remove(&DataKey::UserGroup(member.clone()));
+
+        let managers_key = DataKey::GroupManagers(group_id);
+        if let Some(mut managers) = env.storage().persistent().get::<DataKey, Map<Address, bool>>(&managers_key) {
+            if managers.contains_key(member.clone()) {
+                managers.remove(member.clone());
+                env.storage().persistent().set(&managers_key, &managers);
+            }
+        }
+    }
+
+    fn is_group_subscription_valid(env: &Env, group_sub: &GroupSubscription) -> bool {
+        if !group_sub.is_active {
+            return false;
+        }
+
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        let current_time = env.ledger().timestamp();
+        current_time <= group_sub.expiry_time.saturating_add(config.grace_seconds)
+    }
+
+    fn get_tier_price(tier: &SubscriptionTier, config: &Config) -> i128 {
+        match tier {
+            SubscriptionTier::Basic => config.basic_price,
+            SubscriptionTier::Premium => config.premium_price,
+            SubscriptionTier::Enterprise => config.enterprise_price,
+        }
+    }
+
+    fn get_lifetime_price(tier: &SubscriptionTier, config: &Config) -> i128 {
+        let price = match tier {
+            SubscriptionTier::Basic => config.basic_lifetime_price,
+            SubscriptionTier::Premium => config.premium_lifetime_price,
+            SubscriptionTier::Enterprise => config.enterprise_lifetime_price,
+        };
+        if price <= 0 {
+            panic!("Lifetime not available for tier");
+        }
+        price
+    }
+
+    /// Price charged for one billing cycle of a tier
+    fn get_cycle_price(tier: &SubscriptionTier, billing_cycle: &BillingCycle, config: &Config) -> i128 {
+        let price = Self::get_tier_price(tier, config);
+        match billing_cycle {
+            BillingCycle::Monthly => price,
+            BillingCycle::Annual => price * 12 * config.annual_discount_bps as i128 / 10_000,
+        }
+    }
+
+    fn cycle_seconds(billing_cycle: &BillingCycle, config: &Config) -> u64 {
+        match billing_cycle {
+            BillingCycle::Monthly => config.period_seconds,
+            BillingCycle::Annual => config.annual_period_seconds,
+        }
+    }
+
+    /// Consume one use of a promo code and return the discounted price
+    fn redeem_promo(env: &Env, code: Symbol, price: i128) -> i128 {
+        let key = DataKey::Promo(code);
+        let mut promo: Promo = env.storage().persistent()
+            .get(&key)
+            .expect("Invalid promo code");
+
+        if env.ledger().timestamp() > promo.expires_at {
+            panic!("Promo code expired");
+        }
+
+        if promo.remaining_uses == 0 {
+            panic!("Promo code exhausted");
+        }
+
+        promo.remaining_uses -= 1;
+        env.storage().persistent().set(&key, &promo);
+
+        price * (10_000 - promo.discount_bps) as i128 / 10_000
+    }
+
+    fn create_gift(
+        env: &Env,
+        config: &Config,
+        gifter: &Address,
+        recipient: &Address,
+        tier: SubscriptionTier,
+    ) {
+        let current_time = env.ledger().timestamp();
+        let subscription = Subscription {
+            tier,
+            start_time: current_time,
+            expiry_time: current_time + config.period_seconds,
+            auto_renew: false,
+            is_active: true,
+            total_renewals: 0,
+            benefits_used: 0,
+            is_gifted: true,
+            gifted_by: Some(gifter.clone()),
+            pending_tier: OptionalTier::None,
+            billing_cycle: BillingCycle::Monthly,
+            transferred_from: None,
+            benefit_usage: Map::new(env),
+            is_trial: false,
+            is_frozen: false,
+            frozen_at: 0,
+            frozen_remaining: 0,
+        };
+
+        env.storage().persistent().set(&DataKey::Subscription(recipient.clone()), &subscription);
+        env.events().publish(
+            (symbol_short!("gift"), gifter.clone(), recipient.clone()),
+            (subscription.tier, subscription.expiry_time),
+        );
+
+        // Update total subscribers
+        let total: u64 = env.storage().persistent().get(&DataKey::TotalSubscribers).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::TotalSubscribers, &(total + 1));
+    }
+
+    fn record_revenue(env: &Env, tier: &SubscriptionTier, amount: i128) {
+        let total: i128 = env.storage().persistent().get(&DataKey::TotalRevenue).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::TotalRevenue, &(total + amount));
+
+        let key = DataKey::TierRevenue(*tier);
+        let tier_total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(tier_total + amount));
+    }
+
+    fn assert_admin(env: &Env, user: &Address) {
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
+        if config.admin != *user {
+            panic!("Admin only");
+        }
+    }
+
+    /// A new subscription would discard the time held by a frozen one
+    fn is_frozen(env: &Env, user: &Address) -> bool {
+        Self::get_subscription(env.clone(), user.clone()).is_some_and(|sub| sub.is_frozen)
+    }
+
+    fn assert_not_frozen(env: &Env, user: &Address) {
+        if Self::is_frozen(env, user) {
+            panic!("Subscription is frozen");
+        }
+    }
+
+    fn assert_not_paused(env: &Env) {
+        let config: Config = env.storage().persistent().get(&DataKey::Config).unwrap();
         if config.paused {
             panic!("Contract is paused");
         }
@@ -671,9 +1987,15 @@ impl SubscriptionContract {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::testutils::{Address as _, Events, Ledger};
+    use soroban_sdk::{vec, IntoVal, Val};
     use soroban_sdk::token::StellarAssetClient;
 
+    fn last_event_topics(env: &Env) -> Vec<Val> {
+        let (_, topics, _) = env.events().all().last().unwrap();
+        topics
+    }
+
     fn create_token_contract(env: &Env, admin: &Address) -> Address {
         env.register_stellar_asset_contract_v2(admin.clone()).address()
     }
@@ -697,6 +2019,9 @@ mod test {
             &1_000_000,  // Basic: 1 token
             &5_000_000,  // Premium: 5 tokens
             &20_000_000, // Enterprise: 20 tokens
+            &None,
+            &None,
+            &None,
         );
 
         let user = Address::generate(&env);
@@ -715,18 +2040,56 @@ mod test {
         let contract_id = env.register_contract(None, SubscriptionContract);
         let client = SubscriptionContractClient::new(&env, &contract_id);
 
-        client.initialize(&admin, &payment_token, &1_000_000, &5_000_000, &20_000_000);
+        client.initialize(&admin, &payment_token, &1_000_000, &5_000_000, &20_000_000, &None, &None, &None);
 
         let config = client.get_config();
         assert_eq!(config.admin, admin);
         assert_eq!(config.basic_price, 1_000_000);
+        assert_eq!(config.period_seconds, MONTH_IN_SECONDS);
+        assert_eq!(config.annual_period_seconds, YEAR_IN_SECONDS);
+        assert_eq!(config.grace_seconds, GRACE_PERIOD_SECONDS);
+    }
+
+    #[test]
+    fn test_initialize_with_custom_durations() {
+        let (env, admin, payment_token, user, token_admin_client, _client) = setup();
+        let contract_id = env.register_contract(None, SubscriptionContract);
+        let client = SubscriptionContractClient::new(&env, &contract_id);
+
+        client.initialize(&admin, &payment_token, &1_000_000, &5_000_000, &20_000_000, &Some(100), &Some(1_000), &Some(20));
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.expiry_time, sub.start_time + 100);
+
+        // Inside the custom grace period, past the default one
+        env.ledger().with_mut(|li| li.timestamp += 100 + GRACE_PERIOD_SECONDS + 1);
+        assert!(client.is_in_grace_period(&user));
+        assert!(client.has_active_subscription(&user));
+
+        env.ledger().with_mut(|li| li.timestamp += 20);
+        assert!(!client.has_active_subscription(&user));
+
+        // Proration uses the configured period too
+        let other = Address::generate(&env);
+        token_admin_client.mint(&other, &10_000_000);
+        client.purchase_subscription(&other, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        env.ledger().with_mut(|li| li.timestamp += 25);
+        assert_eq!(client.refund_and_cancel(&other), 750_000);
+
+        // Annual plans run for the configured annual period
+        let annual = Address::generate(&env);
+        token_admin_client.mint(&annual, &20_000_000);
+        client.purchase_subscription(&annual, &SubscriptionTier::Basic, &true, &BillingCycle::Annual, &None);
+        let sub = client.get_subscription(&annual).unwrap();
+        assert_eq!(sub.expiry_time, sub.start_time + 1_000);
     }
 
     #[test]
     fn test_purchase_basic_subscription() {
         let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
 
         assert!(client.has_active_subscription(&user));
         
@@ -737,11 +2100,54 @@ mod test {
         assert_eq!(sub.total_renewals, 0);
     }
 
+    #[test]
+    fn test_purchase_annual_subscription() {
+        let (env, admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        // Pay for 10 of 12 months
+        client.set_annual_discount(&admin, &8_333);
+        let balance_before = token_client.balance(&user);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Annual, &None);
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.billing_cycle, BillingCycle::Annual);
+        assert_eq!(sub.expiry_time, sub.start_time + YEAR_IN_SECONDS);
+        assert_eq!(balance_before - token_client.balance(&user), 9_999_600);
+
+        // Still valid long after a monthly cycle would have lapsed
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS * 6);
+        assert!(client.has_active_subscription(&user));
+    }
+
+    #[test]
+    fn test_annual_renewal_follows_cycle() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Annual, &None);
+
+        env.ledger().with_mut(|li| li.timestamp += YEAR_IN_SECONDS + 1);
+        let balance_before = token_client.balance(&user);
+        client.process_renewal(&user);
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.expiry_time, env.ledger().timestamp() + YEAR_IN_SECONDS);
+        assert_eq!(balance_before - token_client.balance(&user), 12_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid annual discount")]
+    fn test_invalid_annual_discount() {
+        let (_env, admin, _payment_token, _user, _token_admin_client, client) = setup();
+        client.set_annual_discount(&admin, &10_001);
+    }
+
     #[test]
     fn test_purchase_premium_subscription() {
         let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Premium, &false);
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &false, &BillingCycle::Monthly, &None);
 
         let sub = client.get_subscription(&user).unwrap();
         assert_eq!(sub.tier, SubscriptionTier::Premium);
@@ -753,8 +2159,8 @@ mod test {
     fn test_cannot_purchase_duplicate_subscription() {
         let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
-        client.purchase_subscription(&user, &SubscriptionTier::Premium, &false);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &false, &BillingCycle::Monthly, &None);
     }
 
     // ───────────── SUBSCRIPTION VALIDITY TESTS ─────────────
@@ -763,7 +2169,7 @@ mod test {
     fn test_subscription_validity() {
         let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
         assert!(client.has_active_subscription(&user));
 
         // Still valid before expiry
@@ -779,7 +2185,7 @@ mod test {
     fn test_grace_period() {
         let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
 
         // Advance past expiry but within grace period
         env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
@@ -802,7 +2208,7 @@ mod test {
     fn test_auto_renewal() {
         let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
         
         let sub_before = client.get_subscription(&user).unwrap();
         assert_eq!(sub_before.total_renewals, 0);
@@ -823,7 +2229,7 @@ mod test {
     fn test_cannot_renew_before_expiry() {
         let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
         
         env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS - 1);
         client.process_renewal(&user);
@@ -834,19 +2240,137 @@ mod test {
     fn test_cannot_auto_renew_when_disabled() {
         let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &false);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &false, &BillingCycle::Monthly, &None);
         
         env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
         client.process_renewal(&user);
     }
 
+    #[test]
+    fn test_keeper_renewal_with_allowance() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        token_client.approve(&user, &client.address, &1_000_000, &(env.ledger().sequence() + 1000));
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        let balance_before = token_client.balance(&user);
+
+        // Triggered by a keeper: no signature from the user is required
+        assert!(client.process_renewal_keeper(&user));
+        assert!(env.auths().iter().all(|(address, _)| *address != user));
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.total_renewals, 1);
+        assert!(sub.is_active);
+        assert_eq!(balance_before - token_client.balance(&user), 1_000_000);
+        assert_eq!(token_client.allowance(&user, &client.address), 0);
+    }
+
+    #[test]
+    fn test_keeper_renewal_without_allowance_deactivates() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + GRACE_PERIOD_SECONDS + 1);
+        assert!(!client.process_renewal_keeper(&user));
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert!(!sub.is_active);
+        assert!(!sub.auto_renew);
+        assert!(!client.has_active_subscription(&user));
+    }
+
+    #[test]
+    fn test_keeper_call_during_grace_does_not_deactivate() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.schedule_downgrade(&user, &SubscriptionTier::Basic);
+
+        // One second past expiry, with no allowance granted
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        let before = client.get_subscription(&user).unwrap();
+        assert!(!client.process_renewal_keeper(&user));
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.expiry_time, before.expiry_time);
+        assert_eq!(sub.tier, SubscriptionTier::Premium);
+        assert!(sub.is_active);
+        assert!(sub.auto_renew);
+        assert_eq!(sub.pending_tier, OptionalTier::Some(SubscriptionTier::Basic));
+        assert!(client.is_in_grace_period(&user));
+
+        // The user can still renew within grace, at the downgraded tier
+        let balance_before = token_client.balance(&user);
+        client.process_renewal(&user);
+        assert_eq!(token_client.balance(&user), balance_before - 1_000_000);
+        assert_eq!(client.get_subscription(&user).unwrap().tier, SubscriptionTier::Basic);
+    }
+
+    #[test]
+    fn test_reactivate_inside_window() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        client.process_renewal(&user);
+        client.use_benefit(&user);
+
+        // Lapse, then come back on the last second of the window
+        let expiry = client.get_subscription(&user).unwrap().expiry_time;
+        env.ledger().with_mut(|li| {
+            li.timestamp = expiry + GRACE_PERIOD_SECONDS + REACTIVATION_WINDOW_SECONDS
+        });
+        assert!(!client.has_active_subscription(&user));
+
+        let balance_before = token_client.balance(&user);
+        client.reactivate(&user);
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert!(sub.is_active);
+        assert_eq!(sub.total_renewals, 1);
+        assert_eq!(sub.benefits_used, 1);
+        assert_eq!(sub.expiry_time, env.ledger().timestamp() + MONTH_IN_SECONDS);
+        assert_eq!(balance_before - token_client.balance(&user), 5_000_000);
+        assert!(client.has_active_subscription(&user));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reactivation window has passed")]
+    fn test_reactivate_outside_window() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+
+        let expiry = client.get_subscription(&user).unwrap().expiry_time;
+        env.ledger().with_mut(|li| {
+            li.timestamp = expiry + GRACE_PERIOD_SECONDS + REACTIVATION_WINDOW_SECONDS + 1
+        });
+
+        client.reactivate(&user);
+    }
+
+    #[test]
+    #[should_panic(expected = "Subscription still active")]
+    fn test_cannot_reactivate_active_subscription() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.reactivate(&user);
+    }
+
     // ───────────── SUBSCRIPTION MANAGEMENT TESTS ─────────────
 
     #[test]
     fn test_cancel_subscription() {
         let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
         
         let sub = client.get_subscription(&user).unwrap();
         assert!(sub.auto_renew);
@@ -862,11 +2386,75 @@ mod test {
         assert!(client.has_active_subscription(&user));
     }
 
+    #[test]
+    fn test_refund_and_cancel_mid_cycle() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        let balance_before = token_client.balance(&user);
+
+        // 6 of 10 seconds unused
+        env.ledger().with_mut(|li| li.timestamp += 4);
+        let refund = client.refund_and_cancel(&user);
+
+        assert_eq!(refund, 600_000);
+        assert_eq!(token_client.balance(&user) - balance_before, 600_000);
+        assert!(!client.get_subscription(&user).unwrap().is_active);
+        assert!(!client.has_active_subscription(&user));
+    }
+
+    #[test]
+    #[should_panic(expected = "Gifted subscriptions cannot be refunded")]
+    fn test_cannot_refund_gifted_subscription() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+        let recipient = Address::generate(&env);
+
+        client.gift_subscription(&user, &recipient, &SubscriptionTier::Premium);
+        client.refund_and_cancel(&recipient);
+    }
+
+    #[test]
+    fn test_freeze_preserves_remaining_time() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+
+        env.ledger().with_mut(|li| li.timestamp += 4);
+        client.freeze_subscription(&user);
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert!(sub.is_frozen);
+        assert_eq!(sub.frozen_remaining, MONTH_IN_SECONDS - 4);
+        assert!(!client.has_active_subscription(&user));
+
+        // A long break doesn't consume paid time
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS * 5);
+        assert!(!client.check_and_notify_expiry(&user));
+        client.unfreeze_subscription(&user);
+
+        assert!(client.has_active_subscription(&user));
+        assert_eq!(client.get_time_until_expiry(&user), MONTH_IN_SECONDS - 4);
+        assert!(!client.get_subscription(&user).unwrap().is_frozen);
+    }
+
+    #[test]
+    #[should_panic(expected = "Subscription is frozen")]
+    fn test_frozen_subscription_is_not_renewed() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.freeze_subscription(&user);
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        client.process_renewal_keeper(&user);
+    }
+
     #[test]
     fn test_toggle_auto_renew() {
         let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &false);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &false, &BillingCycle::Monthly, &None);
         
         let sub = client.get_subscription(&user).unwrap();
         assert!(!sub.auto_renew);
@@ -881,7 +2469,7 @@ mod test {
     fn test_upgrade_subscription() {
         let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
         
         let sub = client.get_subscription(&user).unwrap();
         assert_eq!(sub.tier, SubscriptionTier::Basic);
@@ -893,52 +2481,235 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Can only upgrade to higher tier")]
-    fn test_cannot_downgrade_subscription() {
-        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+    fn test_upgrade_charge_is_prorated() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
 
-        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true);
-        client.upgrade_subscription(&user, &SubscriptionTier::Basic);
-    }
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        let balance_before = token_client.balance(&user);
 
-    // ───────────── BENEFITS TRACKING TESTS ─────────────
+        // Half the month left: half of the 4 token difference
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS / 2);
+        client.upgrade_subscription(&user, &SubscriptionTier::Premium);
+
+        assert_eq!(balance_before - token_client.balance(&user), 2_000_000);
+    }
 
     #[test]
-    fn test_benefit_usage() {
-        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+    fn test_upgrade_in_grace_period_is_free() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
-        
-        let sub = client.get_subscription(&user).unwrap();
-        assert_eq!(sub.benefits_used, 0);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        let balance_before = token_client.balance(&user);
 
-        client.use_benefit(&user);
-        client.use_benefit(&user);
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        client.upgrade_subscription(&user, &SubscriptionTier::Premium);
 
-        let sub = client.get_subscription(&user).unwrap();
-        assert_eq!(sub.benefits_used, 2);
+        assert_eq!(token_client.balance(&user), balance_before);
+        assert_eq!(client.get_subscription(&user).unwrap().tier, SubscriptionTier::Premium);
     }
 
     #[test]
-    fn test_benefit_limits() {
-        let basic_limit = SubscriptionContract::get_benefit_limit(SubscriptionTier::Basic);
-        let premium_limit = SubscriptionContract::get_benefit_limit(SubscriptionTier::Premium);
-        let enterprise_limit = SubscriptionContract::get_benefit_limit(SubscriptionTier::Enterprise);
+    fn test_scheduled_downgrade_applies_next_cycle() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.schedule_downgrade(&user, &SubscriptionTier::Basic);
+        assert_eq!(
+            client.get_subscription(&user).unwrap().pending_tier,
+            OptionalTier::Some(SubscriptionTier::Basic)
+        );
 
-        assert_eq!(basic_limit, 10);
-        assert_eq!(premium_limit, 50);
-        assert_eq!(enterprise_limit, 999_999);
-    }
+        // Paid-for premium time is kept until the cycle ends
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS - 1);
+        assert_eq!(client.get_user_tier(&user), Some(SubscriptionTier::Premium));
 
-    // ───────────── GROUP SUBSCRIPTION TESTS ─────────────
+        // In the grace period the lower tier is already reported
+        env.ledger().with_mut(|li| li.timestamp += 2);
+        assert_eq!(client.get_user_tier(&user), Some(SubscriptionTier::Basic));
 
-    #[test]
-    fn test_create_group_subscription() {
-        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+        let balance_before = token_client.balance(&user);
+        client.process_renewal(&user);
 
-        let group_id = client.create_group_subscription(
-            &user,
-            &SubscriptionTier::Premium,
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.tier, SubscriptionTier::Basic);
+        assert_eq!(sub.pending_tier, OptionalTier::None);
+        assert_eq!(balance_before - token_client.balance(&user), 1_000_000);
+    }
+
+    #[test]
+    fn test_cancel_scheduled_downgrade() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.schedule_downgrade(&user, &SubscriptionTier::Basic);
+        client.cancel_scheduled_downgrade(&user);
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        client.process_renewal(&user);
+
+        assert_eq!(client.get_subscription(&user).unwrap().tier, SubscriptionTier::Premium);
+    }
+
+    #[test]
+    fn test_upgrade_clears_scheduled_downgrade() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.schedule_downgrade(&user, &SubscriptionTier::Basic);
+        client.upgrade_subscription(&user, &SubscriptionTier::Enterprise);
+
+        assert_eq!(client.get_subscription(&user).unwrap().pending_tier, OptionalTier::None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can only downgrade to lower tier")]
+    fn test_cannot_schedule_downgrade_to_same_tier() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.schedule_downgrade(&user, &SubscriptionTier::Basic);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can only upgrade to higher tier")]
+    fn test_cannot_downgrade_subscription() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.upgrade_subscription(&user, &SubscriptionTier::Basic);
+    }
+
+    #[test]
+    fn test_transfer_subscription() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+        let new_wallet = Address::generate(&env);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        let original = client.get_subscription(&user).unwrap();
+
+        client.transfer_subscription(&user, &new_wallet);
+
+        assert!(client.get_subscription(&user).is_none());
+        assert!(!client.has_active_subscription(&user));
+
+        let moved = client.get_subscription(&new_wallet).unwrap();
+        assert_eq!(moved.tier, SubscriptionTier::Premium);
+        assert_eq!(moved.expiry_time, original.expiry_time);
+        assert_eq!(moved.transferred_from, Some(user));
+        assert_eq!(client.get_user_tier(&new_wallet), Some(SubscriptionTier::Premium));
+    }
+
+    #[test]
+    #[should_panic(expected = "Recipient already has active subscription")]
+    fn test_cannot_transfer_to_subscribed_user() {
+        let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
+        let other = Address::generate(&env);
+        token_admin_client.mint(&other, &10_000_000);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.purchase_subscription(&other, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+
+        client.transfer_subscription(&user, &other);
+    }
+
+    #[test]
+    #[should_panic(expected = "Subscription is frozen")]
+    fn test_cannot_transfer_to_frozen_user() {
+        let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
+        let other = Address::generate(&env);
+        token_admin_client.mint(&other, &10_000_000);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+        client.purchase_subscription(&other, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.freeze_subscription(&other);
+
+        client.transfer_subscription(&user, &other);
+    }
+
+    #[test]
+    #[should_panic(expected = "Group membership cannot be transferred")]
+    fn test_group_member_cannot_transfer() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+        let member = Address::generate(&env);
+        let new_wallet = Address::generate(&env);
+
+        let group_id = client.create_group_subscription(&user, &SubscriptionTier::Premium, &5, &true);
+        client.add_group_member(&user, &group_id, &member);
+
+        client.transfer_subscription(&member, &new_wallet);
+    }
+
+    // ───────────── BENEFITS TRACKING TESTS ─────────────
+
+    #[test]
+    fn test_benefit_usage() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.benefits_used, 0);
+
+        client.use_benefit(&user);
+        client.use_benefit(&user);
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.benefits_used, 2);
+    }
+
+    #[test]
+    fn test_benefit_limits() {
+        let basic_limit = SubscriptionContract::get_benefit_limit(SubscriptionTier::Basic);
+        let premium_limit = SubscriptionContract::get_benefit_limit(SubscriptionTier::Premium);
+        let enterprise_limit = SubscriptionContract::get_benefit_limit(SubscriptionTier::Enterprise);
+
+        assert_eq!(basic_limit, 10);
+        assert_eq!(premium_limit, 50);
+        assert_eq!(enterprise_limit, 999_999);
+    }
+
+    #[test]
+    fn test_typed_benefit_limits_are_per_category() {
+        let (env, admin, _payment_token, user, _token_admin_client, client) = setup();
+        let hint = Symbol::new(&env, "hint");
+        let retry = Symbol::new(&env, "retry");
+
+        client.set_benefit_limit(&admin, &SubscriptionTier::Basic, &hint, &2);
+        client.set_benefit_limit(&admin, &SubscriptionTier::Basic, &retry, &1);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+
+        client.use_benefit_typed(&user, &hint);
+        client.use_benefit_typed(&user, &hint);
+        assert!(client.try_use_benefit_typed(&user, &hint).is_err());
+
+        // Hints are exhausted but retries are tracked separately
+        client.use_benefit_typed(&user, &retry);
+
+        assert_eq!(client.get_benefit_usage(&user, &hint), 2);
+        assert_eq!(client.get_benefit_usage(&user, &retry), 1);
+        assert_eq!(client.get_subscription(&user).unwrap().benefits_used, 3);
+    }
+
+    #[test]
+    fn test_typed_benefit_limit_defaults_to_tier_limit() {
+        let (env, _admin, _payment_token, _user, _token_admin_client, client) = setup();
+        let skip = Symbol::new(&env, "skip");
+
+        assert_eq!(client.get_benefit_type_limit(&SubscriptionTier::Premium, &skip), 50);
+    }
+
+    // ───────────── GROUP SUBSCRIPTION TESTS ─────────────
+
+    #[test]
+    fn test_create_group_subscription() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &user,
+            &SubscriptionTier::Premium,
             &5,
             &true,
         );
@@ -1044,6 +2815,359 @@ mod test {
         client.remove_group_member(&owner, &group_id, &owner);
     }
 
+    #[test]
+    fn test_expand_group_mid_cycle() {
+        let (env, _admin, payment_token, owner, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &3,
+            &true,
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS / 2);
+        let balance_before = token_client.balance(&owner);
+
+        client.expand_group(&owner, &group_id, &5);
+
+        // 2 seats * 5 tokens * 80% for half a cycle
+        assert_eq!(balance_before - token_client.balance(&owner), 4_000_000);
+        assert_eq!(client.get_group_subscription(&group_id).unwrap().max_members, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "New max must be above current max and at most 10")]
+    fn test_cannot_expand_group_beyond_limit() {
+        let (_env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        client.expand_group(&owner, &group_id, &11);
+    }
+
+    #[test]
+    fn test_upgrade_group() {
+        let (env, _admin, payment_token, owner, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let member = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &member);
+        assert_eq!(client.get_user_tier(&member), Some(SubscriptionTier::Premium));
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS / 2);
+        let balance_before = token_client.balance(&owner);
+
+        client.upgrade_group(&owner, &group_id, &SubscriptionTier::Enterprise);
+
+        // 15 token difference * 5 seats * 80% for half a cycle
+        assert_eq!(balance_before - token_client.balance(&owner), 30_000_000);
+        assert_eq!(client.get_user_tier(&member), Some(SubscriptionTier::Enterprise));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can only upgrade to higher tier")]
+    fn test_cannot_downgrade_group() {
+        let (_env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        client.upgrade_group(&owner, &group_id, &SubscriptionTier::Basic);
+    }
+
+    #[test]
+    fn test_manager_can_add_and_remove_members() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let manager = Address::generate(&env);
+        let member = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &manager);
+        client.set_group_manager(&owner, &group_id, &manager, &true);
+        assert!(client.is_group_manager(&group_id, &manager));
+
+        client.add_group_member(&manager, &group_id, &member);
+        assert_eq!(client.get_user_tier(&member), Some(SubscriptionTier::Premium));
+
+        client.remove_group_member(&manager, &group_id, &member);
+        assert!(!client.has_active_subscription(&member));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not group owner or manager")]
+    fn test_non_manager_cannot_add_member() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let member = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &member);
+
+        client.add_group_member(&member, &group_id, &Address::generate(&env));
+    }
+
+    #[test]
+    #[should_panic(expected = "Managers cannot remove other managers")]
+    fn test_manager_cannot_remove_manager() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let manager1 = Address::generate(&env);
+        let manager2 = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &manager1);
+        client.add_group_member(&owner, &group_id, &manager2);
+        client.set_group_manager(&owner, &group_id, &manager1, &true);
+        client.set_group_manager(&owner, &group_id, &manager2, &true);
+
+        client.remove_group_member(&manager1, &group_id, &manager2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot remove owner")]
+    fn test_manager_cannot_remove_owner() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let manager = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &manager);
+        client.set_group_manager(&owner, &group_id, &manager, &true);
+
+        client.remove_group_member(&manager, &group_id, &owner);
+    }
+
+    #[test]
+    fn test_get_group_details() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let manager = Address::generate(&env);
+        let member = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &manager);
+        client.add_group_member(&owner, &group_id, &member);
+        client.set_group_manager(&owner, &group_id, &manager, &true);
+
+        let details = client.get_group_details(&group_id);
+        assert_eq!(details.group_id, group_id);
+        assert_eq!(details.owner, owner);
+        assert_eq!(details.tier, SubscriptionTier::Premium);
+        assert_eq!(details.max_members, 5);
+        assert!(details.is_valid);
+        assert_eq!(details.members.len(), 3);
+
+        let manager_status = details.members.get(1).unwrap();
+        assert_eq!(manager_status.member, manager);
+        assert!(manager_status.is_manager);
+        assert!(manager_status.is_valid);
+        assert_eq!(manager_status.tier, OptionalTier::Some(SubscriptionTier::Premium));
+        assert!(!details.members.get(2).unwrap().is_manager);
+        assert_eq!(client.get_group_details(&group_id), details);
+
+        // Once the group lapses every member reports invalid
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + GRACE_PERIOD_SECONDS + 1);
+        let details = client.get_group_details(&group_id);
+        assert!(!details.is_valid);
+        for status in details.members.iter() {
+            assert!(!status.is_valid);
+            assert_eq!(status.tier, OptionalTier::None);
+        }
+    }
+
+    #[test]
+    fn test_transfer_member_between_groups() {
+        let (env, _admin, _payment_token, owner1, token_admin_client, client) = setup();
+        let owner2 = Address::generate(&env);
+        token_admin_client.mint(&owner2, &100_000_000);
+
+        let group1 = client.create_group_subscription(&owner1, &SubscriptionTier::Premium, &5, &true);
+        let group2 = client.create_group_subscription(&owner2, &SubscriptionTier::Enterprise, &5, &true);
+
+        let member = Address::generate(&env);
+        client.add_group_member(&owner1, &group1, &member);
+        assert_eq!(client.get_user_tier(&member), Some(SubscriptionTier::Premium));
+
+        client.transfer_member(&owner1, &group1, &owner2, &group2, &member);
+
+        assert!(!client.get_group_members(&group1).contains(&member));
+        assert!(client.get_group_members(&group2).contains(&member));
+        assert_eq!(client.get_user_tier(&member), Some(SubscriptionTier::Enterprise));
+    }
+
+    #[test]
+    #[should_panic(expected = "Group is full")]
+    fn test_cannot_transfer_member_into_full_group() {
+        let (env, _admin, _payment_token, owner1, token_admin_client, client) = setup();
+        let owner2 = Address::generate(&env);
+        token_admin_client.mint(&owner2, &100_000_000);
+
+        let group1 = client.create_group_subscription(&owner1, &SubscriptionTier::Premium, &5, &true);
+        let group2 = client.create_group_subscription(&owner2, &SubscriptionTier::Premium, &2, &true);
+        client.add_group_member(&owner2, &group2, &Address::generate(&env));
+
+        let member = Address::generate(&env);
+        client.add_group_member(&owner1, &group1, &member);
+
+        client.transfer_member(&owner1, &group1, &owner2, &group2, &member);
+    }
+
+    #[test]
+    fn test_expire_group_releases_members() {
+        let (env, _admin, _payment_token, owner, token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let member = Address::generate(&env);
+        token_admin_client.mint(&member, &10_000_000);
+        client.add_group_member(&owner, &group_id, &member);
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + GRACE_PERIOD_SECONDS + 1);
+        client.expire_group(&group_id);
+
+        assert!(!client.get_group_subscription(&group_id).unwrap().is_active);
+        assert_eq!(client.get_group_members(&group_id).len(), 0);
+
+        // The former member is free to subscribe on their own
+        client.purchase_subscription(&member, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        assert_eq!(client.get_user_tier(&member), Some(SubscriptionTier::Basic));
+
+        // Expiring the old group again leaves a new membership untouched
+        let new_group = client.create_group_subscription(&owner, &SubscriptionTier::Premium, &3, &true);
+        client.add_group_member(&owner, &new_group, &member);
+        client.expire_group(&group_id);
+        assert_eq!(client.get_group_details(&new_group).members.len(), 2);
+        assert!(client.get_group_details(&new_group).members.get(1).unwrap().is_valid);
+    }
+
+    #[test]
+    #[should_panic(expected = "Group still active")]
+    fn test_cannot_expire_group_in_grace() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        client.expire_group(&group_id);
+    }
+
+    #[test]
+    fn test_leave_group() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let member = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &member);
+        assert!(client.has_active_subscription(&member));
+
+        client.leave_group(&member, &group_id);
+
+        assert_eq!(client.get_group_members(&group_id).len(), 1);
+        assert!(!client.has_active_subscription(&member));
+        assert!(client.has_active_subscription(&owner));
+    }
+
+    #[test]
+    #[should_panic(expected = "Owner cannot leave group, dissolve it instead")]
+    fn test_owner_cannot_leave_group() {
+        let (_env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        client.leave_group(&owner, &group_id);
+    }
+
+    #[test]
+    fn test_dissolve_group() {
+        let (env, _admin, _payment_token, owner, _token_admin_client, client) = setup();
+
+        let group_id = client.create_group_subscription(
+            &owner,
+            &SubscriptionTier::Premium,
+            &5,
+            &true,
+        );
+
+        let member1 = Address::generate(&env);
+        let member2 = Address::generate(&env);
+        client.add_group_member(&owner, &group_id, &member1);
+        client.add_group_member(&owner, &group_id, &member2);
+
+        client.dissolve_group(&owner, &group_id);
+
+        let group = client.get_group_subscription(&group_id).unwrap();
+        assert!(!group.is_active);
+        assert_eq!(client.get_group_members(&group_id).len(), 0);
+        assert!(!client.has_active_subscription(&owner));
+        assert!(!client.has_active_subscription(&member1));
+        assert!(!client.has_active_subscription(&member2));
+    }
+
     // ───────────── GIFTING TESTS ─────────────
 
     #[test]
@@ -1064,6 +3188,146 @@ mod test {
         assert!(!sub.auto_renew); // Gifts don't auto-renew
     }
 
+    #[test]
+    fn test_bulk_gift_skips_existing_subscribers() {
+        let (env, _admin, payment_token, gifter, token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        let recipient1 = Address::generate(&env);
+        let subscribed = Address::generate(&env);
+        let recipient2 = Address::generate(&env);
+        token_admin_client.mint(&subscribed, &10_000_000);
+        client.purchase_subscription(&subscribed, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+
+        let balance_before = token_client.balance(&gifter);
+        let gifted = client.gift_subscriptions(
+            &gifter,
+            &vec![&env, recipient1.clone(), subscribed.clone(), recipient2.clone()],
+            &SubscriptionTier::Premium,
+        );
+
+        assert_eq!(gifted, vec![&env, recipient1.clone(), recipient2.clone()]);
+        assert_eq!(balance_before - token_client.balance(&gifter), 10_000_000);
+        assert!(client.get_subscription(&recipient1).unwrap().is_gifted);
+        assert!(client.get_subscription(&recipient2).unwrap().is_gifted);
+        assert_eq!(client.get_subscription(&subscribed).unwrap().tier, SubscriptionTier::Basic);
+    }
+
+    #[test]
+    #[should_panic(expected = "Subscription is frozen")]
+    fn test_gift_to_frozen_subscription_rejected() {
+        let (env, _admin, _payment_token, gifter, token_admin_client, client) = setup();
+
+        let recipient = Address::generate(&env);
+        token_admin_client.mint(&recipient, &10_000_000);
+        client.purchase_subscription(&recipient, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.freeze_subscription(&recipient);
+
+        client.gift_subscription(&gifter, &recipient, &SubscriptionTier::Premium);
+    }
+
+    #[test]
+    fn test_bulk_gift_skips_frozen_subscribers() {
+        let (env, _admin, payment_token, gifter, token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        let recipient = Address::generate(&env);
+        let frozen = Address::generate(&env);
+        token_admin_client.mint(&frozen, &10_000_000);
+        client.purchase_subscription(&frozen, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.freeze_subscription(&frozen);
+
+        let balance_before = token_client.balance(&gifter);
+        let gifted = client.gift_subscriptions(
+            &gifter,
+            &vec![&env, recipient.clone(), frozen.clone()],
+            &SubscriptionTier::Premium,
+        );
+
+        assert_eq!(gifted, vec![&env, recipient.clone()]);
+        assert_eq!(balance_before - token_client.balance(&gifter), 5_000_000);
+        let sub = client.get_subscription(&frozen).unwrap();
+        assert!(sub.is_frozen);
+        assert_eq!(sub.tier, SubscriptionTier::Basic);
+    }
+
+    #[test]
+    #[should_panic(expected = "Subscription is frozen")]
+    fn test_trial_rejected_while_frozen() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.freeze_subscription(&user);
+
+        client.start_trial(&user, &SubscriptionTier::Premium);
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch must contain between 1 and 20 recipients")]
+    fn test_bulk_gift_batch_cap() {
+        let (env, _admin, _payment_token, gifter, _token_admin_client, client) = setup();
+
+        let mut recipients = Vec::new(&env);
+        for _ in 0..=MAX_GIFT_BATCH {
+            recipients.push_back(Address::generate(&env));
+        }
+
+        client.gift_subscriptions(&gifter, &recipients, &SubscriptionTier::Basic);
+    }
+
+    #[test]
+    fn test_scheduled_gift_delivery() {
+        let (env, _admin, payment_token, gifter, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+        let recipient = Address::generate(&env);
+
+        let balance_before = token_client.balance(&gifter);
+        let gift_id = client.schedule_gift(&gifter, &recipient, &SubscriptionTier::Premium, &50);
+
+        assert_eq!(balance_before - token_client.balance(&gifter), 5_000_000);
+        assert_eq!(client.get_pending_gift(&gift_id).unwrap().deliver_at, 50);
+        assert!(!client.has_active_subscription(&recipient));
+
+        env.ledger().with_mut(|li| li.timestamp = 50);
+        client.claim_gift(&recipient, &gift_id);
+
+        let sub = client.get_subscription(&recipient).unwrap();
+        assert!(sub.is_gifted);
+        assert_eq!(sub.gifted_by, Some(gifter));
+        assert_eq!(sub.expiry_time, 50 + MONTH_IN_SECONDS);
+        assert!(client.get_pending_gift(&gift_id).is_none());
+        assert_eq!(client.get_revenue(), 5_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Gift not yet deliverable")]
+    fn test_cannot_claim_gift_early() {
+        let (env, _admin, _payment_token, gifter, _token_admin_client, client) = setup();
+        let recipient = Address::generate(&env);
+
+        let gift_id = client.schedule_gift(&gifter, &recipient, &SubscriptionTier::Premium, &50);
+
+        env.ledger().with_mut(|li| li.timestamp = 49);
+        client.claim_gift(&recipient, &gift_id);
+    }
+
+    #[test]
+    fn test_cancel_scheduled_gift_refunds() {
+        let (env, _admin, payment_token, gifter, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+        let recipient = Address::generate(&env);
+
+        let balance_before = token_client.balance(&gifter);
+        let gift_id = client.schedule_gift(&gifter, &recipient, &SubscriptionTier::Premium, &50);
+        client.cancel_gift(&gifter, &gift_id);
+
+        assert_eq!(token_client.balance(&gifter), balance_before);
+        assert!(client.get_pending_gift(&gift_id).is_none());
+
+        env.ledger().with_mut(|li| li.timestamp = 50);
+        assert!(client.try_claim_gift(&recipient, &gift_id).is_err());
+    }
+
     #[test]
     #[should_panic(expected = "Recipient already has active subscription")]
     fn test_cannot_gift_to_existing_subscriber() {
@@ -1072,10 +3336,239 @@ mod test {
         let recipient = Address::generate(&env);
         token_admin_client.mint(&recipient, &100_000_000);
         
-        client.purchase_subscription(&recipient, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&recipient, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
         client.gift_subscription(&gifter, &recipient, &SubscriptionTier::Premium);
     }
 
+    // ───────────── LIFETIME TESTS ─────────────
+
+    #[test]
+    fn test_lifetime_never_expires() {
+        let (env, admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.update_lifetime_pricing(&admin, &10_000_000, &40_000_000, &80_000_000);
+        let balance_before = token_client.balance(&user);
+        client.purchase_lifetime(&user, &SubscriptionTier::Premium);
+
+        assert_eq!(balance_before - token_client.balance(&user), 40_000_000);
+        let sub = client.get_subscription(&user).unwrap();
+        assert_eq!(sub.expiry_time, u64::MAX);
+        assert!(!sub.auto_renew);
+
+        env.ledger().with_mut(|li| li.timestamp += YEAR_IN_SECONDS * 100);
+        assert!(client.has_active_subscription(&user));
+        assert!(!client.is_in_grace_period(&user));
+        assert_eq!(client.get_user_tier(&user), Some(SubscriptionTier::Premium));
+    }
+
+    #[test]
+    #[should_panic(expected = "Lifetime subscriptions cannot be refunded")]
+    fn test_lifetime_cannot_be_refunded() {
+        let (_env, admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.update_lifetime_pricing(&admin, &10_000_000, &40_000_000, &80_000_000);
+        client.purchase_lifetime(&user, &SubscriptionTier::Basic);
+        client.refund_and_cancel(&user);
+    }
+
+    #[test]
+    fn test_lifetime_upgrade_charges_lifetime_difference() {
+        let (env, admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.update_lifetime_pricing(&admin, &10_000_000, &40_000_000, &80_000_000);
+        client.purchase_lifetime(&user, &SubscriptionTier::Basic);
+        let balance_before = token_client.balance(&user);
+
+        client.upgrade_subscription(&user, &SubscriptionTier::Premium);
+
+        assert_eq!(balance_before - token_client.balance(&user), 30_000_000);
+        assert_eq!(client.get_subscription(&user).unwrap().expiry_time, u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lifetime not available for tier")]
+    fn test_lifetime_requires_configured_price() {
+        let (_env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+        client.purchase_lifetime(&user, &SubscriptionTier::Basic);
+    }
+
+    // ───────────── TRIAL TESTS ─────────────
+
+    #[test]
+    fn test_trial_is_free_and_lapses() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+        let balance_before = token_client.balance(&user);
+
+        client.start_trial(&user, &SubscriptionTier::Premium);
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert!(sub.is_trial);
+        assert_eq!(sub.expiry_time, sub.start_time + TRIAL_PERIOD_SECONDS);
+        assert_eq!(token_client.balance(&user), balance_before);
+        assert_eq!(client.get_user_tier(&user), Some(SubscriptionTier::Premium));
+
+        // No allowance: the keeper cannot convert it and the trial lapses
+        env.ledger().with_mut(|li| li.timestamp += TRIAL_PERIOD_SECONDS + 1);
+        assert!(!client.has_active_subscription(&user));
+        env.ledger().with_mut(|li| li.timestamp += GRACE_PERIOD_SECONDS);
+        assert!(!client.process_renewal_keeper(&user));
+        assert!(!client.get_subscription(&user).unwrap().is_active);
+    }
+
+    #[test]
+    #[should_panic(expected = "Trial already used")]
+    fn test_cannot_start_second_trial() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.start_trial(&user, &SubscriptionTier::Premium);
+        env.ledger().with_mut(|li| li.timestamp += TRIAL_PERIOD_SECONDS + 1);
+
+        // A different tier doesn't grant another trial
+        client.start_trial(&user, &SubscriptionTier::Basic);
+    }
+
+    #[test]
+    fn test_trial_converts_with_allowance() {
+        let (env, _admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+
+        client.start_trial(&user, &SubscriptionTier::Basic);
+        token_client.approve(&user, &client.address, &1_000_000, &(env.ledger().sequence() + 1000));
+
+        env.ledger().with_mut(|li| li.timestamp += TRIAL_PERIOD_SECONDS + 1);
+        assert!(client.process_renewal_keeper(&user));
+
+        let sub = client.get_subscription(&user).unwrap();
+        assert!(!sub.is_trial);
+        assert_eq!(sub.expiry_time, env.ledger().timestamp() + MONTH_IN_SECONDS);
+        assert!(client.has_active_subscription(&user));
+    }
+
+    // ───────────── PROMO CODE TESTS ─────────────
+
+    #[test]
+    fn test_promo_discount_applied() {
+        let (env, admin, payment_token, user, _token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+        let code = Symbol::new(&env, "LAUNCH25");
+
+        client.create_promo(&admin, &code, &2_500, &2, &100);
+        let balance_before = token_client.balance(&user);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &Some(code.clone()));
+
+        assert_eq!(balance_before - token_client.balance(&user), 3_750_000);
+        assert_eq!(client.get_promo(&code).unwrap().remaining_uses, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Promo code expired")]
+    fn test_expired_promo_rejected() {
+        let (env, admin, _payment_token, user, _token_admin_client, client) = setup();
+        let code = Symbol::new(&env, "SPRING");
+
+        client.create_promo(&admin, &code, &1_000, &5, &100);
+        env.ledger().with_mut(|li| li.timestamp += 101);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &Some(code));
+    }
+
+    #[test]
+    #[should_panic(expected = "Promo code exhausted")]
+    fn test_exhausted_promo_rejected() {
+        let (env, admin, _payment_token, user, token_admin_client, client) = setup();
+        let code = Symbol::new(&env, "ONCE");
+        let other = Address::generate(&env);
+        token_admin_client.mint(&other, &10_000_000);
+
+        client.create_promo(&admin, &code, &1_000, &1, &100);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &Some(code.clone()));
+        assert_eq!(client.get_promo(&code).unwrap().remaining_uses, 0);
+
+        client.purchase_subscription(&other, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &Some(code));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid promo code")]
+    fn test_unknown_promo_rejected() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &Some(Symbol::new(&env, "NOPE")));
+    }
+
+    // ───────────── EVENT TESTS ─────────────
+
+    #[test]
+    fn test_lifecycle_events() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+        let recipient = Address::generate(&env);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        assert_eq!(last_event_topics(&env), vec![&env, symbol_short!("purchase").into_val(&env), user.into_val(&env)]);
+
+        client.upgrade_subscription(&user, &SubscriptionTier::Premium);
+        assert_eq!(last_event_topics(&env), vec![&env, symbol_short!("upgrade").into_val(&env), user.into_val(&env)]);
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        client.process_renewal(&user);
+        assert_eq!(last_event_topics(&env), vec![&env, symbol_short!("renewed").into_val(&env), user.into_val(&env)]);
+
+        client.cancel_subscription(&user);
+        assert_eq!(last_event_topics(&env), vec![&env, symbol_short!("cancel").into_val(&env), user.into_val(&env)]);
+
+        client.gift_subscription(&user, &recipient, &SubscriptionTier::Basic);
+        assert_eq!(
+            last_event_topics(&env),
+            vec![&env, symbol_short!("gift").into_val(&env), user.into_val(&env), recipient.into_val(&env)]
+        );
+    }
+
+    #[test]
+    fn test_check_and_notify_expiry() {
+        let (env, _admin, _payment_token, user, _token_admin_client, client) = setup();
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &false, &BillingCycle::Monthly, &None);
+        assert!(!client.check_and_notify_expiry(&user));
+
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS + 1);
+        assert!(client.check_and_notify_expiry(&user));
+        assert_eq!(
+            last_event_topics(&env),
+            vec![&env, Symbol::new(&env, "grace_entered").into_val(&env), user.into_val(&env)]
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += GRACE_PERIOD_SECONDS);
+        assert!(client.check_and_notify_expiry(&user));
+        assert_eq!(last_event_topics(&env), vec![&env, symbol_short!("expired").into_val(&env), user.into_val(&env)]);
+    }
+
+    // ───────────── REVENUE TESTS ─────────────
+
+    #[test]
+    fn test_revenue_accounting() {
+        let (env, _admin, payment_token, user, token_admin_client, client) = setup();
+        let token_client = token::Client::new(&env, &payment_token);
+        let recipient = Address::generate(&env);
+        let refunder = Address::generate(&env);
+        token_admin_client.mint(&refunder, &10_000_000);
+
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
+        client.gift_subscription(&user, &recipient, &SubscriptionTier::Premium);
+        client.purchase_subscription(&refunder, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
+
+        // Half a cycle in: upgrade charges 9.5, refund returns 2.5
+        env.ledger().with_mut(|li| li.timestamp += MONTH_IN_SECONDS / 2);
+        client.upgrade_subscription(&user, &SubscriptionTier::Enterprise);
+        client.refund_and_cancel(&refunder);
+
+        assert_eq!(client.get_revenue(), 18_000_000);
+        assert_eq!(client.get_revenue_by_tier(), (1_000_000, 7_500_000, 9_500_000));
+        assert_eq!(token_client.balance(&client.address), client.get_revenue());
+    }
+
     // ───────────── ADMIN TESTS ─────────────
 
     #[test]
@@ -1106,7 +3599,7 @@ mod test {
         let (_env, admin, _payment_token, user, _token_admin_client, client) = setup();
 
         client.set_paused(&admin, &true);
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
     }
 
     // ───────────── VIEW FUNCTION TESTS ─────────────
@@ -1115,7 +3608,7 @@ mod test {
     fn test_get_time_until_expiry() {
         let (env, _admin, _payment_token, user, token_admin_client, client) = setup();
 
-        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
 
         let time_left = client.get_time_until_expiry(&user);
         assert_eq!(time_left, MONTH_IN_SECONDS);
@@ -1132,12 +3625,12 @@ mod test {
 
         assert_eq!(client.get_total_subscribers(), 0);
 
-        client.purchase_subscription(&user1, &SubscriptionTier::Basic, &true);
+        client.purchase_subscription(&user1, &SubscriptionTier::Basic, &true, &BillingCycle::Monthly, &None);
         assert_eq!(client.get_total_subscribers(), 1);
 
         let user2 = Address::generate(&env);
         token_admin_client.mint(&user2, &100_000_000);
-        client.purchase_subscription(&user2, &SubscriptionTier::Premium, &true);
+        client.purchase_subscription(&user2, &SubscriptionTier::Premium, &true, &BillingCycle::Monthly, &None);
         assert_eq!(client.get_total_subscribers(), 2);
     }
 
@@ -1147,7 +3640,7 @@ mod test {
 
         assert!(client.get_user_tier(&user).is_none());
 
-        client.purchase_subscription(&user, &SubscriptionTier::Enterprise, &true);
+        client.purchase_subscription(&user, &SubscriptionTier::Enterprise, &true, &BillingCycle::Monthly, &None);
 
         let tier = client.get_user_tier(&user).unwrap();
         assert_eq!(tier, SubscriptionTier::Enterprise);